@@ -0,0 +1,111 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use criterion::{black_box, Criterion};
+
+use advent::common::get_data_path;
+use advent::registry;
+use advent::solution::Solution;
+use advent::{
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13,
+    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+};
+
+const SUMMARY_PATH: &str = "bench_output.txt";
+
+fn group_name(day: usize) -> String {
+    format!("day{:02}", day)
+}
+
+fn bench_day<S: Solution>(c: &mut Criterion, day: usize, input_path: &str) {
+    let input = fs::read_to_string(get_data_path(input_path).unwrap()).unwrap();
+    let parsed = S::parse(&input).unwrap();
+    let config = S::Config::default();
+
+    let mut group = c.benchmark_group(group_name(day));
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(&input))));
+    if registry::find(day, 1).is_some_and(|e| !e.slow) {
+        group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed), &config).unwrap()));
+    }
+    if registry::find(day, 2).is_some_and(|e| !e.slow) {
+        group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed), &config).unwrap()));
+    }
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    bench_day::<day1::Day1>(c, 1, day1::constants::LOCATIONS_FILE_PATH);
+    bench_day::<day2::Day2>(c, 2, day2::constants::REPORT_FILE_PATH);
+    bench_day::<day3::Day3>(c, 3, day3::constants::MEMORY_FILE_PATH);
+    bench_day::<day4::Day4>(c, 4, day4::constants::WORD_SEARCH_PATH);
+    bench_day::<day5::Day5>(c, 5, day5::constants::PRINTER_UPDATES);
+    bench_day::<day6::Day6>(c, 6, day6::constants::INPUT_PATH);
+    bench_day::<day7::Day7>(c, 7, day7::constants::INPUT_PATH);
+    bench_day::<day8::Day8>(c, 8, day8::constants::INPUT_PATH);
+    bench_day::<day9::Day9>(c, 9, day9::constants::INPUT_PATH);
+    bench_day::<day10::Day10>(c, 10, day10::constants::INPUT_PATH);
+    bench_day::<day11::Day11>(c, 11, day11::constants::INPUT_PATH);
+    bench_day::<day12::Day12>(c, 12, day12::constants::INPUT_PATH);
+    bench_day::<day13::Day13>(c, 13, day13::constants::INPUT_PATH);
+    bench_day::<day14::Day14>(c, 14, day14::constants::INPUT_PATH);
+    bench_day::<day15::Day15>(c, 15, day15::constants::INPUT_PATH);
+    bench_day::<day16::Day16>(c, 16, day16::constants::INPUT_PATH);
+    bench_day::<day17::Day17>(c, 17, day17::constants::INPUT_PATH);
+    bench_day::<day18::Day18>(c, 18, day18::constants::INPUT_PATH);
+    bench_day::<day19::Day19>(c, 19, day19::constants::INPUT_PATH);
+    bench_day::<day20::Day20>(c, 20, day20::constants::INPUT_PATH);
+    bench_day::<day21::Day21>(c, 21, day21::constants::INPUT_PATH);
+    bench_day::<day22::Day22>(c, 22, day22::constants::INPUT_PATH);
+    bench_day::<day23::Day23>(c, 23, day23::constants::INPUT_PATH);
+    bench_day::<day24::Day24>(c, 24, day24::constants::INPUT_PATH);
+    bench_day::<day25::Day25>(c, 25, day25::constants::INPUT_PATH);
+}
+
+fn criterion_dir() -> PathBuf {
+    let target = env::var_os("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"));
+    target.join("criterion")
+}
+
+// mean time of the latest run, as recorded by criterion
+fn mean_estimate(day: usize, bench: &str) -> Option<Duration> {
+    let path = criterion_dir().join(group_name(day)).join(bench).join("new/estimates.json");
+    let estimates: serde_json::Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let nanos = estimates["mean"]["point_estimate"].as_f64()?;
+    Some(Duration::from_nanos(nanos as u64))
+}
+
+// one row per day from whatever criterion has on disk, so a filtered run only refreshes its own rows
+fn write_summary() {
+    let mut table = format!("{:>3}  {:>12}  {:>12}  {:>12}\n", "day", "parse", "part1", "part2");
+    for day in 1..=25 {
+        let cols: Vec<String> = ["parse", "part1", "part2"].iter()
+            .map(|bench| match mean_estimate(day, bench) {
+                Some(mean) => format!("{:.3?}", mean),
+                None => String::from("-"),
+            })
+            .collect();
+        table.push_str(&format!("{:>3}  {:>12}  {:>12}  {:>12}\n", day, cols[0], cols[1], cols[2]));
+    }
+    print!("\n{}", table);
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(SUMMARY_PATH);
+    if let Err(e) = fs::write(&path, table) {
+        eprintln!("could not write {}: {}", path.display(), e);
+    }
+}
+
+fn main() {
+    // some parts take tens of seconds per iteration, criterion's default of 100 samples is too many
+    let mut c = Criterion::default().sample_size(10).configure_from_args();
+    benches(&mut c);
+    c.final_summary();
+
+    // `cargo test --benches` runs each bench once, nothing worth summarising
+    if !env::args().any(|a| a == "--test" || a == "--list") {
+        write_summary();
+    }
+}
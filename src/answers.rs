@@ -0,0 +1,113 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use toml::{Table, Value};
+
+use crate::common::get_data_path;
+use crate::solution::Answer;
+
+pub mod constants {
+    // relative to the data dir, see common::get_data_path
+    pub const ANSWERS_PATH: &str = "answers.toml";
+}
+
+// known-good answers for the real inputs, laid out as
+//
+//     [day1]
+//     part1 = 3246517
+//     part2 = "..."
+//
+// anything that isn't an integer, or doesn't fit in one, goes in as a string
+pub struct Answers {
+    table: Table,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL (expected {})", expected),
+            Verdict::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn from(input: &str) -> Result<Self, Box<dyn Error>> {
+        let table = input.parse::<Table>()?;
+        Ok(Self { table })
+    }
+
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let path = get_data_path(constants::ANSWERS_PATH)?;
+        Self::from(&fs::read_to_string(path)?)
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<String> {
+        let day = self.table.get(&format!("day{}", day))?;
+        match day.get(format!("part{}", part))? {
+            Value::Integer(i) => Some(i.to_string()),
+            Value::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn check(&self, day: usize, part: usize, answer: &Answer) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail { expected },
+            None => Verdict::Missing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "
+[day1]
+part1 = 11
+part2 = 31
+
+[day17]
+part1 = \"4,6,3,5,6,3,5,2,1,0\"
+";
+
+    #[test]
+    fn checks_int_and_string_answers() {
+        let answers = Answers::from(ANSWERS).unwrap();
+        assert_eq!(answers.check(1, 1, &Answer::from(11usize)), Verdict::Pass);
+        assert_eq!(answers.check(17, 1, &Answer::from("4,6,3,5,6,3,5,2,1,0")), Verdict::Pass);
+        assert_eq!(
+            answers.check(1, 2, &Answer::from(30usize)),
+            Verdict::Fail { expected: String::from("31") },
+        );
+    }
+
+    #[test]
+    fn unknown_parts_are_missing() {
+        let answers = Answers::from(ANSWERS).unwrap();
+        assert_eq!(answers.check(17, 2, &Answer::from(0usize)), Verdict::Missing);
+        assert_eq!(answers.check(2, 1, &Answer::from(0usize)), Verdict::Missing);
+    }
+
+    #[test]
+    fn bad_toml() {
+        assert!(Answers::from("[day1\npart1 = 1").is_err());
+    }
+
+    #[test]
+    fn real_answers_load() {
+        let answers = Answers::load().unwrap();
+        assert_eq!(answers.get(1, 1), Some(String::from("3246517")));
+    }
+}
@@ -1,14 +1,10 @@
-use std::error::Error;
-use advent::day1::result_from_file;
+use advent::day1::{result_from_file, ColumnReader};
 use advent::day1::constants::LOCATIONS_FILE_PATH;
-use advent::common::get_data_path;
+use advent::common::{get_data_path, report};
 
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let path = get_data_path(LOCATIONS_FILE_PATH).unwrap();
-    match result_from_file(&path) {
-        Ok(result) => println!("{}", result),
-        Err(e) => panic!("{:?}", e),
-    }
-    Ok(())
+fn main() {
+    let result = get_data_path(LOCATIONS_FILE_PATH)
+        .and_then(|path| result_from_file(&path, &ColumnReader::default()));
+    report(result);
 }
@@ -1,14 +1,10 @@
-use std::error::Error;
-use advent::day1::compute_similarity_score;
+use advent::day1::{compute_similarity_score, ColumnReader};
 use advent::day1::constants::LOCATIONS_FILE_PATH;
-use advent::common::get_data_path;
+use advent::common::{get_data_path, report};
 
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let path = get_data_path(LOCATIONS_FILE_PATH).unwrap();
-    match compute_similarity_score(&path) {
-        Ok(result) => println!("{}", result),
-        Err(e) => panic!("{:?}", e),
-    }
-    Ok(())
+fn main() {
+    let result = get_data_path(LOCATIONS_FILE_PATH)
+        .and_then(|path| compute_similarity_score(&path, &ColumnReader::default()));
+    report(result);
 }
@@ -1,10 +1,9 @@
-use std::error::Error;
 use advent::common;
 use advent::day12;
 use advent::day12::constants::INPUT_PATH;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let path = common::get_data_path(INPUT_PATH).unwrap();
-    println!("{}", day12::solution1(&path));
-    Ok(())
+fn main() {
+    let result = common::get_data_path(INPUT_PATH)
+        .and_then(|path| day12::solution1(&path));
+    common::report(result);
 }
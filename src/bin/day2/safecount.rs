@@ -1,13 +1,9 @@
-use std::error::Error;
-use advent::common::get_data_path;
+use advent::common::{get_data_path, report};
 use advent::day2::constants::REPORT_FILE_PATH;
-use advent::day2;
+use advent::day2::{self, Config};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let path = get_data_path(REPORT_FILE_PATH).unwrap();
-    match day2::count_safe_reports_strict(path) {
-        Ok(result) => println!("{}", result),
-        Err(e) => panic!("{:?}", e),
-    };
-    Ok(())
+fn main() {
+    let result = get_data_path(REPORT_FILE_PATH)
+        .and_then(|path| day2::count_safe_reports_strict(path, &Config::default()));
+    report(result);
 }
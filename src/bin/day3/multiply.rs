@@ -1,15 +1,12 @@
-use std::error::Error;
-use advent::common::get_data_path;
+use advent::common::{get_data_path, report};
 use advent::day3::constants::MEMORY_FILE_PATH;
 use advent::day3;
 
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let path = get_data_path(MEMORY_FILE_PATH).unwrap();
-    match day3::multiply(path) {
-        Ok(val) => println!("result: {}", val),
-        Err(e) => panic!("{}", e),
-    }
-    Ok(())
+fn main() {
+    let result = get_data_path(MEMORY_FILE_PATH)
+        .and_then(day3::multiply)
+        .map(|val| format!("result: {}", val));
+    report(result);
 }
 
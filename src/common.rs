@@ -1,13 +1,30 @@
+pub mod grid;
+#[cfg(test)]
+pub mod prop;
+pub mod search;
+
+use std::env;
 use std::path::PathBuf;
 use std::error::Error;
+use std::fmt::Display;
+use std::process;
+
+// points the binaries at a puzzle input dir other than the one in the checkout
+pub const DATA_DIR_VAR: &str = "ADVENT_DATA_DIR";
+
+pub fn data_dir() -> PathBuf {
+    match env::var_os(DATA_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("data"),
+    }
+}
 
 pub fn get_data_path(rel_path: &str) -> Result<PathBuf, Box<dyn Error>> {
-    let mut pb = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    pb.push("data/");
+    let mut pb = data_dir();
     pb.push(&rel_path);
     match pb.is_file() {
         true => Ok(pb),
-        false => Err(Box::from(format!("could not resolve path: {}", rel_path))),
+        false => Err(Box::from(format!("could not resolve path: {}", pb.display()))),
     }
 }
 
@@ -21,6 +38,17 @@ pub fn get_test_data_path(rel_path: &str) -> Result<PathBuf, Box<dyn Error>> {
     }
 }
 
+// used by the per-day binaries: print the answer, or a diagnostic and a non-zero exit
+pub fn report<T: Display>(result: Result<T, Box<dyn Error>>) {
+    match result {
+        Ok(answer) => println!("{}", answer),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            process::exit(1);
+        },
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,390 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::error::{ParseError, ParseErrorKind};
+
+// (x, y), x grows to the right and y grows down the page
+pub type Ipos = (isize, isize);
+pub type Upos = (usize, usize);
+
+pub const OFFSETS_4: [Ipos; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub const OFFSETS_8: [Ipos; 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+pub fn offset(pos: Ipos, by: Ipos) -> Ipos {
+    (pos.0 + by.0, pos.1 + by.1)
+}
+
+pub fn to_ipos(upos: Upos) -> Ipos {
+    (isize::try_from(upos.0).unwrap(), isize::try_from(upos.1).unwrap())
+}
+
+// dense, row-major map of cells, for the days whose input is a picture
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn parse<F>(day: usize, input: &str, to_cell: F) -> Result<Self, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        Self::parse_lines(day, input.lines(), to_cell)
+    }
+
+    // for inputs where the map is only one section, `lines` should stop where the map does.
+    // `to_cell` returns None for characters that don't belong on the map
+    pub fn parse_lines<'a, I, F>(day: usize, lines: I, mut to_cell: F) -> Result<Self, ParseError>
+    where
+        I: IntoIterator<Item = &'a str>,
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (y, line) in lines.into_iter().enumerate() {
+            let line_width = line.chars().count();
+            if y == 0 {
+                if line_width == 0 {
+                    return Err(ParseError::new(day, ParseErrorKind::Empty));
+                }
+                width = line_width;
+            } else if line_width != width {
+                return Err(ParseError::new(day, ParseErrorKind::RaggedRow { expected: width, found: line_width })
+                    .at(y, 0)
+                    .with_text(line));
+            }
+
+            for (x, c) in line.chars().enumerate() {
+                match to_cell(c) {
+                    Some(cell) => cells.push(cell),
+                    None => {
+                        return Err(ParseError::new(day, ParseErrorKind::UnexpectedChar(c))
+                            .at(y, x)
+                            .with_text(line));
+                    },
+                }
+            }
+            height += 1;
+        }
+
+        if height == 0 {
+            return Err(ParseError::new(day, ParseErrorKind::Empty));
+        }
+        Ok(Self { width, height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Ipos) -> bool {
+        self.to_upos(pos).is_some()
+    }
+
+    // None when the position is off the grid
+    pub fn to_upos(&self, pos: Ipos) -> Option<Upos> {
+        let x = usize::try_from(pos.0).ok()?;
+        let y = usize::try_from(pos.1).ok()?;
+        match x < self.width && y < self.height {
+            true => Some((x, y)),
+            false => None,
+        }
+    }
+
+    fn idx(&self, pos: Upos) -> Option<usize> {
+        match pos.0 < self.width && pos.1 < self.height {
+            true => Some(pos.1 * self.width + pos.0),
+            false => None,
+        }
+    }
+
+    fn upos_of(&self, idx: usize) -> Upos {
+        (idx % self.width, idx / self.width)
+    }
+
+    pub fn get(&self, pos: Ipos) -> Option<&T> {
+        self.get_u(self.to_upos(pos)?)
+    }
+
+    pub fn get_mut(&mut self, pos: Ipos) -> Option<&mut T> {
+        self.get_u_mut(self.to_upos(pos)?)
+    }
+
+    pub fn get_u(&self, pos: Upos) -> Option<&T> {
+        self.idx(pos).map(|idx| &self.cells[idx])
+    }
+
+    pub fn get_u_mut(&mut self, pos: Upos) -> Option<&mut T> {
+        self.idx(pos).map(|idx| &mut self.cells[idx])
+    }
+
+    // row by row, left to right
+    pub fn iter(&self) -> impl Iterator<Item = (Upos, &T)> {
+        self.cells.iter().enumerate().map(|(idx, cell)| (self.upos_of(idx), cell))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Upos> + '_ {
+        (0..self.cells.len()).map(|idx| self.upos_of(idx))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width)
+    }
+
+    // first match in row-major order
+    pub fn find<P>(&self, pred: P) -> Option<Upos>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        self.cells.iter().position(pred).map(|idx| self.upos_of(idx))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    fn neighbors<'a>(&'a self, pos: Ipos, offsets: &'a [Ipos]) -> impl Iterator<Item = Ipos> + 'a {
+        offsets.iter()
+            .map(move |o| offset(pos, *o))
+            .filter(|n| self.contains(*n))
+    }
+
+    // on-grid neighbors, clockwise from up
+    pub fn neighbors4(&self, pos: Ipos) -> impl Iterator<Item = Ipos> + '_ {
+        self.neighbors(pos, &OFFSETS_4)
+    }
+
+    // on-grid neighbors including diagonals, clockwise from up
+    pub fn neighbors8(&self, pos: Ipos) -> impl Iterator<Item = Ipos> + '_ {
+        self.neighbors(pos, &OFFSETS_8)
+    }
+
+    pub fn neighbors4_u(&self, pos: Upos) -> impl Iterator<Item = Upos> + '_ {
+        self.neighbors4(to_ipos(pos)).map(|n| self.to_upos(n).unwrap())
+    }
+
+    pub fn neighbors8_u(&self, pos: Upos) -> impl Iterator<Item = Upos> + '_ {
+        self.neighbors8(to_ipos(pos)).map(|n| self.to_upos(n).unwrap())
+    }
+}
+
+impl<T> Index<Upos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Upos) -> &T {
+        match self.get_u(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside the {}x{} grid", pos, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Upos> for Grid<T> {
+    fn index_mut(&mut self, pos: Upos) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        match self.get_u_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside the {}x{} grid", pos, width, height),
+        }
+    }
+}
+
+// which tile each character of a picture stands for. markers are characters that must appear
+// exactly once, like a start or a robot, and stand for the tile at their position
+pub struct TileMap<T: 'static> {
+    pub tiles: &'static [(char, T)],
+    pub markers: &'static [(char, T)],
+}
+
+// a parsed picture along with where each of its markers was
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedMap<T> {
+    pub grid: Grid<T>,
+    markers: Vec<(char, Upos)>,
+}
+
+impl<T> ParsedMap<T> {
+    pub fn marker(&self, marker: char) -> Upos {
+        match self.markers.iter().find(|(c, _)| *c == marker) {
+            Some((_, pos)) => *pos,
+            None => panic!("{:?} is not one of the map's markers", marker),
+        }
+    }
+}
+
+impl<T: Clone> TileMap<T> {
+    fn tile(&self, c: char) -> Option<&T> {
+        self.tiles.iter().chain(self.markers)
+            .find(|(tc, _)| *tc == c)
+            .map(|(_, tile)| tile)
+    }
+
+    pub fn parse(&self, day: usize, input: &str) -> Result<ParsedMap<T>, ParseError> {
+        self.parse_lines(day, input.lines())
+    }
+
+    pub fn parse_lines<'a, I>(&self, day: usize, lines: I) -> Result<ParsedMap<T>, ParseError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let chars = Grid::parse_lines(day, lines, |c| self.tile(c).map(|_| c))?;
+
+        let mut markers = Vec::with_capacity(self.markers.len());
+        for (marker, _) in self.markers {
+            let mut found = chars.iter().filter(|(_, c)| *c == marker).map(|(pos, _)| pos);
+            match (found.next(), found.next()) {
+                (Some(pos), None) => markers.push((*marker, pos)),
+                (None, _) => return Err(ParseError::new(day, ParseErrorKind::MissingMarker(*marker))),
+                (Some(_), Some((x, y))) => {
+                    return Err(ParseError::new(day, ParseErrorKind::DuplicateMarker(*marker)).at(y, x));
+                },
+            }
+        }
+
+        let grid = chars.map(|c| self.tile(*c).unwrap().clone());
+        Ok(ParsedMap { grid, markers })
+    }
+}
+
+// each cell renders as one character, the inverse of parse
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(0, input, |c| c.to_digit(10))
+    }
+
+    #[test]
+    fn parses_row_major() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(0, 1)], 4);
+        assert_eq!(grid.get((1, 1)), Some(&5));
+        assert_eq!(grid.find(|d| *d == 6), Some((2, 1)));
+    }
+
+    #[test]
+    fn signed_lookups_off_the_grid() {
+        let grid = digits("12\n34").unwrap();
+        assert_eq!(grid.get((-1, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+        assert_eq!(grid.get_u((2, 0)), None);
+        assert!(!grid.contains((2, -1)));
+    }
+
+    #[test]
+    fn neighbors_stay_on_the_grid() {
+        let grid = digits("123\n456\n789").unwrap();
+        let corner: Vec<Ipos> = grid.neighbors4((0, 0)).collect();
+        assert_eq!(corner, vec![(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        let edge: Vec<Upos> = grid.neighbors8_u((1, 0)).collect();
+        assert_eq!(edge, vec![(2, 0), (2, 1), (1, 1), (0, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn displays_as_parsed() {
+        let input = "123\n456\n";
+        assert_eq!(digits(input).unwrap().to_string(), input);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(digits("").unwrap_err().kind, ParseErrorKind::Empty);
+
+        let err = digits("123\n45\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::RaggedRow { expected: 3, found: 2 });
+        assert_eq!(err.line, 2);
+
+        let err = digits("123\n4x6\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('x'));
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+
+    const MAZE: TileMap<bool> = TileMap {
+        tiles: &[('#', false), ('.', true)],
+        markers: &[('S', true), ('E', true)],
+    };
+
+    #[test]
+    fn tile_map_finds_markers() {
+        let map = MAZE.parse(0, "#####\n#S.E#\n#####\n").unwrap();
+        assert_eq!(map.marker('S'), (1, 1));
+        assert_eq!(map.marker('E'), (3, 1));
+        assert_eq!(map.grid.iter().filter(|(_, open)| **open).count(), 3);
+    }
+
+    #[test]
+    fn tile_map_errors() {
+        let err = MAZE.parse(0, "#S.#\n#..#\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingMarker('E'));
+
+        let err = MAZE.parse(0, "#S.E#\n#..S#\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::DuplicateMarker('S'));
+        assert_eq!((err.line, err.column), (2, 4));
+
+        let err = MAZE.parse(0, "#S.E#\n#..#\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::RaggedRow { expected: 5, found: 4 });
+
+        let err = MAZE.parse(0, "#S.E#\n#.x.#\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('x'));
+    }
+
+    #[test]
+    fn parse_lines_stops_with_the_iterator() {
+        let input = "12\n34\n\nnot a map\n";
+        let grid = Grid::parse_lines(0, input.lines().take_while(|l| !l.is_empty()), |c| c.to_digit(10)).unwrap();
+        assert_eq!(grid.height(), 2);
+    }
+}
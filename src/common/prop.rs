@@ -0,0 +1,82 @@
+use std::env;
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use quickcheck::{Arbitrary, Gen};
+
+// property tests pit a day's optimized solver against a naive oracle on small random inputs.
+// each day implements Arbitrary for its own input type, or a newtype around one, and keeps
+// the sizes small enough for the oracle to be quick
+
+// the oracles are slow by design, so fewer cases than quickcheck's default 100.
+// setting QUICKCHECK_TESTS runs as many as it asks for instead
+const NUM_TESTS: usize = 50;
+
+// quickcheck's generator size, which bounds the built-in Arbitrary impls
+const GEN_SIZE: usize = 100;
+
+// a fixed seed keeps every run on the same inputs so a failure shows up each time.
+// setting ADVENT_PROP_SEED explores other inputs, or replays the seed a failure reported
+const SEED: u64 = 2024;
+
+fn num_tests() -> usize {
+    env::var("QUICKCHECK_TESTS").ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(NUM_TESTS)
+}
+
+fn seed() -> u64 {
+    env::var("ADVENT_PROP_SEED").ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(SEED)
+}
+
+// quickcheck's own runner only reports failing inputs it managed to shrink, and the
+// generated inputs here don't shrink, so this runs the cases and reports them itself
+pub fn check<A, P>(prop: P)
+where
+    A: Arbitrary + Debug,
+    P: Fn(&A) -> bool,
+{
+    let seed = seed();
+    let mut g = Gen::from_size_and_seed(GEN_SIZE, seed);
+    for case in 0..num_tests() {
+        let input = A::arbitrary(&mut g);
+        assert!(
+            prop(&input),
+            "property failed on case {} with ADVENT_PROP_SEED={} for {:#?}", case, seed, input,
+        );
+    }
+}
+
+// uniform in `range`, quickcheck only hands out whole integer types
+pub fn in_range(g: &mut Gen, range: RangeInclusive<usize>) -> usize {
+    range.start() + usize::arbitrary(g) % (range.end() - range.start() + 1)
+}
+
+// `len` characters drawn from `alphabet`
+pub fn string_of(g: &mut Gen, alphabet: &[char], len: usize) -> String {
+    (0..len).map(|_| *g.choose(alphabet).unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_are_inclusive() {
+        let mut g = Gen::new(10);
+        for _ in 0..100 {
+            assert!((3..=5).contains(&in_range(&mut g, 3..=5)));
+        }
+        assert_eq!(in_range(&mut g, 7..=7), 7);
+    }
+
+    #[test]
+    fn strings_stay_in_alphabet() {
+        let mut g = Gen::new(10);
+        let s = string_of(&mut g, &['a', 'b'], 12);
+        assert_eq!(s.len(), 12);
+        assert!(s.chars().all(|c| c == 'a' || c == 'b'));
+    }
+}
@@ -0,0 +1,362 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Add;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+
+// searches take the graph as a closure from a node to its neighbors, so the days don't have
+// to build one. weighted searches want (neighbor, cost) pairs, costs start at C::default()
+
+// fewest steps from `start` to everything reachable
+pub fn bfs<N, F, I>(start: N, mut neighbors: F) -> FxHashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut dists = FxHashMap::default();
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+    dists.insert(start, 0);
+
+    while let Some((node, dist)) = queue.pop_front() {
+        for next in neighbors(&node) {
+            if !dists.contains_key(&next) {
+                dists.insert(next.clone(), dist + 1);
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+    dists
+}
+
+// fewest steps to the nearest goal, stopping as soon as one is reached
+pub fn bfs_to<N, F, I, G>(start: N, mut neighbors: F, mut is_goal: G) -> Option<usize>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut seen = FxHashSet::default();
+    let mut queue = VecDeque::from([(start.clone(), 0)]);
+    seen.insert(start);
+
+    while let Some((node, dist)) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(dist);
+        }
+        for next in neighbors(&node) {
+            if seen.insert(next.clone()) {
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+    None
+}
+
+// heap entry ordered by cost alone, cheapest first
+struct Frontier<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for Frontier<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<N, C: Ord> Eq for Frontier<N, C> {}
+
+impl<N, C: Ord> PartialOrd for Frontier<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for Frontier<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+// cheapest cost from `start` to everything reachable
+pub fn dijkstra<N, C, F, I>(start: N, neighbors: F) -> FxHashMap<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    walk_shortest_paths(start, neighbors).0.costs
+}
+
+// cheapest path to the nearest goal, as (cost, nodes from start to goal).
+// `heuristic` must never overestimate the remaining cost, `|_| C::default()` makes this dijkstra
+pub fn astar<N, C, F, I, H, G>(start: N, mut neighbors: F, mut heuristic: H, mut is_goal: G) -> Option<(C, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+    H: FnMut(&N) -> C,
+    G: FnMut(&N) -> bool,
+{
+    let mut costs: FxHashMap<N, C> = FxHashMap::default();
+    let mut came_from: FxHashMap<N, N> = FxHashMap::default();
+    let mut heap = BinaryHeap::new();
+
+    costs.insert(start.clone(), C::default());
+    heap.push(Frontier { priority: heuristic(&start), cost: C::default(), node: start });
+
+    while let Some(Frontier { cost, node, .. }) = heap.pop() {
+        if costs.get(&node).is_some_and(|best| cost > *best) {
+            continue;
+        }
+        if is_goal(&node) {
+            let mut path = vec![node];
+            while let Some(prev) = came_from.get(path.last().unwrap()) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+        for (next, step) in neighbors(&node) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_none_or(|best| next_cost < *best) {
+                costs.insert(next.clone(), next_cost);
+                came_from.insert(next.clone(), node.clone());
+                heap.push(Frontier { priority: next_cost + heuristic(&next), cost: next_cost, node: next });
+            }
+        }
+    }
+    None
+}
+
+// every node's cheapest cost from the start, along with every predecessor that achieves it.
+// following predecessors back from a node walks exactly the optimal paths to it
+pub struct PathDag<N, C> {
+    pub start: N,
+    pub costs: FxHashMap<N, C>,
+    preds: FxHashMap<N, Vec<N>>,
+}
+
+// two nodes joined by zero-cost edges tie with each other both ways round, which makes each
+// the other's predecessor, and following predecessors back from either never ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonPositiveCost;
+
+impl fmt::Display for NonPositiveCost {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "every edge has to cost more than nothing to keep all the shortest paths")
+    }
+}
+
+impl Error for NonPositiveCost {}
+
+// the edge costs have to be above C::default(), dijkstra will take zero-cost edges
+pub fn all_shortest_paths<N, C, F, I>(start: N, neighbors: F) -> Result<PathDag<N, C>, NonPositiveCost>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    match walk_shortest_paths(start, neighbors) {
+        (dag, true) => Ok(dag),
+        (_, false) => Err(NonPositiveCost),
+    }
+}
+
+// the dag, and whether every edge it crossed cost more than nothing
+fn walk_shortest_paths<N, C, F, I>(start: N, mut neighbors: F) -> (PathDag<N, C>, bool)
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut costs: FxHashMap<N, C> = FxHashMap::default();
+    let mut preds: FxHashMap<N, Vec<N>> = FxHashMap::default();
+    let mut heap = BinaryHeap::new();
+    let mut all_positive = true;
+
+    costs.insert(start.clone(), C::default());
+    heap.push(Frontier { priority: C::default(), cost: C::default(), node: start.clone() });
+
+    while let Some(Frontier { cost, node, .. }) = heap.pop() {
+        if costs.get(&node).is_some_and(|best| cost > *best) {
+            continue;
+        }
+        for (next, step) in neighbors(&node) {
+            all_positive &= step > C::default();
+            let next_cost = cost + step;
+            match costs.get(&next).map(|best| next_cost.cmp(best)) {
+                Some(Ordering::Greater) => (),
+                Some(Ordering::Equal) => preds.entry(next).or_default().push(node.clone()),
+                Some(Ordering::Less) | None => {
+                    costs.insert(next.clone(), next_cost);
+                    preds.insert(next.clone(), vec![node.clone()]);
+                    heap.push(Frontier { priority: next_cost, cost: next_cost, node: next });
+                },
+            }
+        }
+    }
+    (PathDag { start, costs, preds }, all_positive)
+}
+
+impl<N, C> PathDag<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy,
+{
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    pub fn predecessors(&self, node: &N) -> &[N] {
+        self.preds.get(node).map_or(&[], |p| p.as_slice())
+    }
+
+    // every node on some optimal path to any of `targets`, targets included
+    pub fn nodes_on_paths_to<T>(&self, targets: T) -> FxHashSet<N>
+    where
+        T: IntoIterator<Item = N>,
+    {
+        let mut seen = FxHashSet::default();
+        let mut to_visit: Vec<N> = targets.into_iter().filter(|t| self.costs.contains_key(t)).collect();
+        while let Some(node) = to_visit.pop() {
+            if seen.insert(node.clone()) {
+                to_visit.extend(self.predecessors(&node).iter().cloned());
+            }
+        }
+        seen
+    }
+
+    pub fn count_paths_to(&self, target: &N) -> usize {
+        let mut counts = FxHashMap::default();
+        self.count_paths_memo(target, &mut counts)
+    }
+
+    fn count_paths_memo(&self, node: &N, counts: &mut FxHashMap<N, usize>) -> usize {
+        if *node == self.start {
+            return 1;
+        }
+        if let Some(count) = counts.get(node) {
+            return *count;
+        }
+        let count = self.predecessors(node).iter()
+            .map(|pred| self.count_paths_memo(pred, counts))
+            .sum();
+        counts.insert(node.clone(), count);
+        count
+    }
+
+    // each path runs from the start to `target`. there can be a lot of these,
+    // count_paths_to is the cheap way to ask how many
+    pub fn paths_to(&self, target: &N) -> Vec<Vec<N>> {
+        if !self.costs.contains_key(target) {
+            return vec![];
+        }
+        if *target == self.start {
+            return vec![vec![target.clone()]];
+        }
+        let mut paths = vec![];
+        for pred in self.predecessors(target) {
+            for mut path in self.paths_to(pred) {
+                path.push(target.clone());
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 - 1 - 3
+    //  \     /
+    //   - 2 -      plus 4, which nothing reaches
+    fn diamond(node: &usize) -> Vec<(usize, usize)> {
+        match node {
+            0 => vec![(1, 1), (2, 1)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 1)],
+            4 => vec![(3, 1)],
+            _ => vec![],
+        }
+    }
+
+    fn unweighted(node: &usize) -> Vec<usize> {
+        diamond(node).into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn bfs_counts_steps() {
+        let dists = bfs(0, unweighted);
+        assert_eq!(dists.get(&3), Some(&2));
+        assert_eq!(dists.get(&4), None);
+        assert_eq!(bfs_to(0, unweighted, |n| *n == 3), Some(2));
+        assert_eq!(bfs_to(0, unweighted, |n| *n == 4), None);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheap_detours() {
+        // straight to 2 costs 10, going through 1 costs 3
+        let costs = dijkstra(0usize, |n: &usize| match n {
+            0 => vec![(2, 10), (1, 1)],
+            1 => vec![(2, 2)],
+            _ => vec![],
+        });
+        assert_eq!(costs.get(&2), Some(&3));
+    }
+
+    #[test]
+    fn astar_on_a_line() {
+        let goal = 10isize;
+        let (cost, path) = astar(
+            0isize,
+            |n: &isize| vec![(n - 1, 1), (n + 1, 1)],
+            |n: &isize| (goal - n).abs(),
+            |n: &isize| *n == goal,
+        ).unwrap();
+        assert_eq!(cost, 10);
+        assert_eq!(path, (0..=10).collect::<Vec<isize>>());
+    }
+
+    #[test]
+    fn dag_keeps_every_optimal_path() {
+        let dag = all_shortest_paths(0, diamond).unwrap();
+        assert_eq!(dag.cost(&3), Some(2));
+        assert_eq!(dag.count_paths_to(&3), 2);
+
+        let mut paths = dag.paths_to(&3);
+        paths.sort();
+        assert_eq!(paths, vec![vec![0, 1, 3], vec![0, 2, 3]]);
+
+        let on_paths = dag.nodes_on_paths_to([3]);
+        assert_eq!(on_paths.len(), 4);
+        assert!(dag.paths_to(&4).is_empty());
+    }
+
+    #[test]
+    fn zero_cost_edges() {
+        // 1 and 2 are joined both ways for free, so each ties with the other
+        let free_loop = |n: &usize| match n {
+            0 => vec![(1, 1), (2, 1)],
+            1 => vec![(2, 0)],
+            2 => vec![(1, 0)],
+            _ => vec![],
+        };
+        assert_eq!(all_shortest_paths(0, free_loop).err(), Some(NonPositiveCost));
+
+        let costs = dijkstra(0, free_loop);
+        assert_eq!((costs.get(&1), costs.get(&2)), (Some(&1), Some(&1)));
+    }
+}
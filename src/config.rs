@@ -0,0 +1,136 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use toml::{Table, Value};
+
+// puzzle parameters that aren't part of the input, laid out as
+//
+//     [day18]
+//     x_max = 6
+//     bytes_fallen = 12
+//
+// every day's Config defaults to the real puzzle, a key that's left out keeps its default
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Overrides {
+    table: Table,
+}
+
+// the Config of the days that don't have any parameters
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoConfig {}
+
+// for #[serde(deserialize_with)] on the parameters that can't go below 0, like costs
+pub fn non_negative<'de, D: Deserializer<'de>>(d: D) -> Result<isize, D::Error> {
+    match isize::deserialize(d)? {
+        n if n < 0 => Err(de::Error::custom(format!("{} is below 0", n))),
+        n => Ok(n),
+    }
+}
+
+// same again, for the ones that can't be 0 either
+pub fn positive<'de, D: Deserializer<'de>>(d: D) -> Result<isize, D::Error> {
+    match isize::deserialize(d)? {
+        n if n < 1 => Err(de::Error::custom(format!("{} is not above 0", n))),
+        n => Ok(n),
+    }
+}
+
+impl Overrides {
+    pub fn from(input: &str) -> Result<Self, Box<dyn Error>> {
+        let table = input.parse::<Table>()?;
+        Ok(Self { table })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let input = fs::read_to_string(path)
+            .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        Self::from(&input)
+    }
+
+    // a key=value from the command line. the value is read as TOML, so numbers stay numbers,
+    // anything that isn't valid TOML is taken as a plain string
+    pub fn set(&mut self, day: usize, assignment: &str) -> Result<(), String> {
+        let (key, value) = assignment.split_once('=')
+            .ok_or(format!("expected key=value, got {}", assignment))?;
+        let key = key.trim();
+        let value = format!("v = {}", value.trim()).parse::<Table>().ok()
+            .and_then(|mut t| t.remove("v"))
+            .unwrap_or_else(|| Value::String(value.trim().to_string()));
+
+        let section = self.table.entry(format!("day{}", day))
+            .or_insert_with(|| Value::Table(Table::new()));
+        match section {
+            Value::Table(section) => {
+                section.insert(key.to_string(), value);
+                Ok(())
+            },
+            _ => Err(format!("day{} in the config is not a table", day)),
+        }
+    }
+
+    pub fn for_day<C: Default + DeserializeOwned>(&self, day: usize) -> Result<C, Box<dyn Error>> {
+        match self.table.get(&format!("day{}", day)) {
+            Some(section) => section.clone().try_into::<C>()
+                .map_err(|e| Box::from(format!("bad config for day {}: {}", day, e.message()))),
+            None => Ok(C::default()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    #[serde(default, deny_unknown_fields)]
+    struct Lobby {
+        #[serde(deserialize_with = "positive")]
+        width: isize,
+        height: isize,
+    }
+
+    impl Default for Lobby {
+        fn default() -> Self {
+            Self { width: 101, height: 103 }
+        }
+    }
+
+    #[test]
+    fn missing_keys_keep_defaults() {
+        let overrides = Overrides::from("[day14]\nwidth = 11\n").unwrap();
+        assert_eq!(overrides.for_day::<Lobby>(14).unwrap(), Lobby { width: 11, height: 103 });
+        assert_eq!(overrides.for_day::<Lobby>(13).unwrap(), Lobby::default());
+    }
+
+    #[test]
+    fn set_from_the_command_line() {
+        let mut overrides = Overrides::from("[day14]\nwidth = 11\n").unwrap();
+        overrides.set(14, "height=7").unwrap();
+        overrides.set(14, " width = 12 ").unwrap();
+        assert_eq!(overrides.for_day::<Lobby>(14).unwrap(), Lobby { width: 12, height: 7 });
+        assert!(overrides.set(14, "height").is_err());
+    }
+
+    #[test]
+    fn bad_keys_and_values() {
+        let mut overrides = Overrides::default();
+        overrides.set(14, "depth=3").unwrap();
+        assert!(overrides.for_day::<Lobby>(14).is_err());
+
+        let mut overrides = Overrides::default();
+        overrides.set(14, "width=wide").unwrap();
+        let err = overrides.for_day::<Lobby>(14).unwrap_err();
+        assert!(err.to_string().starts_with("bad config for day 14"));
+
+        let mut overrides = Overrides::default();
+        overrides.set(14, "width=-1").unwrap();
+        assert!(overrides.for_day::<Lobby>(14).unwrap_err().to_string().contains("-1 is not above 0"));
+
+        assert!(Overrides::default().set(1, "x=1").is_ok());
+        assert!(Overrides::default().for_day::<NoConfig>(1).is_ok());
+    }
+}
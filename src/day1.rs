@@ -1,48 +1,128 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use std::fs::read_to_string;
 use std::fmt;
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::path::Path;
 
-use regex::Regex;
+use crate::config::NoConfig;
+use crate::error::{expect_token, parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
+use crate::stream::{self, StreamError};
 
 pub mod constants {
     pub const LOCATIONS_FILE_PATH: &str = "day1/locations.txt";
 }
 
-#[derive(Debug, PartialEq)]
-enum LocError {
-    PathError,
+const DAY: usize = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Delimiter {
+    // any run of spaces or tabs, like the puzzle input
+    Whitespace,
+    Comma,
+    Tab,
 }
 
-impl Error for LocError {}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    // counting from 0
+    Index(usize),
+    // looked up in the header, so it needs header set
+    Name(String),
+}
 
-impl fmt::Display for LocError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "loc error")
+// how to pick the two lists out of a file of rows. blank lines are skipped and extra columns
+// ignored. fields are split on the delimiter as they are, there's no quoting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnReader {
+    pub delimiter: Delimiter,
+    // whether the first line that isn't blank names the columns rather than holding locations
+    pub header: bool,
+    pub columns: [Column; 2],
+}
+
+impl Default for ColumnReader {
+    fn default() -> Self {
+        Self { delimiter: Delimiter::Whitespace, header: false, columns: [Column::Index(0), Column::Index(1)] }
     }
 }
 
-fn _read_columns(file_path: &PathBuf) -> Result<(Vec<u64>, Vec<u64>), Box<dyn Error>> {
-    let mut col1: Vec<u64> = Vec::new();
-    let mut col2: Vec<u64> = Vec::new();
+impl ColumnReader {
+    pub fn parse(&self, input: &str) -> Result<(Vec<u64>, Vec<u64>), ParseError> {
+        let mut parse_row = self.row_parser();
+        input.lines().enumerate()
+            .filter_map(|(line_idx, line)| parse_row(line_idx, line).transpose())
+            .collect()
+    }
 
-    let rx = Regex::new(r"\s+").expect("invalid regex");
+    // the same, without holding the text. the columns themselves still have to be kept to sort them
+    pub fn read<R: BufRead>(&self, reader: R) -> Result<(Vec<u64>, Vec<u64>), StreamError> {
+        stream::records(reader, self.row_parser())
+            .filter_map(Result::transpose)
+            .collect()
+    }
 
-    for line in read_to_string(file_path).unwrap().lines() {
-        let locations: Vec<&str> = rx.split(line).collect();
+    pub fn read_file(&self, path: impl AsRef<Path>) -> Result<(Vec<u64>, Vec<u64>), Box<dyn Error>> {
+        Ok(self.read(stream::open(path)?)?)
+    }
 
-        let loc1 = locations[0].parse::<u64>().unwrap();
-        let loc2 = locations[1].parse::<u64>().unwrap();
+    fn fields<'a>(&self, line: &'a str) -> Vec<&'a str> {
+        match self.delimiter {
+            Delimiter::Whitespace => line.split_whitespace().collect(),
+            Delimiter::Comma => line.split(',').map(str::trim).collect(),
+            Delimiter::Tab => line.split('\t').map(str::trim).collect(),
+        }
+    }
 
-        col1.push(loc1);
-        col2.push(loc2);
+    // which fields the columns are, header holds the names if there are any
+    fn resolve(&self, header: &[&str], line_idx: usize, line: &str) -> Result<[usize; 2], ParseError> {
+        let resolve = |column: &Column| match column {
+            Column::Index(idx) => Ok(*idx),
+            Column::Name(name) => header.iter().position(|field| field == name).ok_or_else(|| {
+                ParseError::new(DAY, ParseErrorKind::MissingColumn(name.clone()))
+                    .on_line(line_idx)
+                    .with_text(line)
+            }),
+        };
+        Ok([resolve(&self.columns[0])?, resolve(&self.columns[1])?])
     }
-    Ok((col1, col2))
+
+    // a pair of locations from each row, None for the lines that aren't rows
+    fn row_parser(&self) -> impl FnMut(usize, &str) -> Result<Option<(u64, u64)>, ParseError> + '_ {
+        let mut indices = None;
+        move |line_idx, line| {
+            if line.trim().is_empty() {
+                return Ok(None);
+            }
+            let fields = self.fields(line);
+            let [idx1, idx2] = match indices {
+                Some(indices) => indices,
+                None if self.header => {
+                    indices = Some(self.resolve(&fields, line_idx, line)?);
+                    return Ok(None);
+                },
+                None => *indices.insert(self.resolve(&[], line_idx, line)?),
+            };
+
+            let loc1 = expect_token(DAY, line_idx, line, fields.get(idx1).copied(), "first location")?;
+            let loc2 = expect_token(DAY, line_idx, line, fields.get(idx2).copied(), "second location")?;
+
+            Ok(Some((parse_num(DAY, line_idx, line, loc1)?, parse_num(DAY, line_idx, line, loc2)?)))
+        }
+    }
+}
+
+pub fn parse_columns(input: &str) -> Result<(Vec<u64>, Vec<u64>), ParseError> {
+    ColumnReader::default().parse(input)
+}
+
+pub fn read_columns<R: BufRead>(reader: R) -> Result<(Vec<u64>, Vec<u64>), StreamError> {
+    ColumnReader::default().read(reader)
 }
 
-pub fn result_from_file(file_path: &PathBuf) -> Result<u64, Box<dyn Error>> {
-    let (mut col1, mut col2) = _read_columns(file_path)?;
+pub fn total_distance(col1: &[u64], col2: &[u64]) -> u64 {
+    let mut col1 = col1.to_vec();
+    let mut col2 = col2.to_vec();
 
     col1.sort();
     col2.sort();
@@ -53,26 +133,217 @@ pub fn result_from_file(file_path: &PathBuf) -> Result<u64, Box<dyn Error>> {
     for (loc1, loc2) in it {
         result += loc1.abs_diff(*loc2);
     }
-    Ok(result)
+    result
 }
 
-pub fn compute_similarity_score(file_path: &PathBuf) -> Result<u64, Box<dyn Error>> {
-    let (col1, col2) = _read_columns(file_path)?;
+pub fn similarity_score(col1: &[u64], col2: &[u64]) -> u64 {
     let mut col2_counts: HashMap<u64, u64> = HashMap::new();
 
     for loc in col2 {
-        *col2_counts.entry(loc).or_insert(0) += 1;
+        *col2_counts.entry(*loc).or_insert(0) += 1;
     }
 
     let mut result: u64 = 0;
     for loc in col1 {
-        let multiplier = *col2_counts.entry(loc).or_insert(0);
+        let multiplier = *col2_counts.entry(*loc).or_insert(0);
         result += loc * multiplier;
     }
+    result
+}
+
+// ways of comparing two lists of locations. the first three compare the lists sorted, smallest
+// with smallest and so on, like total_distance. jaccard and intersection go by which locations
+// are in both, in any order, and are larger the more alike the lists are. kendall tau compares
+// the lists row by row, as rankings of the same rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    // the sum of the differences
+    L1,
+    // the euclidean distance
+    L2,
+    // the largest difference
+    Chebyshev,
+    // the share of the distinct locations that are in both, 1 when they're the same
+    Jaccard,
+    // how many locations the lists have in common, counting repeats
+    Intersection,
+    // how many pairs of rows the lists put in opposite orders
+    KendallTau,
+}
+
+// kendall tau pairs the lists up row by row, so it can't compare lists of different lengths
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LengthMismatch {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "can't compare the rows of lists of {} and {} locations", self.left, self.right)
+    }
+}
+
+impl Error for LengthMismatch {}
 
-    Ok(result)
+// a column asked for by index that the lists don't have
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoSuchColumn {
+    pub column: usize,
+    pub columns: usize,
 }
 
+impl fmt::Display for NoSuchColumn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "there's no column {}, the lists have {}", self.column, self.columns)
+    }
+}
+
+impl Error for NoSuchColumn {}
+
+impl Metric {
+    pub fn all() -> [Metric; 6] {
+        [Metric::L1, Metric::L2, Metric::Chebyshev, Metric::Jaccard, Metric::Intersection, Metric::KendallTau]
+    }
+
+    // the sorted metrics compare as many locations as the shorter list has, kendall tau needs a
+    // and b to be the same length, as columns of the same rows always are
+    pub fn compare(&self, a: &[u64], b: &[u64]) -> Result<f64, LengthMismatch> {
+        let distance = match self {
+            Metric::L1 => total_distance(a, b) as f64,
+            Metric::L2 => sorted_diffs(a, b).map(|d| (d as f64).powi(2)).sum::<f64>().sqrt(),
+            Metric::Chebyshev => sorted_diffs(a, b).max().unwrap_or(0) as f64,
+            Metric::Jaccard => {
+                let a: HashSet<u64> = a.iter().copied().collect();
+                let b: HashSet<u64> = b.iter().copied().collect();
+                let union = a.union(&b).count();
+                match union {
+                    0 => 1.0,
+                    _ => a.intersection(&b).count() as f64 / union as f64,
+                }
+            },
+            Metric::Intersection => {
+                let mut counts: HashMap<u64, u64> = HashMap::new();
+                for loc in a {
+                    *counts.entry(*loc).or_insert(0) += 1;
+                }
+                let mut common = 0;
+                for loc in b {
+                    if let Some(count) = counts.get_mut(loc).filter(|count| **count > 0) {
+                        *count -= 1;
+                        common += 1;
+                    }
+                }
+                common as f64
+            },
+            Metric::KendallTau => {
+                if a.len() != b.len() {
+                    return Err(LengthMismatch { left: a.len(), right: b.len() });
+                }
+                // ties in either list don't order the pair, so they don't count against it
+                let mut discordant = 0;
+                for i in 0..a.len() {
+                    for j in i+1..a.len() {
+                        if a[i].cmp(&a[j]) == b[j].cmp(&b[i]) && a[i] != a[j] {
+                            discordant += 1;
+                        }
+                    }
+                }
+                discordant as f64
+            },
+        };
+        Ok(distance)
+    }
+}
+
+fn sorted_diffs(a: &[u64], b: &[u64]) -> impl Iterator<Item = u64> {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    a.sort();
+    b.sort();
+    a.into_iter().zip(b).map(|(loc1, loc2)| loc1.abs_diff(loc2))
+}
+
+// any number of columns of locations, a row per line like the puzzle's two. blank lines are
+// skipped, as ColumnReader does
+pub struct LocationLists {
+    columns: Vec<Vec<u64>>,
+}
+
+impl LocationLists {
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut columns: Vec<Vec<u64>> = Vec::new();
+        for (line_idx, line) in input.lines().enumerate() {
+            let row = line.split_whitespace()
+                .map(|loc| parse_num(DAY, line_idx, line, loc))
+                .collect::<Result<Vec<u64>, ParseError>>()?;
+            if row.is_empty() {
+                continue;
+            }
+            if columns.is_empty() {
+                columns = vec![Vec::new(); row.len()];
+            }
+            if row.len() != columns.len() {
+                return Err(ParseError::new(DAY, ParseErrorKind::RaggedRow { expected: columns.len(), found: row.len() })
+                    .on_line(line_idx)
+                    .with_text(line));
+            }
+            for (column, loc) in columns.iter_mut().zip(row) {
+                column.push(loc);
+            }
+        }
+        Ok(Self { columns })
+    }
+
+    pub fn columns(&self) -> &[Vec<u64>] {
+        &self.columns
+    }
+
+    pub fn compare(&self, col1: usize, col2: usize, metric: Metric) -> Result<f64, NoSuchColumn> {
+        let column = |column| self.columns.get(column).ok_or(NoSuchColumn { column, columns: self.columns.len() });
+        Ok(compare_columns(column(col1)?, column(col2)?, metric))
+    }
+
+    // every column against every other, matrix[i][j] compares column i with column j
+    pub fn matrix(&self, metric: Metric) -> Vec<Vec<f64>> {
+        self.columns.iter()
+            .map(|a| self.columns.iter().map(|b| compare_columns(a, b, metric)).collect())
+            .collect()
+    }
+}
+
+fn compare_columns(a: &[u64], b: &[u64], metric: Metric) -> f64 {
+    metric.compare(a, b).expect("columns of the same rows are the same length")
+}
+
+pub fn result_from_file(file_path: impl AsRef<Path>, reader: &ColumnReader) -> Result<u64, Box<dyn Error>> {
+    let (col1, col2) = reader.read_file(file_path)?;
+    Ok(total_distance(&col1, &col2))
+}
+
+pub fn compute_similarity_score(file_path: impl AsRef<Path>, reader: &ColumnReader) -> Result<u64, Box<dyn Error>> {
+    let (col1, col2) = reader.read_file(file_path)?;
+    Ok(similarity_score(&col1, &col2))
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = (Vec<u64>, Vec<u64>);
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_columns(input)
+    }
+
+    fn part1((col1, col2): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(total_distance(col1, col2).into())
+    }
+
+    fn part2((col1, col2): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(similarity_score(col1, col2).into())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -82,14 +353,88 @@ mod tests {
     #[test]
     fn compute_result_from_file() {
         let path = get_test_data_path("day1/case1.txt").unwrap();
-        let result = result_from_file(&path).unwrap();
+        let result = result_from_file(&path, &ColumnReader::default()).unwrap();
         assert_eq!(result, 116, "computed the correct result");
     }
 
     #[test]
     fn similarity_score() {
         let path = get_test_data_path("day1/case2.txt").unwrap();
-        let result = compute_similarity_score(&path).unwrap();
+        let result = compute_similarity_score(&path, &ColumnReader::default()).unwrap();
         assert_eq!(result, 31, "computed the correct result");
     }
+
+    #[test]
+    fn streamed_columns() {
+        let (col1, col2) = read_columns("3   4\n4   3\n".as_bytes()).unwrap();
+        assert_eq!((col1, col2), (vec![3, 4], vec![4, 3]));
+        let err = read_columns("3   4\n4\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, StreamError::Parse(e) if e.line == 2));
+    }
+
+    #[test]
+    fn metrics() {
+        let a = [3, 4, 2, 1, 3, 3];
+        let b = [4, 3, 5, 3, 9, 3];
+        assert_eq!(Metric::L1.compare(&a, &b), Ok(11.0));
+        assert_eq!(Metric::L2.compare(&a, &b), Ok(35.0_f64.sqrt()));
+        assert_eq!(Metric::Chebyshev.compare(&a, &b), Ok(5.0));
+        assert_eq!(Metric::Jaccard.compare(&a, &b), Ok(2.0 / 6.0));
+        assert_eq!(Metric::Intersection.compare(&a, &b), Ok(4.0));
+        assert_eq!(Metric::KendallTau.compare(&[1, 2, 3], &[3, 2, 1]), Ok(3.0));
+        assert_eq!(Metric::KendallTau.compare(&[1, 2, 2], &[1, 3, 2]), Ok(0.0));
+        assert_eq!(Metric::KendallTau.compare(&[1, 2, 3], &[1, 2]), Err(LengthMismatch { left: 3, right: 2 }));
+    }
+
+    #[test]
+    fn location_lists() {
+        let lists = LocationLists::parse("1 1 3\n2 2 1\n3 3 2\n").unwrap();
+        assert_eq!(lists.columns().len(), 3);
+        for metric in Metric::all() {
+            let matrix = lists.matrix(metric);
+            assert_eq!(matrix[0][1], matrix[1][0]);
+            assert_eq!(matrix[0][2], matrix[2][0]);
+        }
+        assert_eq!(lists.matrix(Metric::L1)[0][2], 0.0);
+        assert_eq!(lists.compare(0, 2, Metric::KendallTau), Ok(2.0));
+        assert_eq!(lists.compare(1, 3, Metric::L1), Err(NoSuchColumn { column: 3, columns: 3 }));
+        assert_eq!(lists.matrix(Metric::KendallTau), vec![vec![0.0, 0.0, 2.0], vec![0.0, 0.0, 2.0], vec![2.0, 2.0, 0.0]]);
+
+        let err = LocationLists::parse("1 2 3\n4 5\n").err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::RaggedRow { expected: 3, found: 2 });
+        assert_eq!(err.line, 2);
+
+        let input = "\n3   4\n4   3\n\n2   5\n";
+        let lists = LocationLists::parse(input).unwrap();
+        assert_eq!(lists.columns(), [parse_columns(input).unwrap().0, parse_columns(input).unwrap().1]);
+    }
+
+    #[test]
+    fn csv_with_header() {
+        let reader = ColumnReader {
+            delimiter: Delimiter::Comma,
+            header: true,
+            columns: [Column::Name(String::from("right")), Column::Index(0)],
+        };
+        let input = "\nleft, right\n3, 4\n\n4,3\n";
+        assert_eq!(reader.parse(input).unwrap(), (vec![4, 3], vec![3, 4]));
+        assert_eq!(reader.read(input.as_bytes()).unwrap(), (vec![4, 3], vec![3, 4]));
+
+        let err = reader.parse("left,middle\n3,4\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingColumn(String::from("right")));
+        assert_eq!(err.line, 1);
+        let err = reader.parse("left,right\n3,4\n5\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("first location"));
+        assert_eq!(err.line, 3);
+
+        let tabs = ColumnReader { delimiter: Delimiter::Tab, ..ColumnReader::default() };
+        assert_eq!(tabs.parse("1\t2\n3\tx\n").unwrap_err().column, 3);
+    }
+
+    #[test]
+    fn malformed_location() {
+        let err = parse_columns("3   4\n4   x3\n").unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (1, 2, 5));
+        assert_eq!(err.text, "x3");
+    }
 }
@@ -1,176 +1,169 @@
 use std::fmt::write;
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
-use std::collections::{HashMap, VecDeque, HashSet};
+use std::path::Path;
+
+use crate::common::grid::{to_ipos, Grid, Ipos};
+use crate::common::search::{self, PathDag};
+use crate::config::NoConfig;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day10/input.txt";
 }
 
-type Ipos = (isize, isize);
-type Upos = (usize, usize);
+const DAY: usize = 10;
+
 type Elevation = usize;
-type Trail = VecDeque<Ipos>;
 
-fn to_ipos(upos: Upos) -> Ipos {
-    (isize::try_from(upos.0).unwrap(), isize::try_from(upos.1).unwrap())
-}
+const SUMMIT: Elevation = 9;
 
 pub struct TopoMap {
-    map: HashMap<Ipos, Elevation>,
+    map: Grid<Elevation>,
     trailheads: Vec<Ipos>,
 }
 
 impl TopoMap {
 
-    pub fn from(input: &String) -> Self {
-        let mut map: HashMap<Ipos, Elevation> = HashMap::new();
-        let mut trailheads = Vec::new();
-        for (row_idx, line) in input.lines().enumerate() {
-            for (col_idx, c) in line.chars().enumerate() {
-                let elevation = c.to_digit(10).unwrap() as usize;
-                let pos = to_ipos((col_idx, row_idx));
-
-                map.entry(pos).or_insert(elevation);
-
-                if elevation == 0 {
-                    trailheads.push(pos);
-                }
-            }
-        }
-        Self {
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::parse(DAY, input, |c| c.to_digit(10).map(|d| d as Elevation))?;
+        let trailheads = map.iter()
+            .filter(|(_, elevation)| **elevation == 0)
+            .map(|(upos, _)| to_ipos(upos))
+            .collect();
+        Ok(Self {
             map,
             trailheads,
-        }
+        })
     }
 
     pub fn get_trailheads(&self) -> &Vec<Ipos> {
         &self.trailheads
     }
 
-    pub fn get_trails(&self, start: Ipos) -> Vec<Trail> {
-        let mut trails = Vec::new();
-        let start_elevation = self.elevation_at(start).unwrap();
-        let neighbors = self.get_neighbors(start);
-        let valid_neighbors = neighbors.iter().filter(|&neighbor| {
-            let elevation = self.elevation_at(*neighbor).unwrap();
-            elevation == start_elevation + 1
-        });
-        if valid_neighbors.clone().count() == 0 {
-            let end_of_trail = Trail::from([start]);
-            trails.push(end_of_trail);
-        } else {
-            for n in valid_neighbors {
-                let neighbor_trails = self.get_trails(*n);
-                for mut trail in neighbor_trails {
-                    trail.push_front(start);
-                    trails.push(trail);
-                }
-            }
-        }
-        trails
+    // every step of a trail climbs by exactly one, so every trail to a summit is a shortest path
+    pub fn get_trails(&self, start: Ipos) -> PathDag<Ipos, usize> {
+        search::all_shortest_paths(start, |pos| {
+            let elevation = self.elevation_at(*pos).unwrap();
+            self.get_neighbors(*pos).into_iter()
+                .filter(move |n| self.elevation_at(*n) == Some(elevation + 1))
+                .map(|n| (n, 1))
+        }).expect("every step costs 1")
+    }
+
+    pub fn get_summits<'a>(&'a self, trails: &'a PathDag<Ipos, usize>) -> impl Iterator<Item=&'a Ipos> {
+        trails.costs.keys().filter(|pos| self.elevation_at(**pos) == Some(SUMMIT))
     }
 
     pub fn get_neighbors(&self, pos: Ipos) -> Vec<Ipos> {
-        let mut result = Vec::new();
-        let neighbor_offsets: &[Ipos] = &[
-            (-1, 0),
-            (1, 0),
-            (0, -1),
-            (0, 1),
-        ];
-        for offset in neighbor_offsets.iter() {
-            let neighbor_pos = (
-                pos.0 + offset.0,
-                pos.1 + offset.1,
-            );
-            match self.map.get(&neighbor_pos) {
-                Some(_) =>  result.push(neighbor_pos),
-                None => (),
-            }
-        }
-        result
+        self.map.neighbors4(pos).collect()
     }
 
     pub fn elevation_at(&self, pos: Ipos) -> Option<Elevation> {
-        self.map.get(&pos).copied()
+        self.map.get(pos).copied()
     }
 }
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-pub fn _solution1(input: &String) -> usize {
-    let topo_map = TopoMap::from(&input);
+pub fn solution1_str(input: &str) -> Result<usize, ParseError> {
+    Ok(score_trailheads(&TopoMap::from(input)?))
+}
+
+fn score_trailheads(topo_map: &TopoMap) -> usize {
     let trailheads = topo_map.get_trailheads();
     let mut result = 0;
     for th in trailheads.iter() {
-        let mut distinct_endings = HashSet::new();
         let trails = topo_map.get_trails(*th);
-        for t in trails.iter() {
-            if t.len() == 10 {
-                distinct_endings.insert(t.back().unwrap());
-            }
-        }
-        result += distinct_endings.len();
+        result += topo_map.get_summits(&trails).count();
     }
     result
 }
 
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let topo_map = TopoMap::from(&input);
+pub fn solution2_str(input: &str) -> Result<usize, ParseError> {
+    Ok(rate_trailheads(&TopoMap::from(input)?))
+}
+
+fn rate_trailheads(topo_map: &TopoMap) -> usize {
     let trailheads = topo_map.get_trailheads();
     let mut result = 0;
     for th in trailheads.iter() {
         let trails = topo_map.get_trails(*th);
-        for t in trails.iter() {
-            if t.len() == 10 {
-                result += 1;
-            }
+        for summit in topo_map.get_summits(&trails) {
+            result += trails.count_paths_to(summit);
         }
     }
     result
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = TopoMap;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        TopoMap::from(input)
+    }
+
+    fn part1(topo_map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(score_trailheads(topo_map).into())
+    }
+
+    fn part2(topo_map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(rate_trailheads(topo_map).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn get_trailheads() {
         let path = common::get_test_data_path("day10/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let map = TopoMap::from(&input);
+        let map = TopoMap::from(&input).unwrap();
         assert_eq!(map.get_trailheads().len(), 9);
     }
 
     #[test]
     fn example_day10_1() {
         let path = common::get_test_data_path("day10/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 36);
     }
 
     #[test]
     fn example_day10_2() {
         let path = common::get_test_data_path("day10/case2.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 2);
     }
 
     #[test]
     fn _example_day2() {
         let path = common::get_test_data_path("day10/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 81);
     }
+
+    #[test]
+    fn bad_elevation() {
+        let err = TopoMap::from(&String::from("0123\n1.34\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('.'));
+        assert_eq!((err.line, err.column), (2, 2));
+    }
 }
@@ -1,18 +1,40 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::fmt;
 use std::collections::VecDeque;
 use rustc_hash::FxHashMap;
+use log::debug;
+use serde::Deserialize;
 
-use constants::{NUM_BLINKS_PT1, NUM_BLINKS_PT2};
+use crate::error::{parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day11/input.txt";
-    pub const NUM_BLINKS_PT1: usize = 25;
-    pub const NUM_BLINKS_PT2: usize = 75;
 }
 
-struct StoneTree {
+const DAY: usize = 11;
+
+// stones below this get their leaf counts cached. small values come up over and over, bigger
+// caches cost more to build than they save
+const LEAF_CACHE_VALUES: usize = 1000;
+
+// how many times the stones blink in each part
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub part1_blinks: usize,
+    pub part2_blinks: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { part1_blinks: 25, part2_blinks: 75 }
+    }
+}
+
+pub struct StoneTree {
     value: usize,
     children: Vec<StoneTree>,
 }
@@ -38,16 +60,20 @@ fn split_in_half(num: usize) -> (usize, usize) {
 }
 
 impl StoneTree {
-    pub fn from(input: &String) -> Self {
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        let line = input.trim();
+        if line.is_empty() {
+            return Err(ParseError::new(DAY, ParseErrorKind::Empty));
+        }
         let mut children = Vec::new();
-        for part in input.trim().split(' ') {
-            let val = part.parse::<usize>().unwrap();
+        for part in line.split(' ') {
+            let val = parse_num::<usize>(DAY, 0, line, part)?;
             children.push(Self { value: val, children: Vec::new() });
         }
-        Self {
+        Ok(Self {
             value:  usize::MAX,
             children,
-        }
+        })
     }
 
     pub fn _get_leaf_nodes<'a>(& self, t: &'a StoneTree, leaves: &mut Vec<&'a StoneTree>) -> () {
@@ -76,6 +102,11 @@ impl StoneTree {
             let processing = depth_vals.pop_front().unwrap();
             let mut current_step = processing.0;
             let processing_val = processing.1;
+            // only happens when there are no blinks at all
+            if current_step == num_steps {
+                leaf_count += 1;
+                continue;
+            }
             let mut depth_distance = num_steps - current_step;
             if let Some(v) = cache.get(&(depth_distance, processing_val)) {
                 leaf_count += v;
@@ -109,24 +140,33 @@ impl StoneTree {
         leaf_count
     }
 
-    pub fn get_leaf_count(&self, depth: usize) -> usize {
-        let leaf_vals: VecDeque<(usize, usize)> = self.get_leaf_nodes().iter().map(|t| (0, t.value)).collect();
-
+    // (blinks, stone) -> leaf count for every stone below `num_values`, in batches of ten blinks
+    // where each batch leans on the ones before it
+    pub fn build_leaf_cache(&self, num_values: usize, num_batches: usize) -> FxHashMap<(usize, usize), usize> {
         let mut cache: FxHashMap<(usize, usize), usize> = FxHashMap::default();
 
-        for factor in 0..=8 {
-            for val in 0..100000 {
+        for factor in 0..num_batches {
+            for val in 0..num_values {
                for num_steps in (factor*10 + 1)..(factor*10 + 10) {
                     let to_cache = VecDeque::from([(0, val)]);
                     let leaf_count_in_n_steps = self._get_leaf_count(to_cache, num_steps, &cache);
                     cache.entry((num_steps, val)).or_insert(leaf_count_in_n_steps);
-                    println!("[{}] iter {} complete", factor, num_steps);
+                    debug!("[{}] iter {} complete", factor, num_steps);
                }
             }
         }
+        cache
+    }
 
+    pub fn get_leaf_count_with(&self, depth: usize, cache: &FxHashMap<(usize, usize), usize>) -> usize {
+        let leaf_vals: VecDeque<(usize, usize)> = self.get_leaf_nodes().iter().map(|t| (0, t.value)).collect();
+        self._get_leaf_count(leaf_vals, depth, cache)
+    }
 
-        self._get_leaf_count(leaf_vals, depth, &cache)
+    // the cache only covers batches of blinks that fit in depth
+    pub fn get_leaf_count(&self, depth: usize) -> usize {
+        let cache = self.build_leaf_cache(LEAF_CACHE_VALUES, depth / 10);
+        self.get_leaf_count_with(depth, &cache)
     }
 
     pub fn step(&mut self) -> () {
@@ -188,15 +228,15 @@ impl StoneTree {
     }
 }
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input, &Config::default())?)
 }
 
-pub fn _solution1(input: &String) -> usize {
-    let tree = StoneTree::from(input);
+pub fn solution1_str(input: &str, config: &Config) -> Result<usize, ParseError> {
+    let tree = StoneTree::from(input)?;
 
-    tree.get_leaf_count(NUM_BLINKS_PT1)
+    Ok(tree.get_leaf_count(config.part1_blinks))
 
     //for _blink_count in 0..NUM_BLINKS_PT1 {
     //    tree.step();
@@ -205,26 +245,98 @@ pub fn _solution1(input: &String) -> usize {
     //tree.count_leaf_nodes()
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input, &Config::default())?)
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let tree = StoneTree::from(input);
+pub fn solution2_str(input: &str, config: &Config) -> Result<usize, ParseError> {
+    let tree = StoneTree::from(input)?;
+
+    Ok(tree.get_leaf_count(config.part2_blinks))
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = StoneTree;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        StoneTree::from(input)
+    }
+
+    fn part1(tree: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(tree.get_leaf_count(config.part1_blinks).into())
+    }
 
-    tree.get_leaf_count(NUM_BLINKS_PT2)
+    fn part2(tree: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(tree.get_leaf_count(config.part2_blinks).into())
+    }
 }
 
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::common::prop::{self, in_range};
+    use quickcheck::{Arbitrary, Gen};
+
+    #[derive(Debug, Clone)]
+    struct Blinks {
+        stones: String,
+        depth: usize,
+    }
+
+    // few enough stones and blinks that the whole tree fits in memory
+    impl Arbitrary for Blinks {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let num_stones = in_range(g, 1..=4);
+            let stones: Vec<String> = (0..num_stones).map(|_| in_range(g, 0..=2000).to_string()).collect();
+            Self { stones: stones.join(" "), depth: in_range(g, 1..=15) }
+        }
+    }
+
+    fn expand_and_count(blinks: &Blinks) -> usize {
+        let mut tree = StoneTree::from(&blinks.stones).unwrap();
+        for _ in 0..blinks.depth {
+            tree.step();
+        }
+        tree.count_leaf_nodes()
+    }
 
     #[test]
     fn test_example_day11_1() {
         let path = common::get_test_data_path("day11/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 55312);
     }
+
+    #[test]
+    fn no_blinks() {
+        let tree = StoneTree::from("125 17").unwrap();
+        assert_eq!(tree.get_leaf_count(0), 2);
+        assert_eq!(tree.get_leaf_count(1), 3);
+    }
+
+    #[test]
+    fn leaf_count_matches_expansion() {
+        let tree = StoneTree::from(&String::from("0")).unwrap();
+        let no_cache = FxHashMap::default();
+        let small_cache = tree.build_leaf_cache(100, 2);
+        prop::check(|blinks: &Blinks| {
+            let tree = StoneTree::from(&blinks.stones).unwrap();
+            let expected = expand_and_count(blinks);
+            tree.get_leaf_count_with(blinks.depth, &no_cache) == expected
+                && tree.get_leaf_count_with(blinks.depth, &small_cache) == expected
+        });
+    }
+
+    #[test]
+    fn bad_stone() {
+        let err = StoneTree::from(&String::from("125 1x7\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!((err.line, err.column), (1, 5));
+        assert_eq!(err.text, "1x7");
+    }
 }
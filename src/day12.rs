@@ -1,28 +1,25 @@
+use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
-use rustc_hash::{FxHashMap, FxHashSet};
+use std::path::Path;
+use rustc_hash::FxHashSet;
+
+use crate::common::grid::{offset, to_ipos, Grid, Ipos, OFFSETS_4};
+use crate::config::NoConfig;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day12/input.txt";
 }
 
-#[derive(Hash, PartialEq, Eq, Clone)]
-pub struct Icoord {
-    x: isize,
-    y: isize,
-}
-
-impl Icoord {
-    pub fn new(x: isize, y: isize) -> Self {
-        Self { x, y }
-    }
-}
+const DAY: usize = 12;
 
 type PlantType = char;
 
 pub struct Region {
     plant_type: PlantType,
-    plots: FxHashSet<Icoord>,
+    plots: FxHashSet<Ipos>,
 }
 
 impl Region {
@@ -34,7 +31,7 @@ impl Region {
         let mut permiter = 0;
 
         for region_coord in self.plots.iter() {
-            for n in get_neighboring_coords(region_coord) {
+            for n in OFFSETS_4.iter().map(|o| offset(*region_coord, *o)) {
                 match self.plots.get(&n) {
                     Some(_) => (),
                     None => permiter += 1,
@@ -52,41 +49,41 @@ impl Region {
         self.get_area() * self.get_num_sides()
     }
 
-    pub fn _get_min_max_corners(&self, plots: &FxHashSet<Icoord>) -> (Icoord, Icoord) {
+    pub fn _get_min_max_corners(&self, plots: &FxHashSet<Ipos>) -> (Ipos, Ipos) {
         let (mut min_x, mut max_x) = (isize::MAX, isize::MIN);
         let (mut min_y, mut max_y) = (isize::MAX, isize::MIN);
 
-        for plot in plots.iter() {
-            if plot.x < min_x {
-                min_x = plot.x;
+        for &(x, y) in plots.iter() {
+            if x < min_x {
+                min_x = x;
             }
-            if plot.x > max_x {
-                max_x = plot.x;
+            if x > max_x {
+                max_x = x;
             }
-            if plot.y < min_y {
-                min_y = plot.y;
+            if y < min_y {
+                min_y = y;
             }
-            if plot.y > max_y {
-                max_y = plot.y;
+            if y > max_y {
+                max_y = y;
             }
         }
-        (Icoord { x: min_x, y: min_y }, Icoord { x: max_x, y: max_y })
+        ((min_x, min_y), (max_x, max_y))
     }
 
-    pub fn _get_upper_sides(&self, plots: &FxHashSet<Icoord>) -> usize {
+    pub fn _get_upper_sides(&self, plots: &FxHashSet<Ipos>) -> usize {
         let (min_corner, max_corner) = self._get_min_max_corners(&plots);
         let mut count = 0;
 
-        for y in min_corner.y..=max_corner.y {
+        for y in min_corner.1..=max_corner.1 {
             // track per y line
             let mut tracking_side = false;
 
-            for x in min_corner.x..=max_corner.x {
-                let c = Icoord { x, y };
+            for x in min_corner.0..=max_corner.0 {
+                let c = (x, y);
                 match plots.get(&c) {
                     Some(_) => {
                         if tracking_side {
-                            if plots.contains(&get_up_coord(&c)) {
+                            if plots.contains(&offset(c, (0, 1))) {
                                 // tracking, found above
                                 // stop tracking
                                 tracking_side = false
@@ -96,7 +93,7 @@ impl Region {
                             }
 
                         } else {
-                            if plots.contains(&get_up_coord(&c)) {
+                            if plots.contains(&offset(c, (0, 1))) {
                                 // not tracking, found above
                                 // do nothing
                             } else {
@@ -120,13 +117,13 @@ impl Region {
         count
     }
 
-    pub fn transform_plots(&self, transform: (isize, isize, isize, isize)) -> FxHashSet<Icoord> {
+    pub fn transform_plots(&self, transform: (isize, isize, isize, isize)) -> FxHashSet<Ipos> {
         let mut result = FxHashSet::default();
-        for c in self.plots.iter() {
-            let transformed_c = Icoord {
-                x: transform.0*c.x + transform.1*c.y,
-                y: transform.2*c.x + transform.3*c.y,
-            };
+        for &(x, y) in self.plots.iter() {
+            let transformed_c = (
+                transform.0*x + transform.1*y,
+                transform.2*x + transform.3*y,
+            );
             result.insert(transformed_c);
         }
         result
@@ -163,97 +160,46 @@ impl Region {
 }
 
 pub struct Garden {
-    plots: FxHashMap<Icoord, PlantType>,
-    width: isize,
-    height: isize,
-}
-
-fn get_up_coord(c: &Icoord) -> Icoord {
-    Icoord {
-        x: c.x + 0,
-        y: c.y + 1,
-    }
-}
-
-fn get_neighboring_coords(c: &Icoord) -> Vec<Icoord> {
-    let offsets = [
-        Icoord::new(0, 1),
-        Icoord::new(0, -1),
-        Icoord::new(1, 0),
-        Icoord::new(-1, 0),
-    ];
-
-    offsets.map(|o| {
-        Icoord {
-            x: c.x + o.x,
-            y: c.y + o.y,
-        }
-    }).into_iter().collect()
+    plots: Grid<PlantType>,
 }
 
 impl Garden {
 
-    pub fn from(input: &String) -> Self {
-        let mut plots = FxHashMap::default();
-
-        let height = isize::try_from(
-            input.lines().count()
-        ).unwrap();
-
-        let width = isize::try_from(
-            input.lines().next().unwrap().len()
-        ).unwrap();
-
-        for (y, line) in input.lines().enumerate() {
-            let y = isize::try_from(y).unwrap();
-
-            for (x, c) in line.chars().enumerate() {
-
-                let x = isize::try_from(x).unwrap();
-                let coord = Icoord { x, y };
-
-                plots.entry(coord).or_insert(c);
-            }
-        }
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        let plots = Grid::parse(DAY, input, Some)?;
+        Ok(Self { plots })
+    }
 
-        Self {
-            plots,
-            width,
-            height,
-        }
+    fn plot_at(&self, coord: Ipos) -> Option<&PlantType> {
+        self.plots.get(coord)
     }
 
-    pub fn _get_matching_neighbors(&self, coord: &Icoord) -> Vec<Icoord> {
+    pub fn _get_matching_neighbors(&self, coord: Ipos) -> Vec<Ipos> {
         let mut result = vec![];
 
-        let plot = self.plots.get(coord).unwrap();
+        let plot = self.plot_at(coord).unwrap();
 
-        for neighbor_coord in get_neighboring_coords(coord) {
-            match self.plots.get(&neighbor_coord) {
-                Some(neighbor) => {
-                    if neighbor == plot {
-                        result.push(neighbor_coord)
-                    }
-                },
-                None => (),
+        for neighbor_coord in self.plots.neighbors4(coord) {
+            if self.plot_at(neighbor_coord) == Some(plot) {
+                result.push(neighbor_coord)
             }
         }
 
         result
     }
 
-    pub fn _get_region_plots(&self, coord: &Icoord) -> Vec<Icoord> {
-        let mut in_region: FxHashSet<Icoord> = FxHashSet::default();
-        let mut to_examine: Vec<Icoord> = vec![coord.clone()];
+    pub fn _get_region_plots(&self, coord: Ipos) -> Vec<Ipos> {
+        let mut in_region: FxHashSet<Ipos> = FxHashSet::default();
+        let mut to_examine: Vec<Ipos> = vec![coord];
 
         while to_examine.len() != 0 {
             let coord = to_examine.pop().unwrap();
-            in_region.insert(coord.clone());
-            let neighbor_coords = self._get_matching_neighbors(&coord);
+            in_region.insert(coord);
+            let neighbor_coords = self._get_matching_neighbors(coord);
 
             for nc in neighbor_coords {
                 if !in_region.contains(&nc) {
-                    to_examine.push(nc.clone());
+                    to_examine.push(nc);
                     in_region.insert(nc);
                 }
             }
@@ -264,36 +210,43 @@ impl Garden {
 
     pub fn get_regions(&self) -> Vec<Region> {
         let mut result: Vec<Region> = vec![];
-        let mut in_region: FxHashSet<Icoord> = FxHashSet::default();
-
-        for x in 0..self.width {
-            for y in 0..self.height {
-                let coord = Icoord { x, y, };
-                if !in_region.contains(&coord) {
-                    in_region.insert(coord.clone());
-                    let plots = self._get_region_plots(&coord);
-                    for region_coord in plots.iter() {
-                        in_region.insert(region_coord.clone());
-                    }
-                    let region = Region {
-                        plots: plots.into_iter().collect(),
-                        plant_type: *self.plots.get(&coord).unwrap(),
-                    };
-                    result.push(region);
+        let mut in_region: FxHashSet<Ipos> = FxHashSet::default();
+
+        for pos in self.plots.positions() {
+            let coord = to_ipos(pos);
+            if !in_region.contains(&coord) {
+                in_region.insert(coord);
+                let plots = self._get_region_plots(coord);
+                for region_coord in plots.iter() {
+                    in_region.insert(*region_coord);
                 }
+                let region = Region {
+                    plots: plots.into_iter().collect(),
+                    plant_type: self.plots[pos],
+                };
+                result.push(region);
             }
         }
         result
     }
 }
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+impl fmt::Display for Garden {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.plots)
+    }
+}
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-pub fn _solution1(input: &String) -> usize {
-    let garden = Garden::from(&input);
+pub fn solution1_str(input: &str) -> Result<usize, ParseError> {
+    Ok(fencing_cost(&Garden::from(input)?))
+}
+
+fn fencing_cost(garden: &Garden) -> usize {
     let mut cost = 0;
     for region in garden.get_regions().iter() {
         cost += region.get_cost()
@@ -301,13 +254,16 @@ pub fn _solution1(input: &String) -> usize {
     cost
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
+}
+
+pub fn solution2_str(input: &str) -> Result<usize, ParseError> {
+    Ok(reduced_fencing_cost(&Garden::from(input)?))
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let garden = Garden::from(&input);
+fn reduced_fencing_cost(garden: &Garden) -> usize {
     let mut cost = 0;
     for region in garden.get_regions().iter() {
         cost += region.get_reduced_cost();
@@ -315,22 +271,49 @@ pub fn _solution2(input: &String) -> usize {
     cost
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Garden;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Garden::from(input)
+    }
+
+    fn part1(garden: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(fencing_cost(garden).into())
+    }
+
+    fn part2(garden: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(reduced_fencing_cost(garden).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn test_example_day12_1() {
         let path = common::get_test_data_path("day12/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 1930);
     }
 
     #[test]
     fn test_example_day12_2() {
         let path = common::get_test_data_path("day12/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 1206);
     }
+
+    #[test]
+    fn test_ragged_garden() {
+        let err = Garden::from(&String::from("AAAA\nBBCD\nBBC\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::RaggedRow { expected: 4, found: 3 });
+        assert_eq!(err.line, 3);
+    }
 }
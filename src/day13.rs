@@ -1,13 +1,39 @@
 use std::cmp::{min, max};
-use std::{fs, thread};
-use std::path::PathBuf;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use rayon::prelude::*;
 use regex::Regex;
+use log::{debug, trace};
+use serde::Deserialize;
+
+use crate::config;
+use crate::error::{column_of, parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day13/input.txt";
-    pub const A_BTN_COST: isize = 3;
-    pub const B_BTN_COST: isize = 1;
-    pub const MAX_PRESSES: isize = 100;
+}
+
+const DAY: usize = 13;
+
+// what each button press costs in tokens, how often part 1 lets either button be pressed,
+// and how far part 2 moves every prize
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "config::non_negative")]
+    pub a_cost: isize,
+    #[serde(deserialize_with = "config::non_negative")]
+    pub b_cost: isize,
+    pub max_presses: isize,
+    pub prize_offset: isize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { a_cost: 3, b_cost: 1, max_presses: 100, prize_offset: 10000000000000 }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -34,14 +60,12 @@ pub struct SolutionState<'a> {
     num_a_presses: isize,
     num_b_presses: isize,
     conf: &'a ClawConf,
+    config: &'a Config,
 }
 
 impl SolutionState<'_> {
-    const A_COST: isize = constants::A_BTN_COST;
-    const B_COST: isize = constants::B_BTN_COST;
-
     pub fn cost(&self) -> isize {
-        Self::A_COST * self.num_a_presses + Self::B_COST * self.num_b_presses
+        self.config.a_cost * self.num_a_presses + self.config.b_cost * self.num_b_presses
     }
 
     pub fn loc(&self) -> Loc {
@@ -90,81 +114,102 @@ impl SolutionState<'_> {
         }
     }
 
+    // neither button can be pressed a negative number of times, or more than the machine allows
+    pub fn presses_within(&self, max_presses: isize) -> bool {
+        (0..=max_presses).contains(&self.num_a_presses) && (0..=max_presses).contains(&self.num_b_presses)
+    }
+
     pub fn beyond_target(&self) -> bool {
         let loc = self.loc();
         loc.x > self.conf.prize_location.x || loc.y > self.conf.prize_location.y
     }
 }
 
-pub fn parse_offset(line: &str) -> Offset {
+// line numbers are filled in by parse_conf
+pub fn parse_offset(line: &str) -> Result<Offset, ParseError> {
     let rx = Regex::new(r"X\+(\d+), Y\+(\d+)").expect("invalid regex");
 
-    let (_, [x_offset, y_offset]) = rx.captures_iter(&line).map(|c| c.extract()).next().unwrap();
-    let x = x_offset.parse::<isize>().unwrap();
-    let y = y_offset.parse::<isize>().unwrap();
+    let (_, [x_offset, y_offset]) = rx.captures_iter(line).map(|c| c.extract()).next()
+        .ok_or_else(|| ParseError::new(DAY, ParseErrorKind::UnexpectedToken("\"X+<n>, Y+<n>\"")).with_text(line))?;
+    let x = parse_num::<isize>(DAY, 0, line, x_offset)?;
+    let y = parse_num::<isize>(DAY, 0, line, y_offset)?;
+    // the solver divides by the gcd of the two buttons' offsets, which has to be above 0
+    for (offset, token) in [(x, x_offset), (y, y_offset)] {
+        if offset == 0 {
+            return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedToken("an offset above 0"))
+                .at(0, column_of(line, token))
+                .with_text(line));
+        }
+    }
 
-    Offset { x, y }
+    Ok(Offset { x, y })
 }
 
-pub fn parse_loc(line: &str) -> Loc {
+pub fn parse_loc(line: &str) -> Result<Loc, ParseError> {
     let rx = Regex::new(r"X=(\d+), Y=(\d+)").expect("invalid regex");
 
-    let (_, [x_loc, y_loc]) = rx.captures_iter(&line).map(|c| c.extract()).next().unwrap();
-    let x = x_loc.parse::<isize>().unwrap();
-    let y = y_loc.parse::<isize>().unwrap();
+    let (_, [x_loc, y_loc]) = rx.captures_iter(line).map(|c| c.extract()).next()
+        .ok_or_else(|| ParseError::new(DAY, ParseErrorKind::UnexpectedToken("\"X=<n>, Y=<n>\"")).with_text(line))?;
+    let x = parse_num::<isize>(DAY, 0, line, x_loc)?;
+    let y = parse_num::<isize>(DAY, 0, line, y_loc)?;
 
-    Loc { x, y }
+    Ok(Loc { x, y })
 }
 
-pub fn parse_conf(input: &String) -> Vec<ClawConf> {
-    let mut lines = input.lines();
+pub fn parse_conf(input: &str) -> Result<Vec<ClawConf>, ParseError> {
+    let mut lines = input.lines().enumerate();
     let mut result = vec![];
+    let mut last_idx = 0;
+
+    let mut next_line = |what: &'static str| {
+        match lines.next() {
+            Some((idx, line)) => {
+                last_idx = idx;
+                Ok((idx, line))
+            },
+            None => Err(ParseError::new(DAY, ParseErrorKind::MissingToken(what)).on_line(last_idx + 1)),
+        }
+    };
 
     loop {
 
-        let btn_a_line = lines.next().unwrap();
-        let btn_b_line = lines.next().unwrap();
-        let prize_loc_line = lines.next().unwrap();
+        let (a_idx, btn_a_line) = next_line("button A line")?;
+        let (b_idx, btn_b_line) = next_line("button B line")?;
+        let (prize_idx, prize_loc_line) = next_line("prize line")?;
 
         result.push(ClawConf {
-            a_offset: parse_offset(btn_a_line),
-            b_offset: parse_offset(btn_b_line),
-            prize_location: parse_loc(prize_loc_line),
+            a_offset: parse_offset(btn_a_line).map_err(|e| e.on_line(a_idx))?,
+            b_offset: parse_offset(btn_b_line).map_err(|e| e.on_line(b_idx))?,
+            prize_location: parse_loc(prize_loc_line).map_err(|e| e.on_line(prize_idx))?,
         });
 
-        if let None = lines.next() {
+        if next_line("blank line").is_err() {
             break;
         }
     }
 
-    result
+    Ok(result)
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input, &Config::default())?)
 }
 
-pub fn stop_search(current: &mut Box<SolutionState>, best: &mut Box<SolutionState>, max_press_count: isize) -> bool {
-    if current.num_a_presses > max_press_count || current.num_b_presses > max_press_count {
-        true
-    } else if current.beyond_target() {
-        true
-    } else if current.cost() >= best.cost() {
-        true
-    } else {
-        false
-    }
+pub fn stop_search(current: &mut Box<SolutionState>, best: &mut Option<SolutionState>, max_press_count: isize) -> bool {
+    current.num_a_presses > max_press_count
+        || current.num_b_presses > max_press_count
+        || current.beyond_target()
+        || best.as_ref().is_some_and(|b| current.cost() >= b.cost())
 }
 
-pub fn update_if_at_target(current: &mut Box<SolutionState>, best: &mut Box<SolutionState>) {
+pub fn update_if_at_target<'a>(current: &mut Box<SolutionState<'a>>, best: &mut Option<SolutionState<'a>>) {
     //println!("Target check");
     if current.at_target() {
         //println!("\tPASSED");
-        if current.cost() < best.cost() {
-            println!("\tCOST UPDATE");
-            best.num_a_presses = current.num_a_presses;
-            best.num_b_presses = current.num_b_presses;
+        if best.as_ref().is_none_or(|b| current.cost() < b.cost()) {
+            trace!("\tCOST UPDATE");
+            *best = Some((**current).clone());
         } else {
             //println!("\tNO COST UPDATE")
         }
@@ -211,7 +256,7 @@ pub fn solve(a_offset: isize, b_offset: isize, prize_loc: isize) -> Option<(isiz
 //        && _solution_exists(a_offset.y, b_offset.y, prize_loc.y)
 //}
 
-pub fn _find_min_winning_cost(current: &mut Box<SolutionState>, best: &mut Box<SolutionState>, max_press_count: isize) {
+pub fn _find_min_winning_cost<'a>(current: &mut Box<SolutionState<'a>>, best: &mut Option<SolutionState<'a>>, max_press_count: isize) {
     for b_press in 0..max_press_count {
         if stop_search(current, best, max_press_count) {
             break;
@@ -235,23 +280,20 @@ pub fn find_valid_k_range(x_0: isize, x_scale: isize, y_0: isize, y_scale: isize
     smaller_k..=bigger_k
 }
 
-pub fn find_min_winning_cost(conf: &ClawConf, max_presses: isize) -> Option<isize> {
-    println!("{:#?}", conf);
-    let mut best = Box::new(SolutionState {
-        conf,
-        num_b_presses: max_presses + 1,
-        num_a_presses: max_presses + 1,
-    });
-    
+pub fn find_min_winning_cost(conf: &ClawConf, config: &Config, max_presses: isize) -> Option<isize> {
+    debug!("{:#?}", conf);
+    let mut best: Option<SolutionState> = None;
+
     let mut init = Box::new(SolutionState {
         conf,
+        config,
         num_b_presses: 0,
         num_a_presses: 0
     });
 
     match solve(conf.a_offset.x, conf.b_offset.x, conf.prize_location.x) {
         Some((gcd_x, a_moves_x, b_moves_x)) => {
-            println!("found X solution");
+            trace!("found X solution");
             //println!(
             //    "GCD({}, {}) = {}",
             //    conf.a_offset.x,
@@ -276,7 +318,7 @@ pub fn find_min_winning_cost(conf: &ClawConf, max_presses: isize) -> Option<isiz
             );
             match solve(conf.a_offset.y, conf.b_offset.y, conf.prize_location.y) {
                 Some((gcd_y, a_moves_y, b_moves_y)) => {
-                    println!("Y solution found");
+                    trace!("Y solution found");
                     let valid_k_y = find_valid_k_range(
                         a_moves_y,
                         conf.b_offset.y/gcd_y,
@@ -296,7 +338,9 @@ pub fn find_min_winning_cost(conf: &ClawConf, max_presses: isize) -> Option<isiz
                             init.num_b_presses = b_moves_x - k*(conf.a_offset.x/gcd_x);
                             //println!("\t(X) a presses: {}", init.num_a_presses);
                             //println!("\t(X) b presses: {}", init.num_b_presses);
-                            update_if_at_target(&mut init, &mut best);
+                            if init.presses_within(max_presses) {
+                                update_if_at_target(&mut init, &mut best);
+                            }
                         }
                     } else {
                         for k in valid_k_y {
@@ -305,18 +349,20 @@ pub fn find_min_winning_cost(conf: &ClawConf, max_presses: isize) -> Option<isiz
                             init.num_b_presses = b_moves_y - k*(conf.a_offset.y/gcd_y);
                             //println!("\t(Y) a presses: {}", init.num_a_presses);
                             //println!("\t(Y) b presses: {}", init.num_b_presses);
-                            update_if_at_target(&mut init, &mut best);
+                            if init.presses_within(max_presses) {
+                                update_if_at_target(&mut init, &mut best);
+                            }
                         }
 
                     }
                 },
                 None => {
-                    println!("no Y solution found");
+                    trace!("no Y solution found");
                 },
             }
         },
         None => {
-            println!("no X solution found");
+            trace!("no X solution found");
         },
     }
 
@@ -324,67 +370,130 @@ pub fn find_min_winning_cost(conf: &ClawConf, max_presses: isize) -> Option<isiz
     //    //_find_min_winning_cost(&mut init, &mut best, max_presses);
     //}
 
-    if best.at_target() {
-        Some(best.cost())
-    } else {
-        None
-    }
+    best.map(|b| b.cost())
+}
+
+pub fn solution1_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(total_winning_cost(&parse_conf(input)?, config))
 }
 
-pub fn _solution1(input: &String) -> isize {
-    let confs = parse_conf(input);
+fn total_winning_cost(confs: &[ClawConf], config: &Config) -> isize {
     let mut token_count = 0;
     for c in confs.iter() {
-        match find_min_winning_cost(c, constants::MAX_PRESSES) {
-            Some(cost) => token_count += cost,
-            None => (),
+        if let Some(cost) = find_min_winning_cost(c, config, config.max_presses) {
+            token_count += cost;
         }
     }
     token_count
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input, &Config::default())?)
 }
 
-pub fn _solution2(input: &String) -> isize {
-    let offset_adjustment = 10000000000000;
+pub fn solution2_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(total_far_winning_cost(parse_conf(input)?, config))
+}
+
+fn total_far_winning_cost(mut confs: Vec<ClawConf>, config: &Config) -> isize {
+    let offset_adjustment = config.prize_offset;
     let max_steps = offset_adjustment;
-    let mut confs = parse_conf(input);
     for c in confs.iter_mut() {
         c.prize_location.x += offset_adjustment;
         c.prize_location.y += offset_adjustment;
     }
-    let chunk_size = (confs.len() + 15) / 16;
-    let mut handles = vec![];
-    for conf_chunk in confs.chunks(chunk_size) {
-        let chunk = conf_chunk.to_vec();
-        let mut token_count = 0;
-        let handle = thread::spawn(move || {
-            for c in chunk {
-                match find_min_winning_cost(&c, max_steps) {
-                    Some(cost) => token_count += cost,
-                    None => (),
-                }
-            }
-            token_count
-        });
-        handles.push(handle);
+    confs.par_iter()
+        .filter_map(|c| find_min_winning_cost(c, config, max_steps))
+        .sum()
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<ClawConf>;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_conf(input)
+    }
+
+    fn part1(confs: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(total_winning_cost(confs, config).into())
+    }
+
+    fn part2(confs: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(total_far_winning_cost(confs.clone(), config).into())
     }
-    handles.into_iter().map(|h| h.join().unwrap()).sum()
 }
 
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::common::prop::{self, in_range};
+    use quickcheck::{Arbitrary, Gen};
+
+    // half the prizes are reachable by construction, some only with more than max_presses
+    impl Arbitrary for ClawConf {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let offset = |g: &mut Gen| Offset {
+                x: isize::try_from(in_range(g, 1..=20)).unwrap(),
+                y: isize::try_from(in_range(g, 1..=20)).unwrap(),
+            };
+            let a_offset = offset(g);
+            let b_offset = offset(g);
+            let prize_location = if bool::arbitrary(g) {
+                let a = isize::try_from(in_range(g, 0..=120)).unwrap();
+                let b = isize::try_from(in_range(g, 0..=120)).unwrap();
+                Loc { x: a*a_offset.x + b*b_offset.x, y: a*a_offset.y + b*b_offset.y }
+            } else {
+                Loc {
+                    x: isize::try_from(in_range(g, 0..=2000)).unwrap(),
+                    y: isize::try_from(in_range(g, 0..=2000)).unwrap(),
+                }
+            };
+            ClawConf { a_offset, b_offset, prize_location }
+        }
+    }
+
+    fn brute_force_cost(conf: &ClawConf, config: &Config) -> Option<isize> {
+        (0..=config.max_presses)
+            .flat_map(|a| (0..=config.max_presses).map(move |b| (a, b)))
+            .filter(|(a, b)| {
+                a*conf.a_offset.x + b*conf.b_offset.x == conf.prize_location.x
+                    && a*conf.a_offset.y + b*conf.b_offset.y == conf.prize_location.y
+            })
+            .map(|(a, b)| a*config.a_cost + b*config.b_cost)
+            .min()
+    }
+
+    #[test]
+    fn min_winning_cost_matches_brute_force() {
+        let config = Config::default();
+        prop::check(|conf: &ClawConf| {
+            find_min_winning_cost(conf, &config, config.max_presses) == brute_force_cost(conf, &config)
+        });
+    }
+
+    #[test]
+    fn min_winning_cost_out_of_reach() {
+        // the prize is only reachable with more than max_presses presses
+        let conf = ClawConf {
+            a_offset: Offset { x: 19, y: 2 },
+            b_offset: Offset { x: 1, y: 3 },
+            prize_location: Loc { x: 2020, y: 505 },
+        };
+        let config = Config::default();
+        assert_eq!(find_min_winning_cost(&conf, &config, config.max_presses), None);
+        assert_eq!(brute_force_cost(&conf, &config), None);
+    }
 
     #[test]
     fn example_day_13_1_parse_input() {
         let path = common::get_test_data_path("day13/case1.txt").unwrap();
         let input =  fs::read_to_string(path).unwrap();
-        let configs = parse_conf(&input);
+        let configs = parse_conf(&input).unwrap();
 
         assert_eq!(configs.len(), 4, "parsed all configs");
         let c = &configs[0];
@@ -396,14 +505,38 @@ mod tests  {
     #[test]
     fn example_day_13_1() {
         let path = common::get_test_data_path("day13/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 480);
     }
 
     #[test]
     fn example_day_13_2() {
         let path = common::get_test_data_path("day13/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 480);
     }
+
+    #[test]
+    fn example_day_13_bad_prize() {
+        let input = String::from("Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=8400 Y=5400\n");
+        let err = parse_conf(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("\"X=<n>, Y=<n>\""));
+        assert_eq!(err.line, 3);
+    }
+
+    #[test]
+    fn example_day_13_zero_offset() {
+        let input = String::from("Button A: X+94, Y+34\nButton B: X+22, Y+0\nPrize: X=8400, Y=5400\n");
+        let err = parse_conf(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("an offset above 0"));
+        assert_eq!((err.line, err.column), (2, 19));
+    }
+
+    #[test]
+    fn example_day_13_truncated() {
+        let input = String::from("Button A: X+94, Y+34\nButton B: X+22, Y+67\n");
+        let err = parse_conf(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("prize line"));
+        assert_eq!(err.line, 3);
+    }
 }
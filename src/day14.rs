@@ -1,31 +1,65 @@
-use std::fs;
-use std::path::PathBuf;
+use std::error::Error;
+use std::io::BufRead;
+use std::path::Path;
 use regex::Regex;
 use rustc_hash::FxHashMap;
 use std::cmp::min;
+use log::{debug, info, trace};
+use serde::Deserialize;
+
+use crate::config;
+use crate::error::{parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
+use crate::stream::{self, StreamError};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day14/input.txt";
 }
 
+const DAY: usize = 14;
+
+// the size of the lobby, and how long part 1 lets the robots walk
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "config::positive")]
+    pub width: isize,
+    #[serde(deserialize_with = "config::positive")]
+    pub height: isize,
+    pub seconds: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { width: 101, height: 103, seconds: 100 }
+    }
+}
+
+impl Config {
+    fn dimensions(&self) -> Dimensions {
+        Dimensions { x: self.width, y: self.height }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub struct Location {
     x: isize,
     y: isize,
 }
 
+#[derive(Clone, Copy)]
 pub struct Dimensions {
     pub x: isize,
     pub y: isize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Velocity {
     x: isize,
     y: isize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Robot {
     loc: Location,
     vel: Velocity,
@@ -161,7 +195,7 @@ impl Lobby {
                 }
             }
         }
-        println!("{:#?}", result);
+        debug!("quadrant scores: {:?}", result);
         result
     }
 
@@ -184,47 +218,19 @@ impl Lobby {
         self.robots.push(robot);
     }
 
-    pub fn print_quads(&self) {
+    pub fn render(&self) -> String {
         let robot_map = self._get_loc_robot_counts();
-        let all_bounds = self.get_quadrant_bounds();
+        let mut out = String::new();
         for y in 0..self.dimensions.y {
             for x in 0..self.dimensions.x {
-                let l = Location { x, y };
-                let mut found = false;
-                let mut in_quad = false;
-                for q in QUADRANTS.clone().into_iter() {
-                    if in_bounds(&l, all_bounds.get(&q).unwrap()) {
-                        in_quad = true;
-                        if robot_map.contains_key(&l) {
-                            found = true;
-                            print!("{}", robot_map.get(&l).unwrap());
-                        }
-                    }
-                }
-                if !in_quad {
-                    print!(" ");
-                } else if !found {
-                    print!(".");
+                match robot_map.get(&Location { x, y }) {
+                    Some(count) => out.push_str(&count.to_string()),
+                    None => out.push('.'),
                 }
             }
-            println!("");
-        }
-
-    }
-
-    pub fn print(&self) {
-        let robot_map = self._get_loc_robot_counts();
-        for y in 0..self.dimensions.y {
-            for x in 0..self.dimensions.x {
-                let l = Location { x, y };
-                if robot_map.contains_key(&l) {
-                    print!("{}", robot_map.get(&l).unwrap());
-                } else {
-                    print!(".");
-                }
-            }
-            println!("");
+            out.push('\n');
         }
+        out
     }
 
     pub fn compute_connectivity(&self) -> f64 {
@@ -250,60 +256,85 @@ impl Lobby {
     }
 }
 
-pub fn parse_input(input: &String) -> Vec<Robot> {
+fn robot_regex() -> Regex {
+    Regex::new(r"p=(\d+),(\d+)\s+v=(-?\d+),(-?\d+)").expect("invalid regex")
+}
+
+// the robots on one line, blank lines have none
+fn parse_robots(rx: &Regex, line_idx: usize, line: &str) -> Result<Vec<Robot>, ParseError> {
     let mut robots = vec![];
-    let rx = Regex::new(r"p=(\d+),(\d+)\s+v=(-?\d+),(-?\d+)").expect("invalid regex");
-
-    for line in input.lines() {
-        //println!("LINE: {}", line);
-        for (_, [x_pos, y_pos, x_vel, y_vel]) in rx.captures_iter(&line).map(|c| c.extract()) {
-            //println!("\tCAPTURE: {} {} {} {}", x_pos, y_pos, x_vel, y_vel);
-            let x_pos = x_pos.parse::<isize>().unwrap();
-            let y_pos = y_pos.parse::<isize>().unwrap();
-
-            let x_vel = x_vel.parse::<isize>().unwrap();
-            let y_vel = y_vel.parse::<isize>().unwrap();
-
-            robots.push(Robot {
-                loc: Location {
-                    x: x_pos,
-                    y: y_pos,
-                },
-                vel: Velocity {
-                    x: x_vel,
-                    y: y_vel,
-                }
-            });
-        }
+    //println!("LINE: {}", line);
+    if !line.trim().is_empty() && !rx.is_match(line) {
+        return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedToken("\"p=<x>,<y> v=<dx>,<dy>\""))
+            .on_line(line_idx)
+            .with_text(line));
     }
-    robots
+    for (_, [x_pos, y_pos, x_vel, y_vel]) in rx.captures_iter(line).map(|c| c.extract()) {
+        //println!("\tCAPTURE: {} {} {} {}", x_pos, y_pos, x_vel, y_vel);
+        let x_pos = parse_num::<isize>(DAY, line_idx, line, x_pos)?;
+        let y_pos = parse_num::<isize>(DAY, line_idx, line, y_pos)?;
+
+        let x_vel = parse_num::<isize>(DAY, line_idx, line, x_vel)?;
+        let y_vel = parse_num::<isize>(DAY, line_idx, line, y_vel)?;
+
+        robots.push(Robot {
+            loc: Location {
+                x: x_pos,
+                y: y_pos,
+            },
+            vel: Velocity {
+                x: x_vel,
+                y: y_vel,
+            }
+        });
+    }
+    Ok(robots)
 }
 
-pub fn solution1(path: &PathBuf, map_dimensions: Dimensions) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input, map_dimensions)
+pub fn parse_input(input: &str) -> Result<Vec<Robot>, ParseError> {
+    let rx = robot_regex();
+    let mut robots = vec![];
+    for (line_idx, line) in input.lines().enumerate() {
+        robots.extend(parse_robots(&rx, line_idx, line)?);
+    }
+    Ok(robots)
 }
 
-pub fn _solution1(input: &String, map_dimensions: Dimensions) -> isize {
-    let mut l = Lobby::new(map_dimensions);
-    for robot in parse_input(&input).into_iter() {
+pub fn read_robots<R: BufRead>(reader: R) -> Result<Vec<Robot>, StreamError> {
+    let rx = robot_regex();
+    let mut robots = vec![];
+    for line_robots in stream::records(reader, |line_idx, line| parse_robots(&rx, line_idx, line)) {
+        robots.extend(line_robots?);
+    }
+    Ok(robots)
+}
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    Ok(safety_factor(read_robots(stream::open(path)?)?, &Config::default()))
+}
+
+pub fn solution1_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(safety_factor(parse_input(input)?, config))
+}
+
+fn safety_factor(robots: Vec<Robot>, config: &Config) -> isize {
+    let mut l = Lobby::new(config.dimensions());
+    for robot in robots.into_iter() {
         l.add_robot(robot);
     }
     //println!("###########################");
     //l.print();
     //println!("###########################");
-    for _s in 0..100 {
+    for _s in 0..config.seconds {
         l.step_all_robots();
         //println!("###########################");
         //l.print();
         //println!("###########################");
     }
-    println!("###########################");
-    l.print();
-    println!("###########################");
+    debug!("after {} seconds:\n{}", config.seconds, l.render());
     let mut result = 1;
     let mut scores = l.get_quadrant_scores();
-    println!("{:#?}", l.robots);
+    trace!("{:?}", l.robots);
     for q in QUADRANTS.clone().into_iter() {
         let score = scores.entry(q).or_insert(1);
         result *= *score;
@@ -312,28 +343,46 @@ pub fn _solution1(input: &String, map_dimensions: Dimensions) -> isize {
     result
 }
 
-pub fn solution2(path: &PathBuf, map_dimensions: Dimensions) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input, map_dimensions)
+// the lobby rendered at every second from 0 on, it never runs out so take as many as are wanted
+pub fn lobby_renders(robots: Vec<Robot>, config: &Config) -> impl Iterator<Item = String> {
+    let mut l = Lobby::new(config.dimensions());
+    for robot in robots.into_iter() {
+        l.add_robot(robot);
+    }
+    (0..).map(move |s| {
+        if s > 0 {
+            l.step_all_robots();
+        }
+        l.render()
+    })
 }
 
-pub fn _solution2(input: &String, map_dimensions: Dimensions) -> isize {
-    let mut l = Lobby::new(map_dimensions);
-    for robot in parse_input(&input).into_iter() {
+// the same, headed with the second
+pub fn lobby_frames(robots: Vec<Robot>, config: &Config) -> impl Iterator<Item = String> {
+    lobby_renders(robots, config).enumerate()
+        .map(|(s, render)| format!("second {}\n{}", s, render))
+}
+
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    Ok(find_easter_egg(read_robots(stream::open(path)?)?, &Config::default()))
+}
+
+pub fn solution2_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(find_easter_egg(parse_input(input)?, config))
+}
+
+fn find_easter_egg(robots: Vec<Robot>, config: &Config) -> isize {
+    let mut l = Lobby::new(config.dimensions());
+    for robot in robots.into_iter() {
         l.add_robot(robot);
     }
-    println!("########## SECOND 0 #################");
-    //l.print();
-    println!("###########################");
     for _s in 0..1000000000 {
         l.step_all_robots();
         if l.compute_connectivity() > 0.6 {
-            println!("########### SECOND {} ################", _s + 1);
-            l.print();
-            println!("###########################");
+            info!("candidate at second {}:\n{}", _s + 1, l.render());
         }
         if _s % 10_000 == 0 {
-            println!(".. {} ..", _s);
+            debug!(".. {} ..", _s);
         }
         //println!("###########################");
         //l.print();
@@ -342,9 +391,29 @@ pub fn _solution2(input: &String, map_dimensions: Dimensions) -> isize {
     0
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Robot>;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(robots: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(safety_factor(robots.clone(), config).into())
+    }
+
+    fn part2(robots: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(find_easter_egg(robots.clone(), config).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
+    use std::fs;
     use crate::common;
 
     #[test]
@@ -432,7 +501,44 @@ mod tests  {
     #[test]
     fn example_day_14_1() {
         let path = common::get_test_data_path("day14/case1.txt").unwrap();
-        let result = solution1(&path, Dimensions { x: 11, y: 7 });
+        let input = fs::read_to_string(&path).unwrap();
+        let result = solution1_str(&input, &Config { width: 11, height: 7, ..Config::default() }).unwrap();
         assert_eq!(result, 12, "14_1 example");
     }
+
+    #[test]
+    fn example_day_14_frames() {
+        let input = fs::read_to_string(common::get_test_data_path("day14/case1.txt").unwrap()).unwrap();
+        let config = Config { width: 11, height: 7, ..Config::default() };
+        let frame = lobby_frames(parse_input(&input).unwrap(), &config).nth(100).unwrap();
+        assert_eq!(frame, "\
+second 100
+......2..1.
+...........
+1..........
+.11........
+.....1.....
+...12......
+.1....1....
+");
+    }
+
+    #[test]
+    fn example_day_14_bad_robot() {
+        let input = String::from("p=0,4 v=3,-3\np=6,3 v=-1\n");
+        let err = parse_input(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("\"p=<x>,<y> v=<dx>,<dy>\""));
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn example_day_14_streamed() {
+        let input = fs::read_to_string(common::get_test_data_path("day14/case1.txt").unwrap()).unwrap();
+        let robots = read_robots(input.as_bytes()).unwrap();
+        assert_eq!(robots.len(), parse_input(&input).unwrap().len());
+        assert_eq!(safety_factor(robots, &Config { width: 11, height: 7, ..Config::default() }), 12);
+
+        let err = read_robots("p=0,4 v=3,-3\np=6,3 v=-1\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, StreamError::Parse(e) if e.line == 2));
+    }
 }
@@ -1,9 +1,16 @@
 use core::panic;
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::fmt;
+use std::iter;
 
-use rustc_hash::{FxHashMap,FxHashSet};
+use rustc_hash::FxHashSet;
+
+use crate::common::grid::{offset, to_ipos, Grid, Ipos, TileMap};
+use crate::config::NoConfig;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day15/input.txt";
@@ -22,19 +29,9 @@ pub mod constants {
 
 }
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Loc {
-    x: isize,
-    y: isize,
-}
-
-#[derive(Debug)]
-pub struct Dimensions {
-    x: isize,
-    y: isize,
-}
+const DAY: usize = 15;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Entity {
     Robot,
     Wall,
@@ -51,47 +48,51 @@ pub enum Movement {
     Down,
 }
 
-#[derive(Debug)]
-struct Warehouse {
-    robot_loc: Loc,
-    map: FxHashMap<Loc, Option<Entity>>,
-    dimensions: Dimensions,
+const TILES: TileMap<Option<Entity>> = TileMap {
+    tiles: &[
+        (constants::CHAR_WALL, Some(Entity::Wall)),
+        (constants::CHAR_BOX, Some(Entity::Box)),
+        (constants::CHAR_BOX_LEFT, Some(Entity::BoxLeft)),
+        (constants::CHAR_BOX_RIGHT, Some(Entity::BoxRight)),
+        (constants::CHAR_EMPTY, None),
+    ],
+    markers: &[(constants::CHAR_ROBOT, Some(Entity::Robot))],
+};
+
+#[derive(Debug, Clone)]
+pub struct Warehouse {
+    robot_loc: Ipos,
+    map: Grid<Option<Entity>>,
 }
 
 struct PushErr;
 
-pub fn parse_movements(input: &String) -> Vec<Movement> {
-    let mut mv_lines = input.lines().skip_while(|l| !l.is_empty());
+pub fn parse_movements(input: &str) -> Result<Vec<Movement>, ParseError> {
+    let mut mv_lines = input.lines().enumerate().skip_while(|(_, l)| !l.is_empty());
     mv_lines.next();
 
     let mut movements = vec![];
 
-    for line in mv_lines {
-        for c in line.chars() {
+    for (line_idx, line) in mv_lines {
+        for (col_idx, c) in line.chars().enumerate() {
             match c {
                 constants::CHAR_LEFT => movements.push(Movement::Left),
                 constants::CHAR_RIGHT => movements.push(Movement::Right),
                 constants::CHAR_UP => movements.push(Movement::Up),
                 constants::CHAR_DOWN => movements.push(Movement::Down),
-                c => panic!("unknown movement char detected: {}", c),
+                c => {
+                    return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedChar(c))
+                        .at(line_idx, col_idx)
+                        .with_text(line));
+                },
             }
         }
     }
 
-    movements
-}
-
-pub fn _loc_u(x: usize, y: usize) -> Loc {
-    Loc {
-        x: isize::try_from(x).unwrap(),
-        y: isize::try_from(y).unwrap(),
-    }
-}
-pub fn _loc_i(x: isize, y: isize) -> Loc {
-    Loc { x, y }
+    Ok(movements)
 }
 
-pub fn expand_warehouse(input: &String) -> String {
+pub fn expand_warehouse(input: &str) -> String {
     let result: String = input.chars().map(|c| {
         match c {
             constants::CHAR_ROBOT => format!("{}{}", constants::CHAR_ROBOT, constants::CHAR_EMPTY),
@@ -105,104 +106,55 @@ pub fn expand_warehouse(input: &String) -> String {
 }
 
 impl Warehouse {
-    pub fn from(input: &String) -> Self {
+    pub fn from(input: &str) -> Result<Self, ParseError> {
         let map_lines = input.lines().take_while(|l| !l.is_empty());
-        let y_tiles = map_lines.clone().count();
-        let x_tiles = map_lines.clone().next().unwrap().len();
-        let mut wh = Self {
-            robot_loc: _loc_i(isize::MAX, isize::MAX),
-            map: FxHashMap::default(),
-            dimensions: Dimensions {
-                x: isize::try_from(x_tiles).unwrap(),
-                y: isize::try_from(y_tiles).unwrap(),
-            },
-        };
+        let parsed = TILES.parse_lines(DAY, map_lines)?;
+        let robot_loc = to_ipos(parsed.marker(constants::CHAR_ROBOT));
 
-        for (y, line) in map_lines.enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                let loc = _loc_u(x, y);
-                match c {
-                    constants::CHAR_ROBOT => {
-                        wh.insert(&loc, Some(Entity::Robot));
-                    }
-                    constants::CHAR_WALL => {
-                        wh.insert(&loc, Some(Entity::Wall));
-                    }
-                    constants::CHAR_BOX => {
-                        wh.insert(&loc, Some(Entity::Box));
-                    }
-                    constants::CHAR_BOX_LEFT => {
-                        wh.insert(&loc, Some(Entity::BoxLeft));
-                    }
-                    constants::CHAR_BOX_RIGHT => {
-                        wh.insert(&loc, Some(Entity::BoxRight));
-                    }
-                    constants::CHAR_EMPTY => {
-                        wh.insert(&loc, None);
-                    }
-                    _ => panic!("unknown char found while parsing map"),
-                }
-            }
-        }
+        Ok(Self { robot_loc, map: parsed.grid })
+    }
 
-        assert_ne!(wh.robot_loc, _loc_i(isize::MAX, isize::MAX), "robot loc not updated");
+    fn at(&self, loc: Ipos) -> &Option<Entity> {
+        self.map.get(loc).unwrap()
+    }
 
-        wh
+    fn at_mut(&mut self, loc: Ipos) -> &mut Option<Entity> {
+        self.map.get_mut(loc).unwrap()
     }
 
-    pub fn _get_push_dest(&self, src: &Loc, dir: &Movement) -> Loc {
-        match dir {
-            Movement::Up => {
-                Loc {
-                    x: src.x,
-                    y: src.y - 1,
-                }
-            },
-            Movement::Down => {
-                Loc {
-                    x: src.x,
-                    y: src.y + 1,
-                }
-            },
-            Movement::Left => {
-                Loc {
-                    x: src.x - 1,
-                    y: src.y,
-                }
-            },
-            Movement::Right => {
-                Loc {
-                    x: src.x + 1,
-                    y: src.y,
-                }
-            },
-        }
+    pub fn _get_push_dest(&self, src: Ipos, dir: &Movement) -> Ipos {
+        let by = match dir {
+            Movement::Up => (0, -1),
+            Movement::Down => (0, 1),
+            Movement::Left => (-1, 0),
+            Movement::Right => (1, 0),
+        };
+        offset(src, by)
     }
 
-    pub fn _try_push(&mut self, src: &Loc, dir: &Movement, pushes: &mut Vec<(Loc, Loc, bool)>) -> bool {
+    pub fn _try_push(&mut self, src: Ipos, dir: &Movement, pushes: &mut Vec<(Ipos, Ipos, bool)>) -> bool {
         let dest = self._get_push_dest(src, dir);
-        let dest_entity = self.map.get(&dest).unwrap();
-        let src_entity = self.map.get(&src).unwrap();
+        let dest_entity = self.at(dest);
 
         match (dir, dest_entity) {
             (Movement::Up | Movement::Down, Some(Entity::BoxLeft) | Some(Entity::BoxRight)) => {
                 let (box_l_src, box_r_src);
 
                 if let Some(Entity::BoxLeft) = dest_entity {
-                    box_l_src = dest.clone();
-                    box_r_src = self._get_push_dest(&box_l_src, &Movement::Right);
+                    box_l_src = dest;
+                    box_r_src = self._get_push_dest(box_l_src, &Movement::Right);
                 } else {
-                    box_r_src = dest.clone();
-                    box_l_src = self._get_push_dest(&box_r_src, &Movement::Left);
+                    box_r_src = dest;
+                    box_l_src = self._get_push_dest(box_r_src, &Movement::Left);
                 }
 
-                let push_left = self._try_push(&box_l_src, &dir, pushes);
-                let push_right = self._try_push(&box_r_src, &dir, pushes);
+                let push_left = self._try_push(box_l_src, &dir, pushes);
+                let push_right = self._try_push(box_r_src, &dir, pushes);
 
                 match (push_left, push_right) {
                     (true, true) => {
                         pushes.push((
-                            src.clone(),
+                            src,
                             dest,
                             true
                         ));
@@ -210,7 +162,7 @@ impl Warehouse {
                     },
                     _ => {
                         pushes.push((
-                            src.clone(),
+                            src,
                             dest,
                             false
                         ));
@@ -219,10 +171,10 @@ impl Warehouse {
                 }
             },
             (_, Some(Entity::Box | Entity::Robot | Entity::BoxLeft | Entity::BoxRight)) => {
-                match self._try_push(&dest, &dir, pushes) {
+                match self._try_push(dest, &dir, pushes) {
                     true => {
                         pushes.push((
-                            src.clone(),
+                            src,
                             dest,
                             true
                         ));
@@ -230,7 +182,7 @@ impl Warehouse {
                     },
                     _ => {
                         pushes.push((
-                            src.clone(),
+                            src,
                             dest,
                             false
                         ));
@@ -240,7 +192,7 @@ impl Warehouse {
             },
             (_, Some(Entity::Wall)) => {
                 pushes.push((
-                    src.clone(),
+                    src,
                     dest,
                     false
                 ));
@@ -248,7 +200,7 @@ impl Warehouse {
             },
             (_, None) => {
                 pushes.push((
-                    src.clone(),
+                    src,
                     dest,
                     true
                 ));
@@ -257,13 +209,12 @@ impl Warehouse {
         }
     }
 
-    pub fn _mv(&mut self, src: &Loc, dest: &Loc) {
-        match self.map.get(&dest).unwrap() {
+    pub fn _mv(&mut self, src: Ipos, dest: Ipos) {
+        match self.at(dest) {
             None => {
-                match self.map.remove(&src).unwrap() {
+                match self.at_mut(src).take() {
                     Some(entity) => {
-                        self.map.entry(src.clone()).or_insert(None);
-                        self.map.entry(dest.clone()).and_modify(|v| *v = Some(entity));
+                        *self.at_mut(dest) = Some(entity);
                     },
                     None => panic!("attempting to move None"),
                 }
@@ -272,53 +223,34 @@ impl Warehouse {
         }
     }
 
-    pub fn try_push_robot(&mut self, dir: &Movement) -> Result<Loc, PushErr> {
+    pub fn try_push_robot(&mut self, dir: &Movement) -> Result<Ipos, PushErr> {
         let mut pushes = vec![];
-        match self._try_push(&self.robot_loc.clone(), &dir, &mut pushes) {
+        match self._try_push(self.robot_loc, &dir, &mut pushes) {
             true => {
                 //println!("{:?}", pushes);
-                let mut push_history: FxHashSet<(Loc, Loc)> = FxHashSet::default();
+                let mut push_history: FxHashSet<(Ipos, Ipos)> = FxHashSet::default();
                 for p in pushes.iter() {
-                    if push_history.contains(&(p.0.clone(), p.1.clone())) {
+                    if push_history.contains(&(p.0, p.1)) {
                         // don't do it
                     } else {
-                        self._mv(&p.0, &p.1);
-                        push_history.insert((p.0.clone(), p.1.clone()));
+                        self._mv(p.0, p.1);
+                        push_history.insert((p.0, p.1));
                     }
                 }
                 let last_push = pushes.last().unwrap();
-                self.robot_loc = last_push.1.clone();
-                Ok(self.robot_loc.clone())
+                self.robot_loc = last_push.1;
+                Ok(self.robot_loc)
             },
             false => Err(PushErr),
         }
     }
 
-    pub fn insert(&mut self, loc: &Loc, entity: Option<Entity>) {
-        match &entity {
-            Some(Entity::Robot) => {
-                self.robot_loc = loc.clone();
-                self.map.entry(loc.clone()).or_insert(entity);
-            },
-            Some(_) => {
-                self.map.entry(loc.clone()).or_insert(entity);
-            }
-            None => {
-                self.map.entry(loc.clone()).or_insert(None);
-            },
-        }
-    }
-
     pub fn compute_gps_sum(&self) -> isize {
         let mut sum = 0;
-        for y in 0..self.dimensions.y {
-            for x in 0..self.dimensions.x {
-                match self.map.get(&Loc { x, y }).unwrap() {
-                    Some(Entity::Box) => {
-                        sum += 100 * y + x
-                    },
-                    _ => (),
-                }
+        for (pos, entity) in self.map.iter() {
+            if let Some(Entity::Box) = entity {
+                let (x, y) = to_ipos(pos);
+                sum += 100 * y + x
             }
         }
         sum
@@ -326,14 +258,10 @@ impl Warehouse {
 
     pub fn compute_scaled_gps_sum(&self) -> isize {
         let mut sum = 0;
-        for y in 0..self.dimensions.y {
-            for x in 0..self.dimensions.x {
-                match self.map.get(&Loc { x, y }).unwrap() {
-                    Some(Entity::BoxLeft) => {
-                        sum += 100 * y + x
-                    },
-                    _ => (),
-                }
+        for (pos, entity) in self.map.iter() {
+            if let Some(Entity::BoxLeft) = entity {
+                let (x, y) = to_ipos(pos);
+                sum += 100 * y + x
             }
         }
         sum
@@ -343,20 +271,15 @@ impl Warehouse {
 impl fmt::Display for Warehouse {
 
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for y in 0..self.dimensions.y {
-            for x in 0..self.dimensions.x {
-                match self.map.get(&_loc_i(x, y)) {
-                    Some(opt_e) => {
-                        match opt_e {
-                            Some(Entity::Robot) => write!(f, "{}", constants::CHAR_ROBOT),
-                            Some(Entity::Box) => write!(f, "{}", constants::CHAR_BOX),
-                            Some(Entity::Wall) => write!(f, "{}", constants::CHAR_WALL),
-                            Some(Entity::BoxLeft) => write!(f, "{}", constants::CHAR_BOX_LEFT),
-                            Some(Entity::BoxRight) => write!(f, "{}", constants::CHAR_BOX_RIGHT),
-                            None => write!(f, "{}", constants::CHAR_EMPTY)
-                        }
-                    },
-                    _ => panic!("loc is missing data"),
+        for row in self.map.rows() {
+            for opt_e in row {
+                match opt_e {
+                    Some(Entity::Robot) => write!(f, "{}", constants::CHAR_ROBOT),
+                    Some(Entity::Box) => write!(f, "{}", constants::CHAR_BOX),
+                    Some(Entity::Wall) => write!(f, "{}", constants::CHAR_WALL),
+                    Some(Entity::BoxLeft) => write!(f, "{}", constants::CHAR_BOX_LEFT),
+                    Some(Entity::BoxRight) => write!(f, "{}", constants::CHAR_BOX_RIGHT),
+                    None => write!(f, "{}", constants::CHAR_EMPTY)
                 }.expect("failed to write");
             }
             write!(f, "\n").expect("failed to write");
@@ -366,41 +289,79 @@ impl fmt::Display for Warehouse {
 
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-pub fn _solution1(input: &String) -> isize {
-    let mut wh = Warehouse::from(&input);
-    let moves = parse_movements(&input);
+pub fn solution1_str(input: &str) -> Result<isize, ParseError> {
+    let wh = Warehouse::from(input)?;
+    let moves = parse_movements(input)?;
+    Ok(gps_sum_after(wh, &moves))
+}
 
+fn gps_sum_after(mut wh: Warehouse, moves: &[Movement]) -> isize {
     for mv in moves {
-        let _ = wh.try_push_robot(&mv);
+        let _ = wh.try_push_robot(mv);
     }
     //print!("{}", wh);
     wh.compute_gps_sum()
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+// the warehouse before the robot moves and after every move, whether or not it got anywhere
+pub fn push_frames(mut wh: Warehouse, moves: Vec<Movement>) -> impl Iterator<Item = String> {
+    iter::once(wh.to_string()).chain(moves.into_iter().map(move |mv| {
+        let _ = wh.try_push_robot(&mv);
+        wh.to_string()
+    }))
 }
 
-pub fn _solution2(input: &String) -> isize {
-    let expanded_input = expand_warehouse(&input);
-    let mut wh = Warehouse::from(&expanded_input);
-    let moves = parse_movements(&input);
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
+}
 
+pub fn solution2_str(input: &str) -> Result<isize, ParseError> {
+    // check the map as written first, so errors point at the original columns
+    Warehouse::from(input)?;
+    let expanded_input = expand_warehouse(input);
+    let wh = Warehouse::from(&expanded_input)?;
+    let moves = parse_movements(input)?;
+    Ok(scaled_gps_sum_after(wh, &moves))
+}
+
+fn scaled_gps_sum_after(mut wh: Warehouse, moves: &[Movement]) -> isize {
     //print!("{}", wh);
     for mv in moves {
         //println!("{:?}", mv);
-        let _ = wh.try_push_robot(&mv);
+        let _ = wh.try_push_robot(mv);
         //print!("{}", wh);
     }
     wh.compute_scaled_gps_sum()
 }
 
+pub struct Day15;
+
+// part 2 runs on a widened copy of the map, so both versions are parsed up front
+impl Solution for Day15 {
+    type Input = (Warehouse, Warehouse, Vec<Movement>);
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let wh = Warehouse::from(input)?;
+        let wide_wh = Warehouse::from(&expand_warehouse(input))?;
+        Ok((wh, wide_wh, parse_movements(input)?))
+    }
+
+    fn part1((wh, _, moves): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(gps_sum_after(wh.clone(), moves).into())
+    }
+
+    fn part2((_, wide_wh, moves): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(scaled_gps_sum_after(wide_wh.clone(), moves).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
@@ -409,21 +370,54 @@ mod tests  {
     #[test]
     fn test_example_day_15_1_1() {
         let path = common::get_test_data_path("day15/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 10092);
     }
 
     #[test]
     fn test_example_day_15_1_2() {
         let path = common::get_test_data_path("day15/case2.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 2028);
     }
 
     #[test]
     fn test_example_day_15_2_1() {
         let path = common::get_test_data_path("day15/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 9021);
     }
+
+    #[test]
+    fn test_example_day_15_push_frames() {
+        let input = fs::read_to_string(common::get_test_data_path("day15/case2.txt").unwrap()).unwrap();
+        let (wh, _, moves) = Day15::parse(&input).unwrap();
+        let frames: Vec<String> = push_frames(wh, moves).collect();
+        assert_eq!(frames.len(), 16);
+        assert!(input.starts_with(frames[0].trim_end()));
+        assert_eq!(frames[15].trim_end(), "\
+########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########");
+    }
+
+    #[test]
+    fn test_example_day_15_bad_move() {
+        let input = String::from("####\n#@O#\n####\n\n<>x^\n");
+        let err = solution1_str(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('x'));
+        assert_eq!((err.line, err.column), (5, 3));
+    }
+
+    #[test]
+    fn test_example_day_15_no_robot() {
+        let input = String::from("####\n#.O#\n####\n\n<>\n");
+        let err = solution2_str(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingMarker('@'));
+    }
 }
@@ -1,6 +1,14 @@
-use std::{collections::VecDeque, fs};
-use std::path::PathBuf;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+
+use crate::common::grid::{offset, to_ipos, Grid, Ipos, TileMap};
+use crate::common::search::{self, PathDag};
+use crate::config;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 
 // Node
@@ -53,21 +61,6 @@ use rustc_hash::{FxHashMap, FxHashSet};
     total_cost
 */
 
-#[derive(Debug, Hash, Eq, PartialEq, Clone)]
-pub struct Loc {
-    x: isize,
-    y: isize,
-}
-
-impl Loc {
-    pub fn from_u(x: usize, y: usize) -> Self {
-        Self {
-            x: isize::try_from(x).unwrap(),
-            y: isize::try_from(y).unwrap(),
-        }
-    }
-}
-
 #[derive(Debug, Hash, Eq, PartialEq, Clone)]
 pub enum Direction {
     North,
@@ -76,9 +69,30 @@ pub enum Direction {
     West,
 }
 
+impl Direction {
+    pub fn arrow(&self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::South => 'v',
+            Direction::East => '>',
+            Direction::West => '<',
+        }
+    }
+
+    // the step one tile forward
+    pub fn offset(&self) -> Ipos {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::East => (1, 0),
+            Direction::West => (-1, 0),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Deer {
-    loc: Loc,
+    loc: Ipos,
     dir: Direction,
 }
 
@@ -92,7 +106,7 @@ impl Deer {
         };
 
         Self {
-            loc: self.loc.clone(),
+            loc: self.loc,
             dir: next_dir,
         }
     }
@@ -106,12 +120,13 @@ impl Deer {
         };
 
         Self {
-            loc: self.loc.clone(),
+            loc: self.loc,
             dir: next_dir,
         }
     }
 }
 
+#[derive(Clone)]
 pub enum Tile {
     Deer,
     Empty,
@@ -119,26 +134,17 @@ pub enum Tile {
     Goal,
 }
 
-type BaseMap = FxHashMap<Loc, Tile>;
+type BaseMap = Grid<Tile>;
 
-type NeighborMap = FxHashMap<Loc, FxHashMap<Direction, Loc>>;
+const TILES: TileMap<Tile> = TileMap {
+    tiles: &[('#', Tile::Wall), ('.', Tile::Empty)],
+    markers: &[('S', Tile::Deer), ('E', Tile::Goal)],
+};
 
 pub struct Maze {
     base_map: BaseMap,
     deer_start: Deer,
-    goal: Loc,
-    neighbors: NeighborMap,
-    dim_x: isize,
-    dim_y: isize,
-}
-
-pub fn potential_neighbors(loc: &Loc) -> Vec<(Loc, Direction)> {
-    vec![
-        (Loc { x: loc.x + 1, y: loc.y }, Direction::East),
-        (Loc { x: loc.x - 1, y: loc.y }, Direction::West),
-        (Loc { x: loc.x, y: loc.y + 1 }, Direction::South),
-        (Loc { x: loc.x, y: loc.y - 1 }, Direction::North),
-    ]
+    goal: Ipos,
 }
 
 #[derive(Clone, Debug)]
@@ -147,16 +153,8 @@ pub enum Rotation {
     Right90,
 }
 
-#[derive(Clone, Debug)]
-pub enum Action {
-    Move(Deer, Deer),
-    Turn(Rotation),
-}
-
 #[derive(Debug)]
 pub struct Move {
-    actions: Vec<Action>,
-    src_deer: Deer,
     dest_deer: Deer,
     cost: isize,
 }
@@ -164,119 +162,47 @@ pub struct Move {
 
 impl Maze {
 
-    fn _parse_base(input: &String) -> BaseMap {
-        let mut base_map = BaseMap::default();
-        for (y, line) in input.lines().enumerate() {
-            for (x, c) in line.chars().enumerate() {
-                match c {
-                    '#' => base_map.entry(Loc::from_u(x, y)).or_insert(Tile::Wall),
-                    '.' => base_map.entry(Loc::from_u(x, y)).or_insert(Tile::Empty),
-                    'S' => base_map.entry(Loc::from_u(x, y)).or_insert(Tile::Deer),
-                    'E' => base_map.entry(Loc::from_u(x, y)).or_insert(Tile::Goal),
-                    c => panic!("unexpected character encountered: {}", c),
-                };
-            }
-        }
-        base_map
-    }
+    pub fn from(input: &str) -> Result<Self, ParseError> {
 
-    pub fn _init_neighbors(loc: &Loc, base_map: &BaseMap, neighbor_map: &mut NeighborMap) {
-        for pn in potential_neighbors(loc) {
-            match base_map.get(&pn.0) {
-                Some(Tile::Wall) => (),
-                Some(Tile::Empty) | Some(Tile::Deer) | Some(Tile::Goal) => {
-                    neighbor_map.entry(loc.clone()).or_insert(FxHashMap::default());
-                    neighbor_map.entry(loc.clone())
-                        .and_modify(|m| {
-                            m.entry(pn.1).or_insert(pn.0);
-                        });
-                },
-                None => (),
-            }
-        }
-    }
+        let parsed = TILES.parse(DAY, input)?;
+        let deer_start = Deer { dir: Direction::East, loc: to_ipos(parsed.marker('S')) };
+        let goal = to_ipos(parsed.marker('E'));
 
-    pub fn from(input: &String) -> Self {
-
-        let base_map = Maze::_parse_base(&input);
-        let mut neighbor_map = NeighborMap::default();
-        let mut deer: Option<Deer> = None;
-        let mut goal: Option<Loc> = None;
-
-        let dim_y = isize::try_from(input.lines().count()).unwrap();
-        let dim_x = isize::try_from(input.lines().next().unwrap().chars().count()).unwrap();
-
-        for (loc, tile) in base_map.iter() {
-            match tile {
-                Tile::Wall => (),
-                Tile::Deer => {
-                    deer = Some(Deer {
-                        dir: Direction::East,
-                        loc: loc.clone(),
-                    });
-                    Self::_init_neighbors(&loc, &base_map, &mut neighbor_map);
-                }
-                Tile::Goal => {
-                    goal = Some(loc.clone());
-                    Self::_init_neighbors(&loc, &base_map, &mut neighbor_map);
-                }
-                Tile::Empty => {
-                    Self::_init_neighbors(&loc, &base_map, &mut neighbor_map);
-                }
-            }
-        }
-
-        Self {
-            neighbors: neighbor_map,
-            deer_start: deer.unwrap(),
-            goal: goal.unwrap(),
-            dim_x,
-            dim_y,
-            base_map,
-        }
+        Ok(Self {
+            deer_start,
+            goal,
+            base_map: parsed.grid,
+        })
     }
 
-    pub fn can_move(&self, src_deer: &Deer, r: Option<&Rotation>) -> Option<Move> {
+    pub fn can_move(&self, src_deer: &Deer, r: Option<&Rotation>, config: &Config) -> Option<Move> {
         let mut dest_deer = src_deer.clone();
-        let mut cost = constants::COST_MOVE;
-        let mut actions: Vec<Action> = vec![];
+        let mut cost = config.move_cost;
         match r {
             Some(Rotation::Right90) => {
                 dest_deer = src_deer.turn_right();
-                cost += constants::COST_TURN;
-                actions.push(Action::Turn(Rotation::Right90));
+                cost += config.turn_cost;
             },
             Some(Rotation::Left90) => {
                 dest_deer = src_deer.turn_left();
-                cost += constants::COST_TURN;
-                actions.push(Action::Turn(Rotation::Left90));
+                cost += config.turn_cost;
             },
             None => (),
         }
-        match self.neighbors.get(&src_deer.loc) {
-            Some(neighbors) => {
-                //println!("found neighbors");
-                //println!("\t{:?}", dest_deer.dir);
-                match neighbors.get(&dest_deer.dir) {
-                    Some(loc) => {
-                        //println!("\tfound dir neighbor");
-                        dest_deer.loc = loc.clone();
-                        actions.push(Action::Move(src_deer.clone(), dest_deer.clone()));
-                        Some(Move {
-                            src_deer: src_deer.clone(),
-                            dest_deer,
-                            cost,
-                            actions,
-                        })
-                    },
-                    None => None,
-                }
+        let dest = offset(src_deer.loc, dest_deer.dir.offset());
+        match self.base_map.get(dest) {
+            Some(Tile::Empty) | Some(Tile::Deer) | Some(Tile::Goal) => {
+                dest_deer.loc = dest;
+                Some(Move {
+                    dest_deer,
+                    cost,
+                })
             },
-            None => None,
+            Some(Tile::Wall) | None => None,
         }
     }
 
-    pub fn get_valid_moves(&self, deer_src: &Deer) -> Vec<Move> {
+    pub fn get_valid_moves(&self, deer_src: &Deer, config: &Config) -> Vec<Move> {
         let mut moves: Vec<Move> = vec![];
         let try_rotations = [
             None,
@@ -285,100 +211,14 @@ impl Maze {
         ];
 
         for r in try_rotations.iter() {
-            match self.can_move(deer_src, r.as_ref()) {
-                Some(_move) => {
-                    moves.push(_move);
-                },
-                None => (),
+            if let Some(_move) = self.can_move(deer_src, r.as_ref(), config) {
+                moves.push(_move);
             }
         }
         moves
     }
 }
 
-#[derive(Clone)]
-pub struct Solution {
-    pub cost: isize,
-    pub actions: Vec<Action>,
-}
-impl Solution {
-    pub fn compute_cost(actions: &Vec<Action>) -> isize {
-        actions.iter().map(|a| {
-            match a {
-                Action::Move(..) => {
-                    1
-                },
-                Action::Turn(_) => {
-                    1000
-                },
-            }
-        }).sum()
-    }
-
-    pub fn new(actions: Vec<Action>) -> Self {
-        let cost = Self::compute_cost(&actions);
-        Self {
-            actions,
-            cost,
-        }
-    }
-}
-
-pub fn display(maze: &Maze, deer: &Deer, actions: &Vec<Action>) {
-    let mut moves: FxHashMap<Loc, Direction> = FxHashMap::default();
-
-    for a in actions {
-        match a {
-            Action::Move(sd, dd) => {
-                moves.entry(sd.loc.clone()).or_insert(dd.dir.clone());
-            },
-            _ => (),
-        }
-    }
-
-    println!("");
-
-    let neighbors = maze.neighbors.get(&deer.loc).unwrap();
-    println!("NEIGHBORS:");
-    for (dir, loc) in neighbors.iter() {
-        println!("\t{:?} {:?}", dir, loc);
-    }
-
-    for y in 0..maze.dim_y {
-        for x in 0..maze.dim_x {
-            let loc = Loc{ x, y };
-            let symbol = if loc == maze.goal {
-                'E'
-            } else if loc == deer.loc {
-                'S'
-            } else if moves.contains_key(&loc) {
-                match moves.get(&loc) {
-                    Some(d) => {
-                        match d {
-                            Direction::North => '^',
-                            Direction::South => 'v',
-                            Direction::East => '>',
-                            Direction::West => '>',
-                        }
-                    },
-                    None => panic!("coord not found"),
-                }
-            } else {
-                match maze.base_map.get(&loc) {
-                    Some(Tile::Wall) => '#',
-                    Some(Tile::Empty) => '.',
-                    _ => panic!("should never get here"),
-                }
-            };
-            print!("{}", symbol);
-        }
-        print!("\n");
-    }
-
-
-
-}
-
 /*
 pub fn _get_min_possible_remaining_cost(deer: &Deer, goal: &Loc) -> isize {
     let x_diff = isize::try_from(deer.loc.x.abs_diff(goal.x)).unwrap();
@@ -432,244 +272,248 @@ pub fn _get_min_possible_remaining_cost(deer: &Deer, goal: &Loc) -> isize {
 }
 */
 
-pub fn _compute_best_costs(maze: &Maze, deer: &Deer) -> (FxHashMap<Deer, isize>, FxHashSet<Deer>) {
-    let mut to_process: VecDeque<Deer> = VecDeque::from([deer.clone()]);
-    let mut best_costs: FxHashMap<Deer, isize> = FxHashMap::default();
-    //let mut best_paths: FxHashMap<Deer, Vec<Vec<Action>>> = FxHashMap::default();
-    let mut end_deer: FxHashSet<Deer> = FxHashSet::default();
-
-    best_costs.entry(deer.clone()).or_insert(0);
-    //best_paths.entry(deer.clone()).or_insert(vec![vec![]]);
-    
-    while to_process.len() != 0 {
-        //println!("{}", i);
-        //println!("{}", to_process.len());
-        let processing = to_process.pop_front().unwrap();
-        if processing.loc == maze.goal {
-            end_deer.insert(processing);
-            continue;
-        }
-        for mv in maze.get_valid_moves(&processing).iter() {
-            //println!("SRC: {:#?}", mv.src_deer);
-            //println!("DEST: {:#?}", mv.dest_deer);
-            let src_cost = best_costs.get(&processing).unwrap();
-            let dest_cost = src_cost + mv.cost;
-            match best_costs.get(&mv.dest_deer) {
-                Some(existing_best) => {
-                    if dest_cost < *existing_best {
-                        if mv.dest_deer.loc == maze.goal {
-                            println!("REPLACE NEW BEST: {}", dest_cost);
-                        }
-                        best_costs.entry(mv.dest_deer.clone()).and_modify(|v| *v = dest_cost);
-                        //let best_src_paths = best_paths.get(&mv.src_deer).unwrap().clone();
-                        //best_paths.entry(mv.dest_deer.clone()).and_modify(|v| {
-                        //    *v = vec![];
-                        //    for action_list in best_src_paths.into_iter() {
-                        //        let mut al = action_list.clone();
-                        //        al.append(&mut mv.actions.clone());
-                        //        v.push(al);
-                        //    }
-                        //});
-                        to_process.push_back(mv.dest_deer.clone());
-                    } else if dest_cost == *existing_best {
-                        if mv.dest_deer.loc == maze.goal {
-                            println!("SAME NEW BEST: {}", dest_cost);
-                        }
-                        //let best_src_paths = best_paths.get(&mv.src_deer).unwrap().clone();
-                        //best_paths.entry(mv.dest_deer.clone()).and_modify(|v| {
-                        //    for action_list in best_src_paths.into_iter() {
-                        //        let mut al = action_list.clone();
-                        //        al.append(&mut mv.actions.clone());
-                        //        v.push(al);
-                        //    }
-                        //});
-                        //to_process.push_back(mv.dest_deer.clone());
-                    } else {
-                        // do nothing
-                    }
-                },
-                None => {
-                    //println!("NONE");
-                    if mv.dest_deer.loc == maze.goal {
-                        println!("NEW BEST: {}", dest_cost);
-                    }
-                    best_costs.entry(mv.dest_deer.clone()).or_insert(dest_cost);
-                    //let best_src_paths = best_paths.get(&mv.src_deer).unwrap().clone();
-                    //let mut dest_paths = vec![];
-                    //for action_list in best_src_paths.into_iter() {
-                    //    let mut al = action_list.clone();
-                    //    al.append(&mut mv.actions.clone());
-                    //    dest_paths.push(al);
-                    //}
-                    //best_paths.entry(mv.dest_deer.clone()).or_insert(dest_paths);
-                    to_process.push_back(mv.dest_deer.clone());
-                }
-            }
-        }
-    }
-    (best_costs, end_deer)
+// the race is over once the deer reaches the goal, so nothing leads on from there
+fn next_deer(maze: &Maze, deer: &Deer, config: &Config) -> impl Iterator<Item=(Deer, isize)> {
+    let moves = match deer.loc == maze.goal {
+        true => vec![],
+        false => maze.get_valid_moves(deer, config),
+    };
+    moves.into_iter().map(|mv| (mv.dest_deer, mv.cost))
 }
 
-pub fn get_all_best_paths(maze: &Maze, deer: &Deer) -> Vec<Solution> {
-    let (best_costs, all_end_deer) = _compute_best_costs(maze, deer);
+pub fn get_all_best_paths(maze: &Maze, deer: &Deer, config: &Config) -> PathDag<Deer, isize> {
+    search::all_shortest_paths(deer.clone(), |d| next_deer(maze, d, config))
+        .expect("the config keeps every move above 0")
+}
 
-    let backward_goal = deer.loc.clone();
+pub fn get_best_goal_cost(maze: &Maze, best_costs: &FxHashMap<Deer, isize>) -> Option<isize> {
+    best_costs.iter()
+        .filter(|(deer, _)| deer.loc == maze.goal)
+        .map(|(_, cost)| *cost)
+        .min()
+}
 
-    let mut all_solutions: Vec<Solution> = vec![];
+pub fn solve_for_min(maze: &Maze, deer: &Deer, config: &Config) -> Option<isize> {
+    let best_costs = search::dijkstra(deer.clone(), |d| next_deer(maze, d, config));
+    get_best_goal_cost(maze, &best_costs)
+}
 
-    let best_solution_cost = get_best_goal_cost(&best_costs, &all_end_deer).unwrap();
 
-    for d in all_end_deer.into_iter() {
-        if *best_costs.get(&d).unwrap() > best_solution_cost {
-            continue;
-        }
-        let backward_deer = d.turn_left().turn_left();
-        let first_move = maze.can_move(&backward_deer, None).unwrap();
-        let mut to_process: VecDeque<Solution> = VecDeque::from([Solution::new(first_move.actions.clone())]);
-
-        while to_process.len() != 0 {
-            let processing = to_process.pop_front().unwrap();
-
-            let last_move_deer = match processing.actions.last().unwrap() {
-                Action::Move(_src_deer, dest_deer) => {
-                    dest_deer
-                },
-                _ => panic!("should always be a move"),
-            };
-
-            if last_move_deer.loc == backward_goal {
-                println!("found backward goal");
-                all_solutions.push(processing);
-                continue;
-            }
+pub mod constants {
+    pub const INPUT_PATH: &str = "day16/input.txt";
+}
 
-            for mv in maze.get_valid_moves(&last_move_deer).iter_mut() {
-                let target_deer = vec![
-                    mv.dest_deer.clone(),
-                    mv.dest_deer.turn_left(),
-                    mv.dest_deer.turn_left().turn_left(),
-                    mv.dest_deer.turn_right(),
-                ];
-                let mut target_vals = vec![];
-                for d in target_deer.iter() {
-                    match best_costs.get(&d) {
-                        Some(c) => {
-                            target_vals.push(c);
-                        }
-                        _ => (),
-                    }
-                }
-
-                if target_vals.iter().any(|v| *v + mv.cost + processing.cost <= best_solution_cost) {
-                    println!("keep processing");
-                    let mut sol = processing.clone();
-                    sol.actions.append(&mut mv.actions);
-                    sol.cost += mv.cost;
-                    to_process.push_back(sol);
-                } else {
-                    // don't continue processing
-                    println!("stop processing");
-                }
-            }
-        }
-    }
-    println!("all solutions: {}", all_solutions.len());
-    all_solutions
+const DAY: usize = 16;
+
+// the score of a step forward and of a quarter turn. every move steps forward, so a step
+// has to cost something or the best paths could go round in circles
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "config::positive")]
+    pub move_cost: isize,
+    #[serde(deserialize_with = "config::non_negative")]
+    pub turn_cost: isize,
 }
 
-pub fn get_best_goal_cost(best_costs: &FxHashMap<Deer, isize>, end_deer: &FxHashSet<Deer>) -> Option<isize> {
-    let mut min_cost = isize::MAX;
-    for deer in end_deer.iter() {
-        let best = best_costs.get(&deer).unwrap();
-        if  *best < min_cost {
-            min_cost = *best;
-        }
+impl Default for Config {
+    fn default() -> Self {
+        Self { move_cost: 1, turn_cost: 1000 }
     }
-    Some(min_cost)
 }
 
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input, &Config::default())?)
+}
 
-pub fn solve_for_min(maze: &Maze, deer: &Deer) -> Option<isize> {
-    let (best_costs, end_deer) = _compute_best_costs(maze, deer);
-    println!("neighbor map: {}", maze.neighbors.len());
-    println!("best_costs: {}", best_costs.len());
-    get_best_goal_cost(&best_costs, &end_deer)
+pub fn solution1_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(best_path_cost(&Maze::from(input)?, config))
 }
 
+fn best_path_cost(maze: &Maze, config: &Config) -> isize {
+    let deer = maze.deer_start.clone();
+    solve_for_min(maze, &deer, config).unwrap()
+}
 
-pub mod constants {
-    pub const INPUT_PATH: &str = "day16/input.txt";
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input, &Config::default())?)
+}
 
-    pub const COST_MOVE: isize = 1;
-    pub const COST_TURN: isize = 1000;
+
+pub fn solution2_str(input: &str, config: &Config) -> Result<usize, ParseError> {
+    Ok(count_best_path_tiles(&Maze::from(input)?, config))
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+fn count_best_path_tiles(maze: &Maze, config: &Config) -> usize {
+    best_path_tiles(maze, config).len()
 }
 
-pub fn _solution1(input: &String) -> isize {
-    let maze = Maze::from(&input);
+fn best_path_tiles(maze: &Maze, config: &Config) -> FxHashSet<Ipos> {
     let deer = maze.deer_start.clone();
-    solve_for_min(&maze, &deer).unwrap()
+    let best_paths = get_all_best_paths(maze, &deer, config);
+    let best_cost = get_best_goal_cost(maze, &best_paths.costs).unwrap();
+    let best_end_deer = best_paths.costs.iter()
+        .filter(|(d, cost)| d.loc == maze.goal && **cost == best_cost)
+        .map(|(d, _)| d.clone());
+    best_paths.nodes_on_paths_to(best_end_deer).into_iter()
+        .map(|d| d.loc)
+        .collect()
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+// the maze as the puzzle draws it, with mark picking what goes over a tile instead
+fn render(maze: &Maze, mark: impl Fn(Ipos, &Tile) -> Option<char>) -> String {
+    let mut out = String::new();
+    for (y, row) in maze.base_map.rows().enumerate() {
+        for (x, tile) in row.iter().enumerate() {
+            out.push(mark(to_ipos((x, y)), tile).unwrap_or(match tile {
+                Tile::Deer => 'S',
+                Tile::Goal => 'E',
+                Tile::Wall => '#',
+                Tile::Empty => '.',
+            }));
+        }
+        out.push('\n');
+    }
+    out
 }
 
+// the maze with every tile on some best path marked O, like the puzzle draws it
+pub fn render_best_paths(maze: &Maze, config: &Config) -> String {
+    let tiles = best_path_tiles(maze, config);
+    render(maze, |pos, tile| (matches!(tile, Tile::Empty) && tiles.contains(&pos)).then_some('O'))
+}
 
-pub fn _solution2(input: &String) -> usize {
-    let maze = Maze::from(&input);
+// one of the best paths, from the start to the goal. just the start when the goal is out of reach
+fn best_path(maze: &Maze, config: &Config) -> Vec<Deer> {
     let deer = maze.deer_start.clone();
-    let all_solutions = get_all_best_paths(&maze, &deer);
-    let mut visits: FxHashSet<Loc> = FxHashSet::default();
-    for s in all_solutions.iter() {
-        for a in s.actions.iter() {
-            match a {
-                Action::Move(src, dest) => {
-                    visits.insert(src.loc.clone());
-                    visits.insert(dest.loc.clone());
-                }
-                _ => (),
-            }
-        }
+    let best_paths = get_all_best_paths(maze, &deer, config);
+    let end = get_best_goal_cost(maze, &best_paths.costs).and_then(|best_cost| {
+        best_paths.costs.iter()
+            .find(|(d, cost)| d.loc == maze.goal && **cost == best_cost)
+            .map(|(d, _)| d.clone())
+    });
+    let mut path = vec![end.unwrap_or(deer)];
+    while let Some(pred) = path.last().and_then(|d| best_paths.predecessors(d).first()) {
+        path.push(pred.clone());
+    }
+    path.reverse();
+    path
+}
+
+// the deer walking a best path a tile at a time, leaving O behind it
+pub fn walk_frames(maze: Maze, config: &Config) -> impl Iterator<Item = String> {
+    let path = best_path(&maze, config);
+    (0..path.len()).map(move |i| {
+        let walked: FxHashSet<Ipos> = path[..i].iter().map(|d| d.loc).collect();
+        render(&maze, |pos, _| match pos == path[i].loc {
+            true => Some(path[i].dir.arrow()),
+            false => walked.contains(&pos).then_some('O'),
+        })
+    })
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Maze;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Maze::from(input)
+    }
+
+    fn part1(maze: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(best_path_cost(maze, config).into())
+    }
+
+    fn part2(maze: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_best_path_tiles(maze, config).into())
     }
-    visits.len()
 }
 
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn example_day_16_1_1() {
         let path = common::get_test_data_path("day16/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 7036, "found optimal path");
     }
 
     #[test]
     fn example_day_16_1_2() {
         let path = common::get_test_data_path("day16/case2.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 11048, "found optimal path");
     }
 
     #[test]
     fn example_day_16_2_1() {
         let path = common::get_test_data_path("day16/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 45, "found optimal path");
     }
 
     #[test]
     fn example_day_16_2_2() {
         let path = common::get_test_data_path("day16/case2.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 64, "found optimal path");
     }
+
+    #[test]
+    fn example_day_16_costs() {
+        // the deer starts facing east, so the cheapest way round the corner turns once
+        let input = String::from("####\n#.E#\n#S.#\n####\n");
+        assert_eq!(solution1_str(&input, &Config::default()).unwrap(), 1002);
+        assert_eq!(solution1_str(&input, &Config { move_cost: 2, turn_cost: 10 }).unwrap(), 14);
+    }
+
+    #[test]
+    fn example_day_16_bad_costs() {
+        for set in ["move_cost=-1", "move_cost=0", "turn_cost=-1000"] {
+            let mut overrides = config::Overrides::default();
+            overrides.set(16, set).unwrap();
+            assert!(overrides.for_day::<Config>(16).is_err(), "{} is rejected", set);
+        }
+    }
+
+    #[test]
+    fn example_day_16_render_best_paths() {
+        let input = fs::read_to_string(common::get_test_data_path("day16/case1.txt").unwrap()).unwrap();
+        let render = render_best_paths(&Maze::from(&input).unwrap(), &Config::default());
+        assert_eq!(render.matches(['O', 'S', 'E']).count(), 45);
+        assert_eq!(render.replace('O', "."), input);
+    }
+
+    #[test]
+    fn example_day_16_walk_frames() {
+        let input = fs::read_to_string(common::get_test_data_path("day16/case1.txt").unwrap()).unwrap();
+        let frames: Vec<String> = walk_frames(Maze::from(&input).unwrap(), &Config::default()).collect();
+        assert_eq!(frames[0], input.replacen('S', ">", 1));
+        let last = frames.last().unwrap();
+        assert!(!last.contains('E') && !last.contains('S'));
+        // a 7036 point path turns 7 times, so it steps 36 times
+        assert_eq!(frames.len(), 37);
+        assert_eq!(last.matches('O').count(), 36);
+    }
+
+    #[test]
+    fn example_day_16_missing_goal() {
+        let err = Maze::from(&String::from("#####\n#S..#\n#####\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::MissingMarker('E'));
+    }
+
+    #[test]
+    fn example_day_16_bad_tile() {
+        let err = Maze::from(&String::from("#####\n#S.E#\n##?##\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('?'));
+        assert_eq!((err.line, err.column), (3, 3));
+    }
 }
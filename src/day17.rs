@@ -1,35 +1,61 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::thread;
 use std::collections::VecDeque;
 
 use rustc_hash::{FxHashMap,FxHashSet};
+use log::{debug, trace};
+
+use crate::config::NoConfig;
+use crate::error::{column_of, parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day17/input.txt";
 }
 
+const DAY: usize = 17;
+
 #[derive(Debug, Clone)]
 pub struct Program {
     instructions: Vec<isize>,
 }
 
 impl Program {
-    pub fn from(input: &String) -> Self {
-        let mut instructions: Vec<isize> = vec![];
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        let (line_idx, line, raw_ins) = input.lines().enumerate()
+            .find_map(|(line_idx, line)| line.strip_prefix("Program: ").map(|raw_ins| (line_idx, line, raw_ins)))
+            .ok_or_else(|| ParseError::new(DAY, ParseErrorKind::MissingToken("program line")))?;
 
-        for line in input.lines() {
-            if line.starts_with("Program: ") {
-                let (_, raw_ins) = line.split_once(" ").unwrap();
-                for i in raw_ins.split(',') {
-                    instructions.push(i.parse::<isize>().unwrap());
-                }
+        let mut instructions: Vec<isize> = vec![];
+        for i in raw_ins.split(',') {
+            let val = parse_num::<isize>(DAY, line_idx, line, i)?;
+            // operands to the combo ops can't be 7, it's reserved
+            let is_combo_operand = instructions.len() % 2 == 1
+                && matches!(instructions.last(), Some(0 | 2 | 5 | 6 | 7));
+            let expected = match (val, is_combo_operand) {
+                (0..=6, _) | (7, false) => None,
+                (7, true) => Some("a combo operand below 7"),
+                _ => Some("a 3-bit value"),
+            };
+            if let Some(expected) = expected {
+                return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedToken(expected))
+                    .at(line_idx, column_of(line, i))
+                    .with_text(line));
             }
+            instructions.push(val);
+        }
+        // every opcode is followed by its operand
+        if instructions.len() % 2 == 1 {
+            return Err(ParseError::new(DAY, ParseErrorKind::MissingToken("operand"))
+                .at(line_idx, line.len())
+                .with_text(line));
         }
 
-        Self {
+        Ok(Self {
             instructions,
-        }
+        })
     }
 }
 
@@ -71,7 +97,7 @@ impl Proc {
 
     pub fn _debug(&self, s: String) {
         if self.debug {
-            println!("{}", s);
+            debug!("{}", s);
         }
     }
 
@@ -162,39 +188,47 @@ impl Proc {
         }
     }
 
-    pub fn from(input: &String) -> Self {
-        let mut reg_a: isize = isize::MAX;
-        let mut reg_b: isize = isize::MAX;
-        let mut reg_c: isize = isize::MAX;
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        if input.trim().is_empty() {
+            return Err(ParseError::new(DAY, ParseErrorKind::Empty));
+        }
+        let mut reg_a: Option<isize> = None;
+        let mut reg_b: Option<isize> = None;
+        let mut reg_c: Option<isize> = None;
 
-        for line in input.lines() {
+        for (line_idx, line) in input.lines().enumerate() {
             if line.contains("Register") {
-                let (reg_label, raw_val) = line.split_once(": ").unwrap();
-                let val = raw_val.parse::<isize>().unwrap();
+                let (reg_label, raw_val) = line.split_once(": ").ok_or_else(|| {
+                    ParseError::new(DAY, ParseErrorKind::MissingToken("': '"))
+                        .at(line_idx, line.len())
+                        .with_text(line)
+                })?;
+                let val = parse_num::<isize>(DAY, line_idx, line, raw_val)?;
 
                 match reg_label {
                     "Register A" => {
-                        reg_a = val;
+                        reg_a = Some(val);
                     },
                     "Register B" => {
-                        reg_b = val;
+                        reg_b = Some(val);
                     },
                     "Register C" => {
-                        reg_c = val;
+                        reg_c = Some(val);
                     },
                     _ => (),
                 }
 
             }
         }
-        Self {
-            reg_a,
-            reg_b,
-            reg_c,
+        let missing = |what| ParseError::new(DAY, ParseErrorKind::MissingToken(what));
+        Ok(Self {
+            reg_a: reg_a.ok_or_else(|| missing("Register A line"))?,
+            reg_b: reg_b.ok_or_else(|| missing("Register B line"))?,
+            reg_c: reg_c.ok_or_else(|| missing("Register C line"))?,
             debug: false,
             ins_ptr: 0,
             output: vec![],
-        }
+        })
 
     }
     
@@ -293,25 +327,27 @@ impl Proc {
     }
 }
 
-pub fn solution1(path: &PathBuf) -> String {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<String, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-pub fn _solution1(input: &String) -> String {
-    let mut proc = Proc::from(&input);
-    let prog = Program::from(&input);
-    println!("{:#?}", proc);
-    println!("{:#?}", prog);
-    let output = proc.execute(&prog);
-    println!("{:#?}", proc);
+pub fn solution1_str(input: &str) -> Result<String, ParseError> {
+    Ok(run_program(Proc::from(input)?, &Program::from(input)?))
+}
+
+fn run_program(mut proc: Proc, prog: &Program) -> String {
+    debug!("{:#?}", proc);
+    debug!("{:#?}", prog);
+    let output = proc.execute(prog);
+    debug!("{:#?}", proc);
     let output_as_strs: Vec<String> = output.iter().map(|i| i.to_string()).collect();
     output_as_strs.join(",")
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
 
@@ -402,12 +438,13 @@ A = ((A) / (1 << 3))
 
 */
 
-pub fn _test_a_val(input: &String, a: isize) {
-    let mut proc = Proc::from(&input);
-    let prog = Program::from(&input);
+pub fn _test_a_val(input: &str, a: isize) -> Result<(), ParseError> {
+    let mut proc = Proc::from(input)?;
+    let prog = Program::from(input)?;
     proc.reg_a = a;
     let output = proc.execute(&prog);
-    println!("{:?}", output);
+    debug!("{:?}", output);
+    Ok(())
 }
 /*
 
@@ -435,9 +472,11 @@ output: 2	A: 1	Ab: 000001
 //            if solution[1] == prog.ins[15-1]
             
 
-pub fn _solution2(input: &String) -> isize {
-    let prog = Program::from(&input);
-    let init_proc = Proc::from(&input);
+pub fn solution2_str(input: &str) -> Result<isize, ParseError> {
+    Ok(find_self_output_a(&Proc::from(input)?, &Program::from(input)?))
+}
+
+fn find_self_output_a(init_proc: &Proc, prog: &Program) -> isize {
 
 //    let mut first_vals: FxHashMap<(isize, isize), isize> = FxHashMap::default();
 //    let mut done = false;
@@ -481,13 +520,13 @@ pub fn _solution2(input: &String) -> isize {
                         if proc.output == prog.instructions[prog.instructions.len() - proc.output.len()..] {
                             if power < 15 {
                                 to_process.push_back((power + 1, a*8, result.clone()));
-                                println!("power: {}\t{:?}", power, result);
+                                trace!("power: {}\t{:?}", power, result);
                             }
                         }
                         if power == 15 {
                             if result == prog.instructions {
-                                println!("result found. A: {}", a);
-                                println!("{:?}", result);
+                                debug!("result found. A: {}", a);
+                                debug!("{:?}", result);
                                 results.push(a);
                             }
                         }
@@ -509,7 +548,7 @@ pub fn _solution2(input: &String) -> isize {
     }
     
     results.sort();
-    println!("{:?}", results);
+    debug!("{:?}", results);
     results[0]
 
 
@@ -777,6 +816,25 @@ pub fn _solution2(input: &String) -> isize {
 //    panic!("no solution found");
 //}
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = (Proc, Program);
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((Proc::from(input)?, Program::from(input)?))
+    }
+
+    fn part1((proc, prog): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(run_program(proc.clone(), prog).into())
+    }
+
+    fn part2((proc, prog): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(find_self_output_a(proc, prog).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
@@ -785,7 +843,7 @@ mod tests  {
     #[test]
     fn test_example_day_17_1_1() {
         let path = common::get_test_data_path("day17/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, String::from("4,6,3,5,6,3,5,2,1,0"))
     }
 
@@ -843,7 +901,41 @@ mod tests  {
     #[test]
     fn test_example_day_17_2() {
         let path = common::get_test_data_path("day17/case2.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 117440);
     }
+
+    #[test]
+    fn test_example_day_17_bad_register() {
+        let input = String::from("Register A: 729\nRegister B: zero\n");
+        let err = Proc::from(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!((err.line, err.column), (2, 13));
+    }
+
+    #[test]
+    fn test_example_day_17_missing_register() {
+        let input = String::from("Register A: 729\nRegister C: 0\n\nProgram: 0,1\n");
+        let err = solution1_str(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("Register B line"));
+        assert_eq!(solution1_str("").unwrap_err().kind, ParseErrorKind::Empty);
+    }
+
+    #[test]
+    fn test_example_day_17_bad_program() {
+        let registers = "Register A: 729\nRegister B: 0\nRegister C: 0\n\n";
+        let err = Program::from(registers).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("program line"));
+
+        let err = Program::from(&format!("{}Program: 0,1,5", registers)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("operand"));
+        assert_eq!((err.line, err.column), (5, 15));
+
+        let err = Program::from(&format!("{}Program: 1,7,5,7", registers)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("a combo operand below 7"));
+        assert_eq!((err.line, err.column), (5, 16));
+
+        let err = Program::from(&format!("{}Program: 0,8", registers)).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("a 3-bit value"));
+    }
 }
@@ -1,238 +1,183 @@
+use std::error::Error;
+use std::fmt;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 
-use rustc_hash::{FxHashMap, FxHashSet};
+use rustc_hash::FxHashSet;
+use serde::Deserialize;
 
+use crate::common::grid::{Grid, Ipos};
+use crate::common::search;
+use crate::config;
+use crate::error::{expect_token, parse_num, ParseError};
+use crate::solution::{Answer, Solution};
+
+#[derive(Clone)]
 enum Tile {
     Safe,
     Corrupt,
 }
 
-#[derive(PartialEq, Eq, Hash, Clone)]
-struct Loc {
-    x: isize,
-    y: isize,
-}
-
 struct MemMap {
-    m: FxHashMap<Loc, Tile>,
-    x_max: isize,
-    y_max: isize,
-    start: Loc,
-    goal: Loc,
+    m: Grid<Tile>,
+    start: Ipos,
+    goal: Ipos,
 }
 
 impl MemMap {
-    pub fn new(x_max: isize, y_max: isize, corrupt_locs: &FxHashSet<Loc>) -> Self {
-        let mut m = FxHashMap::<Loc, Tile>::default();
-        for x in 0..=x_max {
-            for y in 0..=y_max {
-                let loc = Loc { x, y };
-                if corrupt_locs.contains(&loc) {
-                    m.entry(loc).or_insert(Tile::Corrupt);
-                } else {
-                    m.entry(loc).or_insert(Tile::Safe);
-                }
+    pub fn new(x_max: isize, y_max: isize, corrupt_locs: &FxHashSet<Ipos>) -> Self {
+        let width = usize::try_from(x_max + 1).unwrap();
+        let height = usize::try_from(y_max + 1).unwrap();
+        let mut m = Grid::new(width, height, Tile::Safe);
+        for loc in corrupt_locs.iter() {
+            if let Some(tile) = m.get_mut(*loc) {
+                *tile = Tile::Corrupt;
             }
         }
         MemMap {
             m,
-            x_max,
-            y_max,
-            start: Loc { x: 0, y: 0 },
-            goal: Loc { x: x_max, y: y_max },
+            start: (0, 0),
+            goal: (x_max, y_max),
         } 
     }
 }
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day18/input.txt";
-    pub const X_MAX: isize = 70;
-    pub const Y_MAX: isize = 70;
-    pub const BYTES_FALLEN: isize = 1024;
 }
 
-fn parse_corrupt_locs(input: &String, mut bytes_fallen: isize) -> FxHashSet<Loc> {
-    let mut result = FxHashSet::<Loc>::default();
-    for line in input.lines() {
-        let loc: Vec<&str> = line.split(',').collect();
-        let x = loc[0].parse::<isize>().unwrap();
-        let y = loc[1].parse::<isize>().unwrap();
-        result.insert(Loc { x, y });
-        bytes_fallen -= 1;
-        if bytes_fallen == 0 {
-            break;
-        }
+const DAY: usize = 18;
+
+// the memory space runs from (0, 0) to (x_max, y_max), part 1 looks at it once bytes_fallen have landed
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    #[serde(deserialize_with = "config::non_negative")]
+    pub x_max: isize,
+    #[serde(deserialize_with = "config::non_negative")]
+    pub y_max: isize,
+    #[serde(deserialize_with = "config::non_negative")]
+    pub bytes_fallen: isize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self { x_max: 70, y_max: 70, bytes_fallen: 1024 }
     }
-    result
 }
 
-fn get_valid_moves(loc: &Loc, mmap: &MemMap) -> Vec<Loc> {
-    let deltas = vec![
-        (0, 1),
-        (0, -1),
-        (1, 0),
-        (-1, 0),
-    ];
-    let mut result: Vec<Loc> = vec![];
-    for d in deltas.iter() {
-        let next_loc = Loc { x: loc.x + d.0, y: loc.y + d.1 };
-        if next_loc.x < 0 || next_loc.y < 0 {
-            continue;
-        } else if next_loc.x > mmap.x_max || next_loc.y > mmap.y_max {
-            continue;
-        }
-        match mmap.m.get(&next_loc).unwrap() {
-            Tile::Safe => {
-                result.push(next_loc);
-            },
-            Tile::Corrupt => (),
-        }
+// every byte fell and there was still a way out, so part 2 has no answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NeverBlocked {
+    pub bytes: usize,
+}
+
+impl fmt::Display for NeverBlocked {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the way out is still open after all {} bytes have fallen", self.bytes)
     }
-    result
 }
 
-fn shortest_path(mmap: &MemMap) -> Option<isize> {
-    let mut best_paths = FxHashMap::<Loc, Vec<Vec<Loc>>>::default();
-    let mut frontier = FxHashSet::<Loc>::default();
+impl Error for NeverBlocked {}
 
-    let mut goal_reached = false;
+// the bytes that fell for part 1 already cut the way out off
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoWayOut {
+    pub bytes_fallen: isize,
+}
 
-    frontier.insert(mmap.start.clone());
-    best_paths.entry(mmap.start.clone()).or_insert(vec![vec![]]);
-    let mut added = false;
+impl fmt::Display for NoWayOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "there's no way out once {} bytes have fallen", self.bytes_fallen)
+    }
+}
 
-    loop {
-        let mut next_frontier = FxHashSet::<Loc>::default();
+impl Error for NoWayOut {}
 
-        if goal_reached {
-            break;
-        }
-        added = false;
-        for frontier_loc in frontier.iter() {
-            let best_len = best_paths.get(&frontier_loc).unwrap()[0].len();
-
-            let next_locs = get_valid_moves(&frontier_loc, &mmap);
-
-            for nl in next_locs.iter() {
-                if *nl == mmap.goal {
-                    goal_reached = true;
-                }
-                if best_paths.contains_key(&nl) {
-                    let nl_best = best_paths.get(&nl).unwrap()[0].len();
-                    if best_len + 1 < nl_best {
-                        // new best
-                        let mut updated_paths = best_paths.get(&frontier_loc).unwrap().clone();
-                        for path in updated_paths.iter_mut() {
-                            path.push(nl.clone());
-                        }
-                        best_paths.entry(nl.clone()).or_insert(updated_paths);
-                        next_frontier.insert(nl.clone());
-                        added = true;
-                    } else if best_len + 1 == nl_best {
-                        // same best
-                        // for now, do nothing, just keep whatever was the current best
-                        //let mut updated_paths = best_paths.get(&frontier_loc).unwrap().clone();
-                        //for path in updated_paths.iter_mut() {
-                        //    path.push(nl.clone());
-                        //}
-                        //let nl_best_paths = best_paths.get_mut(&nl).unwrap();
-                        //nl_best_paths.append(&mut updated_paths);
-                        //next_frontier.insert(nl.clone());
-                    } else {
-                        // worse path
-                        // don't continue processing
-                    }
-                } else {
-                    // new best
-                    let mut updated_paths = best_paths.get(&frontier_loc).unwrap().clone();
-                    for path in updated_paths.iter_mut() {
-                        path.push(nl.clone());
-                    }
-                    best_paths.entry(nl.clone()).or_insert(updated_paths);
-                    next_frontier.insert(nl.clone());
-                    added = true;
-                }
-            }
-        }
-        if !added {
-            break;
-        }
-        let best_path_locs: Vec<Loc> = best_paths.keys().map(|k| k.clone()).collect();
-        for l in best_path_locs.iter() {
-            if frontier.contains(&l) || next_frontier.contains(&l) {
-                // do nothing
-            } else {
-                best_paths.remove(&l);
-            }
+pub fn parse_falling_bytes(input: &str) -> Result<Vec<Ipos>, ParseError> {
+    let mut result = vec![];
+    for (line_idx, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            continue;
         }
-        frontier = next_frontier;
-    }
-    if added {
-        Some(isize::try_from(best_paths.get(&mmap.goal).unwrap()[0].len()).unwrap())
-    } else {
-        None
+        let mut loc = line.split(',');
+        let x = parse_num::<isize>(DAY, line_idx, line, expect_token(DAY, line_idx, line, loc.next(), "x")?)?;
+        let y = parse_num::<isize>(DAY, line_idx, line, expect_token(DAY, line_idx, line, loc.next(), "y")?)?;
+        result.push((x, y));
     }
-    /*
-        best_paths: FxHashMap<Loc, Vec<Vec<Loc>>
-        frontier: FxHashSet<Loc>
-
-        let mut goal_reached = false;
-        loop {
-            if goal_reached {
-                break
-            }
-            let mut next_frontier;
-            for frontier_loc in frontier {
-                let best = best_paths.get(frontier_loc).unwrap()[0].len()
-                valid_next_locs = get_valid_moves(frontier_loc, mmap)
-                for nl in valid_next_locs {
-                    if no best path to nl {
-                        add one
-                        add nl to frontier
-                    } else if best + 1 < best to nl {
-                        add one
-                        add nl to frontier
-                    } else if best + 1 == best to nl {
-                        push one
-                        add nl to frontier
-                    } else {
-                        do nothing
-                    }
-                }
-            }
-        }
-* */
+    Ok(result)
+}
 
+fn corrupt_locs(falling_bytes: &[Ipos], bytes_fallen: usize) -> FxHashSet<Ipos> {
+    falling_bytes.iter().take(bytes_fallen).cloned().collect()
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input, constants::X_MAX, constants::Y_MAX, constants::BYTES_FALLEN)
+fn get_valid_moves(loc: &Ipos, mmap: &MemMap) -> Vec<Ipos> {
+    mmap.m.neighbors4(*loc)
+        .filter(|n| matches!(mmap.m.get(*n), Some(Tile::Safe)))
+        .collect()
+}
+
+fn shortest_path(mmap: &MemMap) -> Option<isize> {
+    let steps = search::bfs_to(mmap.start, |loc| get_valid_moves(loc, mmap), |loc| *loc == mmap.goal)?;
+    Some(isize::try_from(steps).unwrap())
 }
 
-pub fn _solution1(input: &String, x_max: isize, y_max: isize, bytes_fallen: isize) -> isize {
-    let corrupt_locs = parse_corrupt_locs(&input, bytes_fallen);
-    let mmap = MemMap::new(x_max, y_max, &corrupt_locs);
-    shortest_path(&mmap).unwrap()
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    solution1_str(&input, &Config::default())
 }
 
-pub fn solution2(path: &PathBuf) -> String {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input, constants::X_MAX, constants::Y_MAX, constants::BYTES_FALLEN)
+pub fn solution1_str(input: &str, config: &Config) -> Result<isize, Box<dyn Error>> {
+    Ok(min_steps(&parse_falling_bytes(input)?, config)?)
 }
 
-pub fn _solution2(input: &String, x_max: isize, y_max: isize, mut bytes_fallen: isize) -> String {
-    loop {
-        let corrupt_locs = parse_corrupt_locs(&input, bytes_fallen);
-        let mmap = MemMap::new(x_max, y_max, &corrupt_locs);
-        match shortest_path(&mmap) {
-            None => break,
-            _ => bytes_fallen += 1,
+fn min_steps(falling_bytes: &[Ipos], config: &Config) -> Result<isize, NoWayOut> {
+    let corrupt_locs = corrupt_locs(falling_bytes, usize::try_from(config.bytes_fallen).unwrap());
+    let mmap = MemMap::new(config.x_max, config.y_max, &corrupt_locs);
+    shortest_path(&mmap).ok_or(NoWayOut { bytes_fallen: config.bytes_fallen })
+}
+
+pub fn solution2(path: impl AsRef<Path>) -> Result<String, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    solution2_str(&input, &Config::default())
+}
+
+pub fn solution2_str(input: &str, config: &Config) -> Result<String, Box<dyn Error>> {
+    Ok(first_blocking_byte(&parse_falling_bytes(input)?, config)?)
+}
+
+// the way is open before any bytes fall, so the search starts with at least one
+fn first_blocking_byte(falling_bytes: &[Ipos], config: &Config) -> Result<String, NeverBlocked> {
+    let first = usize::try_from(config.bytes_fallen).unwrap().min(falling_bytes.len()).max(1);
+    for bytes_fallen in first..=falling_bytes.len() {
+        let corrupt_locs = corrupt_locs(falling_bytes, bytes_fallen);
+        let mmap = MemMap::new(config.x_max, config.y_max, &corrupt_locs);
+        if shortest_path(&mmap).is_none() {
+            let (x, y) = falling_bytes[bytes_fallen - 1];
+            return Ok(format!("{},{}", x, y));
         }
     }
-    let line_idx = usize::try_from(bytes_fallen - 1).unwrap();
-    input.lines().collect::<Vec<_>>()[line_idx].to_string()
+    Err(NeverBlocked { bytes: falling_bytes.len() })
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Ipos>;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_falling_bytes(input)
+    }
+
+    fn part1(falling_bytes: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(min_steps(falling_bytes, config)?.into())
+    }
+
+    fn part2(falling_bytes: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(first_blocking_byte(falling_bytes, config)?.into())
+    }
 }
 
 #[cfg(test)]
@@ -240,23 +185,45 @@ mod tests  {
     use super::*;
     use crate::common::get_test_data_path;
 
+    const EXAMPLE: Config = Config { x_max: 6, y_max: 6, bytes_fallen: 12 };
+
     #[test]
     fn day_18_1_0() {
-        let bytes_fallen = 12;
-        let (max_x, max_y) = (6, 6);
         let path = get_test_data_path("day18/case1.txt").unwrap();
         let input =  fs::read_to_string(path).unwrap();
-        let result = _solution1(&input, max_x, max_y, bytes_fallen);
+        let result = solution1_str(&input, &EXAMPLE).unwrap();
         assert_eq!(result, 22, "found min steps")
     }
 
     #[test]
     fn day_18_2_0() {
-        let bytes_fallen = 12;
-        let (max_x, max_y) = (6, 6);
         let path = get_test_data_path("day18/case1.txt").unwrap();
         let input =  fs::read_to_string(path).unwrap();
-        let result = _solution2(&input, max_x, max_y, bytes_fallen);
+        let result = solution2_str(&input, &EXAMPLE).unwrap();
         assert_eq!(result, "6,1", "found min steps")
     }
+
+    #[test]
+    fn day_18_1_no_way_out() {
+        let path = get_test_data_path("day18/case1.txt").unwrap();
+        let input =  fs::read_to_string(path).unwrap();
+        let config = Config { bytes_fallen: 25, ..EXAMPLE };
+        let err = Day18::part1(&Day18::parse(&input).unwrap(), &config).unwrap_err();
+        assert_eq!(err.downcast_ref::<NoWayOut>(), Some(&NoWayOut { bytes_fallen: 25 }));
+    }
+
+    #[test]
+    fn day_18_2_never_blocked() {
+        let path = get_test_data_path("day18/case1.txt").unwrap();
+        let input =  fs::read_to_string(path).unwrap();
+        let err = Day18::part2(&Day18::parse(&input).unwrap(), &Config::default()).unwrap_err();
+        assert_eq!(err.downcast_ref::<NeverBlocked>(), Some(&NeverBlocked { bytes: 25 }));
+    }
+
+    #[test]
+    fn day_18_missing_y() {
+        let err = parse_falling_bytes(&String::from("5,4\n4,2\n4\n")).err().unwrap();
+        assert_eq!(err.kind, crate::error::ParseErrorKind::MissingToken("y"));
+        assert_eq!((err.line, err.column), (3, 2));
+    }
 }
@@ -1,9 +1,16 @@
 use std::collections::VecDeque;
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::thread;
 
 use rustc_hash::{FxHashSet, FxHashMap};
+use log::{debug, trace};
+use rayon::prelude::*;
+
+use crate::config::NoConfig;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 
 
@@ -11,22 +18,31 @@ pub mod constants {
     pub const INPUT_PATH: &str = "day19/input.txt";
 }
 
-fn parse_towels(input: &String) -> FxHashSet<String> {
-    let towels_line = input.lines().next().unwrap();
-    towels_line.split(", ").map(|i| i.to_string()).collect()
+const DAY: usize = 19;
+
+fn parse_towels(input: &str) -> Result<FxHashSet<String>, ParseError> {
+    let towels_line = match input.lines().next() {
+        Some(line) if !line.is_empty() => line,
+        _ => return Err(ParseError::new(DAY, ParseErrorKind::Empty)),
+    };
+    Ok(towels_line.split(", ").map(|i| i.to_string()).collect())
 }
 
-fn parse_designs(input: &String) -> Vec<String> {
+fn parse_designs(input: &str) -> Result<Vec<String>, ParseError> {
     let mut lines = input.lines();
     lines.next(); // towel patterns
-    lines.next(); // blank
+    if let Some(line) = lines.next().filter(|l| !l.is_empty()) { // blank
+        return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedToken("blank line"))
+            .on_line(1)
+            .with_text(line));
+    }
 
-    lines.map(|s| s.to_string()).collect()
+    Ok(lines.map(|s| s.to_string()).collect())
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
 fn can_build(towels: &FxHashSet<String>, design: &str, mut max_len: usize) -> bool {
@@ -103,11 +119,11 @@ fn count_combos<'a>(towels: &FxHashSet<String>, design: &'a str, mut max_len: us
     count
 }
 
-pub fn _solution1(input: &String) -> isize {
-    let towels = parse_towels(&input);
-    //println!("{:#?}", towels);
-    let designs = parse_designs(&input);
+pub fn solution1_str(input: &str) -> Result<isize, ParseError> {
+    Ok(count_possible_designs(&parse_towels(input)?, &parse_designs(input)?))
+}
 
+fn count_possible_designs(towels: &FxHashSet<String>, designs: &[String]) -> isize {
     let mut max_len = 0;
 
     for towel in towels.iter() {
@@ -115,22 +131,19 @@ pub fn _solution1(input: &String) -> isize {
     }
 
 
-    let mut num_possible = 0;
-    //let mut prefixes = VecDeque::new();
+    let num_possible = designs.par_iter()
+        .filter(|d| {
+            trace!("processing: {}", d);
+            can_build(towels, d, max_len)
+        })
+        .count();
 
-    for d in designs.iter() {
-        println!("processing: {}", d);
-        if can_build(&towels, d, max_len) {
-            num_possible += 1;
-        }
-    }
-
-    num_possible
+    isize::try_from(num_possible).unwrap()
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
 pub fn get_all_combinations(a: &Vec<String>, b: &Vec<String>) -> Vec<(String, String)> {
@@ -145,10 +158,12 @@ pub fn get_all_combinations(a: &Vec<String>, b: &Vec<String>) -> Vec<(String, St
 }
 
 
-pub fn _solution2(input: &String) -> isize {
-    let towels = parse_towels(&input);
-    println!("towels count: {}", towels.len());
-    let designs = parse_designs(&input);
+pub fn solution2_str(input: &str) -> Result<isize, ParseError> {
+    Ok(count_all_arrangements(&parse_towels(input)?, &parse_designs(input)?))
+}
+
+fn count_all_arrangements(towels: &FxHashSet<String>, designs: &[String]) -> isize {
+    debug!("towels count: {}", towels.len());
 
     let mut max_towel_len = 0;
     for t in towels.iter() {
@@ -157,13 +172,12 @@ pub fn _solution2(input: &String) -> isize {
         }
     }
 
-    max_towel_len = max_towel_len.min(input.len());
-    let mut sum = 0;
-    for d in designs.iter() {
-        println!("processing: {}", d);
-        sum += count_design_combos(&towels, d, max_towel_len);
-    }
-    sum
+    designs.par_iter()
+        .map(|d| {
+            trace!("processing: {}", d);
+            count_design_combos(towels, d, max_towel_len)
+        })
+        .sum()
 }
 
 pub fn count_design_combos(towels: &FxHashSet<String>, design: &str, biggest_towel_len: usize) -> isize {
@@ -176,7 +190,8 @@ pub fn count_design_combos(towels: &FxHashSet<String>, design: &str, biggest_tow
         let min_len = midpt - base_idx + 1;
         for t in towels.iter() {
             if t.len() >= min_len {
-                if towels.contains(&design[base_idx..base_idx + t.len()]) && &design[base_idx..base_idx + t.len()] == t {
+                // towels that would run off the end of the design can't cross the midpoint in it
+                if design.get(base_idx..base_idx + t.len()) == Some(t.as_str()) {
                     gap_crossing_patterns.push((t.clone(), base_idx));
                 }
             }
@@ -233,22 +248,80 @@ pub fn count_design_combos(towels: &FxHashSet<String>, design: &str, biggest_tow
 //    combos
 //}
 
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (FxHashSet<String>, Vec<String>);
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok((parse_towels(input)?, parse_designs(input)?))
+    }
+
+    fn part1((towels, designs): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_possible_designs(towels, designs).into())
+    }
+
+    fn part2((towels, designs): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_all_arrangements(towels, designs).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common::{self, get_test_data_path};
+    use crate::common::prop::{self, in_range, string_of};
+    use quickcheck::{Arbitrary, Gen};
+
+    #[derive(Debug, Clone)]
+    struct TowelCase {
+        towels: FxHashSet<String>,
+        design: String,
+    }
+
+    // a small alphabet so designs can usually be built, and often in many ways
+    impl Arbitrary for TowelCase {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let alphabet = ['w', 'u', 'b'];
+            let num_towels = in_range(g, 1..=6);
+            let towels = (0..num_towels)
+                .map(|_| {
+                    let len = in_range(g, 1..=4);
+                    string_of(g, &alphabet, len)
+                })
+                .collect();
+            let design_len = in_range(g, 0..=14);
+            Self { towels, design: string_of(g, &alphabet, design_len) }
+        }
+    }
 
     #[test]
     fn day_19_1() {
         let path = get_test_data_path("day19/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 6, "correct possible combos identified");
     }
 
     #[test]
     fn day_19_2() {
         let path = get_test_data_path("day19/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 16, "correct possible combos identified");
     }
+
+    #[test]
+    fn design_combos_match_count_combos() {
+        prop::check(|case: &TowelCase| {
+            let max_len = case.towels.iter().map(|t| t.len()).max().unwrap();
+            count_design_combos(&case.towels, &case.design, max_len) == count_combos(&case.towels, &case.design, max_len)
+        });
+    }
+
+    #[test]
+    fn day_19_missing_separator() {
+        let err = solution1_str(&String::from("r, wr, b\nbrwrr\nbggr\n")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("blank line"));
+        assert_eq!(err.line, 2);
+    }
 }
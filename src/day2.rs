@@ -1,320 +1,320 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::error::Error;
-use std::fs;
+use std::io::BufRead;
 use std::fmt;
-use std::rc::Rc;
-use std::cell::RefCell;
-use regex::Regex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
+use crate::stream::{self, StreamError};
 
 pub mod constants {
     pub const REPORT_FILE_PATH: &str = "day2/reports.txt";
 }
 
-struct Report {
-    levels: Vec<u64>,
-}
+const DAY: usize = 2;
 
-enum LevelOrdering {
-    AllIncrementing,
-    AllDecrementing,
-    NotOrdered,
+// what makes a report safe: the steps between levels all go the same way and are between
+// min_step and max_step, inclusive. part 2 lets max_removals levels be dropped to get there
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub min_step: u64,
+    pub max_step: u64,
+    pub max_removals: usize,
 }
 
-fn get_ordering(levels: &Vec<u64>) -> LevelOrdering {
-    if levels.len() == 1 {
-        LevelOrdering::AllIncrementing
-    } else {
-        let first_level = levels[0];
-        let second_level = levels[1];
-        let expected_ordering = if first_level > second_level {
-            LevelOrdering::AllDecrementing
-        } else if second_level > first_level {
-            LevelOrdering::AllIncrementing
-        } else {
-            LevelOrdering::NotOrdered
-        };
-
-        let mut prev_level = first_level;
-
-        for level in &levels[1..] {
-            match expected_ordering {
-                LevelOrdering::AllIncrementing => {
-                    if !(*level > prev_level) {
-                        return LevelOrdering::NotOrdered;
-                    }
-                },
-                LevelOrdering::AllDecrementing => {
-                    if !(*level < prev_level) {
-                        return LevelOrdering::NotOrdered;
-                    }
-                },
-                LevelOrdering::NotOrdered => return LevelOrdering::NotOrdered,
-            };
-            prev_level = *level;
-        }
-        expected_ordering
+impl Default for Config {
+    fn default() -> Self {
+        Self { min_step: 1, max_step: 3, max_removals: 1 }
     }
 }
 
-fn gapping_valid(levels: &Vec<u64>) -> bool {
-    if levels.len() == 1 {
-        true
-    } else {
-        let mut prev_level = levels[0];
-        let mut result = true;
-
-        for level in &levels[1..] {
-            let abs_diff = level.abs_diff(prev_level);
-            if abs_diff > 3 || abs_diff < 1 {
-                result = false;
-            }
-            prev_level = *level;
-        }
-        result
+impl Config {
+    // a level that stays put goes neither way, so it fits both as long as min_step is 0
+    fn step_valid(&self, from: u64, to: u64, ascending: bool) -> bool {
+        let diff = from.abs_diff(to);
+        (diff == 0 || (to > from) == ascending) && diff >= self.min_step && diff <= self.max_step
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-enum EdgeKind {
-    Ascending,
-    Descending,
+pub struct Report {
+    levels: Vec<u64>,
 }
 
-impl fmt::Display for EdgeKind {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let txt = match self {
-            EdgeKind::Ascending => "ASC",
-            EdgeKind::Descending => "DESC",
-        };
-        write!(f, "{}", txt)
+// the index of the first level that goes the other way from the first step that goes anywhere.
+// a level that stays put goes neither way, like in Config::step_valid, so whether that's allowed
+// is left to first_bad_gap
+fn first_ordering_break(levels: &[u64]) -> Option<usize> {
+    let mut ascending = None;
+    for (idx, pair) in levels.windows(2).enumerate() {
+        if pair[0] == pair[1] {
+            continue;
+        }
+        let up = pair[1] > pair[0];
+        match ascending {
+            None => ascending = Some(up),
+            Some(ascending) if ascending != up => return Some(idx + 1),
+            _ => (),
+        }
     }
+    None
 }
 
-struct Edge {
-    cost: usize,
-    dest_node: Rc<RefCell<Node>>,
-    kind: EdgeKind,
-}
-
-struct Node {
-    value: Option<u64>,
-    edges: Vec<Rc<RefCell<Edge>>>,
+// the index of the first level that's too close to or too far from the one before it
+fn first_bad_gap(levels: &[u64], config: &Config) -> Option<usize> {
+    levels.windows(2)
+        .position(|pair| {
+            let abs_diff = pair[0].abs_diff(pair[1]);
+            abs_diff < config.min_step || abs_diff > config.max_step
+        })
+        .map(|idx| idx + 1)
 }
 
-struct LevelsGraph {
-    nodes: Vec<Rc<RefCell<Node>>>,
-    root: Rc<RefCell<Node>>,
+// what's wrong with a report, if anything
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    Safe,
+    Unordered,
+    BadGap,
+    UnorderedAndBadGap,
 }
 
-impl fmt::Display for LevelsGraph {
+impl fmt::Display for Failure {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // pad rather than write so the table columns line up
+        f.pad(match self {
+            Failure::Safe => "safe",
+            Failure::Unordered => "unordered",
+            Failure::BadGap => "bad gap",
+            Failure::UnorderedAndBadGap => "unordered, bad gap",
+        })
+    }
+}
 
-        for src_node in self.nodes.iter().map(|n| n.borrow()) {
-            let src_node_identifier = match src_node.value {
-                Some(val) => val.to_string(),
-                None => String::from("ROOT"),
-            };
-
-            write!(f, "SRC NODE: {}\n", src_node_identifier)?;
-
-            for edge in src_node.edges.iter().map(|e| e.borrow()) {
-                write!(
-                    f,
-                    "\tEDGE:\tto:{}\tkind:{}\tcost:{}\n",
-                    edge.dest_node.borrow().value.unwrap(),
-                    edge.kind,
-                    edge.cost,
-                )?;
-            }
+// why a report is unsafe. the indices are of levels in the report
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnosis {
+    pub ordering_break: Option<usize>,
+    pub bad_gap: Option<usize>,
+    // whether dropping up to max_removals levels makes an unsafe report safe
+    pub fixed_by_dampening: bool,
+}
 
+impl Diagnosis {
+    pub fn failure(&self) -> Failure {
+        match (self.ordering_break, self.bad_gap) {
+            (None, None) => Failure::Safe,
+            (Some(_), None) => Failure::Unordered,
+            (None, Some(_)) => Failure::BadGap,
+            (Some(_), Some(_)) => Failure::UnorderedAndBadGap,
         }
-        Ok(())
-    } 
+    }
 }
 
-impl LevelsGraph {
-    const MAX_LEVEL_DIFF: u64 = 3;
-
-    fn new() -> Self {
-        // initialize a root node so that we can skip
-        // over the first level if needed
-        let root = Rc::new(RefCell::new(Node {
-            value: None,
-            edges: Vec::new(),
-        }));
-        let nodes = Vec::from([root.clone()]);
-        let graph = LevelsGraph {
-            nodes: nodes,
-            root: root.clone(),
-        };
-        graph 
-    }
-
-    fn is_safe(&self) -> bool {
-        let mut all_paths = self.get_all_paths();
-
-        all_paths.sort_by(|a, b| b.len().cmp(&a.len()));
-
-        if all_paths[0].len() > self.nodes.len() - 2 {
-            true
-        } else {
-            false
+impl Report {
+    fn from(levels_input: &str) -> Result<Self, ParseError> {
+        let mut levels: Vec<u64> = Vec::new();
+        for level in levels_input.split_whitespace() {
+            levels.push(parse_num(DAY, 0, levels_input, level)?);
         }
+        if levels.is_empty() {
+            return Err(ParseError::new(DAY, ParseErrorKind::Empty).at(0, 0));
+        }
+        Ok(Self {
+            levels,
+        })
+    }
+
+    pub fn is_safe(&self, config: &Config) -> bool {
+        self.is_safe_with_k_removals(0, config)
     }
 
-    fn _get_paths(&self, node: Rc<RefCell<Node>>, kind: EdgeKind, budget: usize) -> Vec<Vec<Rc<RefCell<Node>>>> {
-        // if current node has no edges of `kind`, we're done, return empty vector
-        let borrowed_node = node.borrow();
-        let safe_edges = borrowed_node.edges.iter().map(|e| e.borrow()).filter(|e| e.kind == kind && e.cost <= budget);
-        if safe_edges.clone().count() == 0 {
-            let mut result = Vec::new();
-            result.push(Vec::from([node.clone()]));
-            return result;
+    pub fn diagnose(&self, config: &Config) -> Diagnosis {
+        let ordering_break = first_ordering_break(&self.levels);
+        let bad_gap = first_bad_gap(&self.levels, config);
+        let unsafe_report = ordering_break.is_some() || bad_gap.is_some();
+        Diagnosis {
+            ordering_break,
+            bad_gap,
+            fixed_by_dampening: unsafe_report && self.is_safe_with_k_removals(config.max_removals, config),
         }
+    }
 
-        let mut result = Vec::new();
+    pub fn is_safe_with_dampening(&self, config: &Config) -> bool {
+        self.is_safe_with_k_removals(config.max_removals, config)
+    }
 
-        for edge in safe_edges {
-            let new_budget = budget - edge.cost;
-            let mut safe_paths = self._get_paths(edge.dest_node.clone(), kind, new_budget);
-            for safe_path in safe_paths.iter_mut() {
-                let mut path = Vec::from([node.clone()]);
-                path.append(safe_path);
-                result.push(path);
-            }
-        }
-        result
+    pub fn is_safe_with_k_removals(&self, k: usize, config: &Config) -> bool {
+        self.min_removals_to_safe(config) <= k
     }
 
-    fn get_all_paths(&self) -> Vec<Vec<Rc<RefCell<Node>>>> {
-        let root = self.root.clone();
-        let kinds = [EdgeKind::Ascending, EdgeKind::Descending];
-        let budget = 1;
-        let mut result: Vec<Vec<Rc<RefCell<Node>>>> = Vec::new();
-        for kind in kinds {
-            for path in self._get_paths(root.clone(), kind, budget) {
-                result.push(path);
-            }
-        }
-        result
-    }
-
-    fn add_level(&mut self, level: u64) {
-        // add a new node
-        let new_node = Rc::new(RefCell::new(Node {
-            value: Some(level),
-            edges: Vec::new(),
-        }));
-        self.nodes.push(new_node.clone());
-
-        // attempt to establish valid edges between the prev two nodes
-        // and the new node
-        let start_idx = usize::try_from(0i32.max(i32::try_from(self.nodes.len()).unwrap() - 3)).unwrap();
-        let new_val = new_node.borrow().value.unwrap();
-        for (i, prev_node) in self.nodes[start_idx..self.nodes.len()].iter().enumerate() {
-
-            // the cost is 1 when a level has to be skipped when creating an edge
-            // upon adding the first new node is the only time when our loop
-            // will have a window size of 1 (instead of 2)
-            let cost = if self.nodes.len() - start_idx >= 3 {
-                if i == 0 {
-                    1
-                } else {
-                    0
-                }
-            } else {
-                0
-            };
+    pub fn min_removals_to_safe(&self, config: &Config) -> usize {
+        self.removals_to_safe(config).len()
+    }
 
-            let mut pn = prev_node.borrow_mut();
-            match pn.value {
-                // links between two supplied levels
-                Some(prev_val) => {
-                    if prev_val < new_val && prev_val.abs_diff(new_val) <= Self::MAX_LEVEL_DIFF {
-                        pn.edges.push(Rc::new(RefCell::new(Edge {
-                            cost: cost,
-                            dest_node: new_node.clone(),
-                            kind: EdgeKind::Ascending,
-                        })));
-                    } else if prev_val > new_val && prev_val.abs_diff(new_val) <= Self::MAX_LEVEL_DIFF {
-                        pn.edges.push(Rc::new(RefCell::new(Edge {
-                            cost: cost,
-                            dest_node: new_node.clone(),
-                            kind: EdgeKind::Descending,
-                        })));
+    // the indices of the fewest levels to drop to leave a safe report. the levels that stay are
+    // the longest run of them, not necessarily adjacent, where each steps safely from the last,
+    // so for each level and direction this finds the longest such run ending there
+    pub fn removals_to_safe(&self, config: &Config) -> Vec<usize> {
+        let n = self.levels.len();
+        let mut best: Option<(usize, usize, Vec<Option<usize>>)> = None;
+        for ascending in [true, false] {
+            let mut run_len = vec![1; n];
+            let mut prev: Vec<Option<usize>> = vec![None; n];
+            for i in 0..n {
+                for j in 0..i {
+                    if run_len[j] + 1 > run_len[i] && config.step_valid(self.levels[j], self.levels[i], ascending) {
+                        run_len[i] = run_len[j] + 1;
+                        prev[i] = Some(j);
                     }
-                },
-                // this is the root node only
-                // always create valid links
-                // need to be able to skip over the first level
-                None => {
-                    pn.edges.push(Rc::new(RefCell::new(Edge {
-                        cost: cost,
-                        dest_node: new_node.clone(),
-                        kind: EdgeKind::Ascending,
-                    })));
-                    pn.edges.push(Rc::new(RefCell::new(Edge {
-                        cost: cost,
-                        dest_node: new_node.clone(),
-                        kind: EdgeKind::Descending,
-                    })));
+                }
+            }
+            if let Some(end) = (0..n).max_by_key(|i| (run_len[*i], n - i)) {
+                if best.as_ref().is_none_or(|(len, _, _)| run_len[end] > *len) {
+                    best = Some((run_len[end], end, prev));
                 }
             }
         }
-    }
-}
 
-impl Report {
-    fn from(levels_input: &str) -> Self {
-        let rx = Regex::new(r"\s+").expect("invalid regex");
-        let levels: Vec<u64> = rx.split(levels_input).map(|s| s.parse::<u64>().unwrap()).collect();
-        Self {
-            levels: levels,
+        let mut kept = vec![false; n];
+        if let Some((_, end, prev)) = best {
+            let mut at = Some(end);
+            while let Some(i) = at {
+                kept[i] = true;
+                at = prev[i];
+            }
         }
+        (0..n).filter(|i| !kept[*i]).collect()
     }
+}
 
-    fn is_safe(&self) -> bool {
-        match get_ordering(&self.levels) {
-            LevelOrdering::NotOrdered => false,
-            _ => gapping_valid(&self.levels),
-        }
-    }
 
-    fn is_safe_with_dampening(&self) -> bool {
-        let graph = self.build_graph();
-        graph.is_safe()
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let levels: Vec<String> = self.levels.iter().map(|l| l.to_string()).collect();
+        write!(f, "{}", levels.join(" "))
     }
+}
 
-    fn build_graph(&self) -> LevelsGraph {
-        let mut graph = LevelsGraph::new();
-
-        for level in &self.levels {
-            graph.add_level(*level);
-        }
+fn parse_report(line_idx: usize, line: &str) -> Result<Report, ParseError> {
+    Report::from(line).map_err(|e| e.on_line(line_idx))
+}
 
-        graph
-    }
+pub fn parse_reports(input: &str) -> Result<Vec<Report>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(line_idx, line)| parse_report(line_idx, line))
+        .collect()
 }
 
+pub fn count_reports<F: Fn(Report) -> bool>(file_path: impl AsRef<Path>, filter: F) -> Result<u64, Box<dyn Error>> {
+    Ok(count_streamed_reports(stream::open(&file_path)?, filter)?)
+}
 
-pub fn count_reports<F: Fn(Report) -> bool>(file_path: PathBuf, filter: F) -> Result<u64, Box<dyn Error>> {
+// only one report is held at a time, however long the input
+pub fn count_streamed_reports<R: BufRead, F: Fn(Report) -> bool>(reader: R, filter: F) -> Result<u64, StreamError> {
     let mut safe_reports: u64 = 0;
 
-    for line in fs::read_to_string(file_path).unwrap().lines() {
-        let report = Report::from(line);
-        if filter(report) {
+    for report in stream::records(reader, parse_report) {
+        if filter(report?) {
             safe_reports += 1;
         }
     }
     Ok(safe_reports)
 }
 
-pub fn count_safe_reports_strict(file_path: PathBuf) -> Result<u64, Box<dyn Error>> {
-    count_reports(file_path, |report| report.is_safe())
+// a report's diagnosis, numbered by its line in the input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReportDiagnosis {
+    pub line: usize,
+    pub levels: String,
+    pub failure: Failure,
+    #[serde(flatten)]
+    pub diagnosis: Diagnosis,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct FailureCount {
+    pub reports: u64,
+    pub fixed_by_dampening: u64,
+}
+
+// every report's diagnosis and how many fall under each failure
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Diagnostics {
+    pub reports: Vec<ReportDiagnosis>,
+    pub summary: BTreeMap<Failure, FailureCount>,
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let index = |idx: Option<usize>| idx.map_or(String::from("-"), |idx| idx.to_string());
+        writeln!(f, "{:>5}  {:<18}  {:>8}  {:>7}  {:>8}  levels", "line", "failure", "ordering", "bad gap", "dampened")?;
+        for report in self.reports.iter() {
+            let dampened = match report.failure {
+                Failure::Safe => "-",
+                _ if report.diagnosis.fixed_by_dampening => "yes",
+                _ => "no",
+            };
+            writeln!(f, "{:>5}  {:<18}  {:>8}  {:>7}  {:>8}  {}", report.line, report.failure,
+                index(report.diagnosis.ordering_break), index(report.diagnosis.bad_gap), dampened, report.levels)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<18}  {:>7}  {:>8}", "failure", "reports", "dampened")?;
+        for (failure, count) in self.summary.iter() {
+            writeln!(f, "{:<18}  {:>7}  {:>8}", failure, count.reports, count.fixed_by_dampening)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn diagnose_reports<R: BufRead>(reader: R, config: &Config) -> Result<Diagnostics, StreamError> {
+    let mut diagnostics = Diagnostics::default();
+
+    for (line_idx, report) in stream::records(reader, parse_report).enumerate() {
+        let report = report?;
+        let diagnosis = report.diagnose(config);
+        let failure = diagnosis.failure();
+        let count = diagnostics.summary.entry(failure).or_default();
+        count.reports += 1;
+        if diagnosis.fixed_by_dampening {
+            count.fixed_by_dampening += 1;
+        }
+        diagnostics.reports.push(ReportDiagnosis { line: line_idx + 1, levels: report.to_string(), failure, diagnosis });
+    }
+    Ok(diagnostics)
 }
 
-pub fn count_safe_reports_dampened(file_path: PathBuf) -> Result<u64, Box<dyn Error>> {
-    count_reports(file_path, |report| report.is_safe_with_dampening())
+pub fn count_safe_reports_strict(file_path: impl AsRef<Path>, config: &Config) -> Result<u64, Box<dyn Error>> {
+    count_reports(file_path, |report| report.is_safe(config))
+}
+
+pub fn count_safe_reports_dampened(file_path: impl AsRef<Path>, config: &Config) -> Result<u64, Box<dyn Error>> {
+    count_reports(file_path, |report| report.is_safe_with_dampening(config))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Report>;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_reports(input)
+    }
+
+    fn part1(reports: &Self::Input, config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(reports.iter().filter(|r| r.is_safe(config)).count().into())
+    }
+
+    fn part2(reports: &Self::Input, config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(reports.iter().filter(|r| r.is_safe_with_dampening(config)).count().into())
+    }
 }
 
 #[cfg(test)]
@@ -325,44 +325,95 @@ mod tests {
     #[test]
     fn test_counting() {
         let path = get_test_data_path("day2/case1.txt").unwrap();
-        let result = count_safe_reports_strict(path).unwrap();
+        let result = count_safe_reports_strict(path, &Config::default()).unwrap();
         assert_eq!(result, 2, "correctly analyzes and counts reports")
     }
 
     #[test]
     fn incrementing_safe() {
-        let report = Report::from("1 4 6 7");
-        assert!(report.is_safe(), "all incrementing 1-3");
+        let report = Report::from("1 4 6 7").unwrap();
+        assert!(report.is_safe(&Config::default()), "all incrementing 1-3");
     }
 
     #[test]
     fn not_all_incrementing() {
-        let report = Report::from("1 4 6 5");
-        assert!(!report.is_safe(), "decrementing is unsafe");
+        let report = Report::from("1 4 6 5").unwrap();
+        assert!(!report.is_safe(&Config::default()), "decrementing is unsafe");
     }
 
     #[test]
     fn incrementing_unsafe_gap() {
-        let report = Report::from("1 4 6 10");
-        assert!(!report.is_safe(), "all incrementing 1-3");
+        let report = Report::from("1 4 6 10").unwrap();
+        assert!(!report.is_safe(&Config::default()), "all incrementing 1-3");
     }
 
     #[test]
-    #[should_panic]
     fn no_levels() {
-        let report = Report::from("");
+        let err = Report::from("").err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::Empty);
+    }
+
+    #[test]
+    fn bad_level_position() {
+        let err = parse_reports("1 2 3\n4 5 x 7\n").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 5));
+    }
+
+    #[test]
+    fn streamed_reports() {
+        let input = "1 2 3\n1 5 6\n3 2 1\n";
+        assert_eq!(count_streamed_reports(input.as_bytes(), |r| r.is_safe(&Config::default())).unwrap(), 2);
+        let err = count_streamed_reports("1 2 3\n4 5 x 7\n".as_bytes(), |r| r.is_safe(&Config::default())).unwrap_err();
+        assert!(matches!(err, StreamError::Parse(e) if (e.line, e.column) == (2, 5)));
     }
 
     #[test]
     fn same_levels() {
-        let report = Report::from("1 2 3 3");
-        assert!(!report.is_safe(), "needs to increment or decrement by at least one");
+        let report = Report::from("1 2 3 3").unwrap();
+        assert!(!report.is_safe(&Config::default()), "needs to increment or decrement by at least one");
+    }
+
+    #[test]
+    fn diagnoses() {
+        let config = Config::default();
+        let diagnosis = Report::from("1 3 2 4 5").unwrap().diagnose(&config);
+        assert_eq!(diagnosis, Diagnosis { ordering_break: Some(2), bad_gap: None, fixed_by_dampening: true });
+        assert_eq!(diagnosis.failure(), Failure::Unordered);
+        let diagnosis = Report::from("8 6 4 4 1").unwrap().diagnose(&config);
+        assert_eq!(diagnosis, Diagnosis { ordering_break: None, bad_gap: Some(3), fixed_by_dampening: true });
+        assert_eq!(diagnosis.failure(), Failure::BadGap);
+        let diagnosis = Report::from("1 2 1 9").unwrap().diagnose(&config);
+        assert_eq!(diagnosis, Diagnosis { ordering_break: Some(2), bad_gap: Some(3), fixed_by_dampening: false });
+        assert_eq!(diagnosis.failure(), Failure::UnorderedAndBadGap);
+        assert_eq!(Report::from("7 6 4 2 1").unwrap().diagnose(&config).failure(), Failure::Safe);
+
+        // safe exactly when there's nothing to diagnose, whatever the steps allowed
+        let reports = ["1 1 2 3", "3 2 1 1", "1 1 2 1", "1 5 9", "9 9 9", "4 2 4"];
+        for config in [config, Config { min_step: 0, max_step: 4, ..Config::default() }] {
+            for report in reports.map(|r| Report::from(r).unwrap()) {
+                assert_eq!(report.diagnose(&config).failure() == Failure::Safe, report.is_safe(&config), "{}", report);
+            }
+        }
+    }
+
+    #[test]
+    fn diagnostics_summary() {
+        let path = get_test_data_path("day2/case1.txt").unwrap();
+        let diagnostics = diagnose_reports(stream::open(path).unwrap(), &Config::default()).unwrap();
+        assert_eq!(diagnostics.reports.len(), 6);
+        assert_eq!(diagnostics.summary[&Failure::Safe], FailureCount { reports: 2, fixed_by_dampening: 0 });
+        let fixed: u64 = diagnostics.summary.values().map(|count| count.fixed_by_dampening).sum();
+        assert_eq!(fixed, 2);
+        let json: serde_json::Value = serde_json::from_str(&crate::output::to_json(&diagnostics)).unwrap();
+        assert_eq!(json["reports"][1]["failure"], "bad_gap");
+        assert_eq!(json["reports"][1]["bad_gap"], 2);
+        assert_eq!(json["summary"]["safe"]["reports"], 2);
     }
 
     #[test]
     fn one_level() {
-        let report = Report::from("1");
-        assert!(report.is_safe(), "only one level should be valid");
+        let report = Report::from("1").unwrap();
+        assert!(report.is_safe(&Config::default()), "only one level should be valid");
     }
 
 }
@@ -373,69 +424,86 @@ mod tests_dampening {
     use crate::common::get_test_data_path;
 
     #[test]
-    fn test_graph_inspect() {
-        let report = Report::from("1 3 2 4 5");
-        let graph = report.build_graph();
-        // println!("FOO");
-        // println!("{}", graph);
-        assert!(report.is_safe_with_dampening());
-
-        //let all_paths = graph.get_all_paths();
-        //for p in all_paths {
-        //    for n in p {
-        //        print!("{}\t", n.borrow().value.unwrap_or(9999));
-        //    }
-        //    print!("\n");
-        //}
+    fn test_removed_levels() {
+        let report = Report::from("1 3 2 4 5").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()));
+        assert_eq!(report.removals_to_safe(&Config::default()), vec![2]);
+        assert_eq!(Report::from("7 6 4 2 1").unwrap().removals_to_safe(&Config::default()), Vec::<usize>::new());
+        assert_eq!(Report::from("9 1 2 3 3 4").unwrap().removals_to_safe(&Config::default()), vec![0, 4]);
+    }
+
+    #[test]
+    fn k_removals() {
+        let config = Config::default();
+        let report = Report::from("1 2 3 3 3").unwrap();
+        assert_eq!(report.min_removals_to_safe(&config), 2);
+        assert!(!report.is_safe_with_k_removals(1, &config));
+        assert!(report.is_safe_with_k_removals(2, &config));
+        assert_eq!(Report::from("5 50 4 40 3 30 2").unwrap().min_removals_to_safe(&config), 3);
+    }
+
+    #[test]
+    fn configured_steps() {
+        let report = Report::from("10 20 30 35").unwrap();
+        assert_eq!(report.min_removals_to_safe(&Config::default()), 3);
+        let config = Config { min_step: 5, max_step: 10, ..Config::default() };
+        assert_eq!(report.min_removals_to_safe(&config), 0);
+        let config = Config { min_step: 10, max_step: 10, ..Config::default() };
+        assert_eq!(report.removals_to_safe(&config), vec![3]);
+
+        let config = Config { min_step: 0, ..Config::default() };
+        assert!(Report::from("1 1 2 3").unwrap().is_safe(&config));
+        assert!(Report::from("3 2 1 1").unwrap().is_safe(&config));
+        assert!(!Report::from("1 1 2 1").unwrap().is_safe(&config));
     }
 
     #[test]
     fn test_counting() {
         let path = get_test_data_path("day2/case1.txt").unwrap();
-        let result = count_safe_reports_dampened(path).unwrap();
+        let result = count_safe_reports_dampened(path, &Config::default()).unwrap();
         assert_eq!(result, 4, "correctly analyzes and counts reports")
     }
 
     #[test]
     fn incrementing_safe() {
-        let report = Report::from("1 4 6 7");
-        assert!(report.is_safe_with_dampening(), "all incrementing 1-3");
+        let report = Report::from("1 4 6 7").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()), "all incrementing 1-3");
     }
 
     #[test]
     fn not_all_incrementing() {
-        let report = Report::from("1 4 6 5");
-        assert!(report.is_safe_with_dampening(), "single value can be removed");
+        let report = Report::from("1 4 6 5").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()), "single value can be removed");
     }
 
     #[test]
     fn incrementing_unsafe_gap() {
-        let report = Report::from("1 4 6 10");
-        assert!(report.is_safe_with_dampening(), "can remove largest");
+        let report = Report::from("1 4 6 10").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()), "can remove largest");
     }
 
     #[test]
     fn same_levels() {
-        let report = Report::from("1 2 3 3");
-        assert!(report.is_safe_with_dampening(), "same can be removed");
+        let report = Report::from("1 2 3 3").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()), "same can be removed");
     }
 
     #[test]
     fn same_levels_multi() {
-        let report = Report::from("1 2 3 3 3");
-        assert!(!report.is_safe_with_dampening(), "same can be removed only once");
+        let report = Report::from("1 2 3 3 3").unwrap();
+        assert!(!report.is_safe_with_dampening(&Config::default()), "same can be removed only once");
     }
 
     #[test]
     fn one_level() {
-        let report = Report::from("1");
-        assert!(report.is_safe_with_dampening(), "only one level should be valid");
+        let report = Report::from("1").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()), "only one level should be valid");
     }
 
     #[test]
     fn inc_or_desc_possible() {
-        let report = Report::from("2 4 3");
-        assert!(report.is_safe_with_dampening(), "multiple possibilities after removal");
+        let report = Report::from("2 4 3").unwrap();
+        assert!(report.is_safe_with_dampening(&Config::default()), "multiple possibilities after removal");
     }
 
 }
@@ -1,18 +1,15 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-pub struct Loc {
-    x: isize,
-    y: isize,
-}
-
-pub struct Dimensions {
-    width: isize,
-    height: isize,
-}
+use crate::common::grid::{offset, to_ipos, Grid, Ipos, TileMap};
+use crate::common::search;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
+#[derive(Clone)]
 pub enum Tile {
     Vacant,
     Wall,
@@ -22,73 +19,56 @@ pub mod constants {
     pub const INPUT_PATH: &str = "day20/input.txt";
 }
 
-pub struct Track {
-    m: FxHashMap<Loc, Tile>,
-    start: Loc,
-    end: Loc,
-    dim: Dimensions,
+const DAY: usize = 20;
+
+// only cheats that save at least min_savings_ps picoseconds count
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub min_savings_ps: isize,
 }
 
-impl Track {
-    pub fn from(input: &String) -> Self {
-        let mut y = 0;
-        let mut m: FxHashMap<Loc, Tile> = FxHashMap::default();
-        let mut start = Loc { x: -1, y: -1 };
-        let mut end = Loc { x: -1, y: -1 };
-        for row in input.lines() {
-            let mut x = 0;
-            for c in row.chars() {
-                let loc = Loc { x, y };
-                match c {
-                    '#' => m.entry(loc.clone()).or_insert(Tile::Wall),
-                    _ => m.entry(loc.clone()).or_insert(Tile::Vacant),
-                };
-                match c {
-                    'S' => start = loc.clone(),
-                    'E' => end = loc.clone(),
-                    _ => (),
-                };
-                x += 1;
-            }
-            y += 1;
-        }
-        let width = isize::try_from(input.lines().next().unwrap().len()).unwrap();
-        let height = isize::try_from(input.lines().count()).unwrap();
-        let dim = Dimensions { width, height };
-        Self {
-            m,
-            start,
-            end,
-            dim,
-        }
+impl Default for Config {
+    fn default() -> Self {
+        Self { min_savings_ps: 100 }
     }
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input, 100)
+const TILES: TileMap<Tile> = TileMap {
+    tiles: &[('#', Tile::Wall), ('.', Tile::Vacant)],
+    markers: &[('S', Tile::Vacant), ('E', Tile::Vacant)],
+};
+
+pub struct Track {
+    m: Grid<Tile>,
+    start: Ipos,
+    end: Ipos,
 }
 
-pub fn get_next_moves(track: &Track, pos: &Loc) -> Vec<Loc> {
-    let offsets = [
-        (0, -1),
-        (0, 1),
-        (-1, 0),
-        (1, 0),
-    ];
-
-    let mut result = vec![];
-
-    for o in offsets.iter() {
-        let l = Loc { x: pos.x + o.0, y: pos.y + o.1 };
-        match track.m.get(&l) {
-            Some(Tile::Vacant) => result.push(l),
-            Some(Tile::Wall) => (),
-            None => (),
-        };
+impl Track {
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        let parsed = TILES.parse(DAY, input)?;
+        Ok(Self {
+            start: to_ipos(parsed.marker('S')),
+            end: to_ipos(parsed.marker('E')),
+            m: parsed.grid,
+        })
     }
 
-    result
+    fn tile_at(&self, loc: Ipos) -> Option<&Tile> {
+        self.m.get(loc)
+    }
+}
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input, &Config::default())?)
+}
+
+pub fn get_next_moves(track: &Track, pos: &Ipos) -> Vec<Ipos> {
+    track.m.neighbors4(*pos)
+        .filter(|n| matches!(track.m.get(*n), Some(Tile::Vacant)))
+        .collect()
 }
 
 pub fn get_cheat_offsets(pico_s: isize) -> FxHashSet<(isize, isize)> {
@@ -103,41 +83,20 @@ pub fn get_cheat_offsets(pico_s: isize) -> FxHashSet<(isize, isize)> {
     result
 }
 
-pub fn compute_goal_distances(track: &Track) -> FxHashMap<Loc, isize> {
-    let mut result = FxHashMap::default();
-    let mut pos = track.end.clone();
-    let mut dist = 0;
-
-    loop {
-        result.entry(pos.clone()).or_insert(dist);
-        if pos == track.start {
-            break;
-        }
-        let moves = get_next_moves(track, &pos);
-
-        let mut move_count = 0;
-        let mut already_moved_count = 0;
-        for m in moves.iter() {
-            if result.contains_key(&m) {
-                assert!(already_moved_count == 0);
-                already_moved_count += 1;
-            } else {
-                assert!(move_count == 0);
-                pos = m.clone();
-                dist += 1;
-                move_count += 1;
-            }
-        }
-        assert!(move_count == 1);
-    }
+pub fn compute_goal_distances(track: &Track) -> FxHashMap<Ipos, isize> {
+    search::bfs(track.end, |pos| get_next_moves(track, pos))
+        .into_iter()
+        .map(|(loc, dist)| (loc, isize::try_from(dist).unwrap()))
+        .collect()
+}
 
-    result
+pub fn solution1_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(count_short_cheats(&Track::from(input)?, config.min_savings_ps))
 }
 
-pub fn _solution1(input: &String, min_savings_ps: isize) -> isize {
-    let track = Track::from(&input);
-    let dists = compute_goal_distances(&track);
-    let cheats = get_cheats(&track, &dists, 2);
+fn count_short_cheats(track: &Track, min_savings_ps: isize) -> isize {
+    let dists = compute_goal_distances(track);
+    let cheats = get_cheats(track, &dists, 2);
 
     let mut result = 0;
     for (ps, count) in cheats.iter() {
@@ -148,23 +107,33 @@ pub fn _solution1(input: &String, min_savings_ps: isize) -> isize {
     result
 }
 
-pub fn get_cheats(track: &Track, dists: &FxHashMap<Loc, isize>, cheat_ps: isize) -> FxHashMap<isize, isize> {
-    let offsets = get_cheat_offsets(cheat_ps);
+pub fn get_cheats(track: &Track, dists: &FxHashMap<Ipos, isize>, cheat_ps: isize) -> FxHashMap<isize, isize> {
     let mut result = FxHashMap::default();
+    for_each_cheat(track, dists, &get_cheat_offsets(cheat_ps), |_, savings| {
+        *result.entry(savings).or_insert(0) += 1;
+    });
+    result
+}
+
+// calls found with where each cheat starts and how much it saves, for every cheat that saves anything.
+// a cheat to an offset takes as long as its manhattan distance
+fn for_each_cheat<F>(track: &Track, dists: &FxHashMap<Ipos, isize>, offsets: &FxHashSet<(isize, isize)>, mut found: F)
+where
+    F: FnMut(&Ipos, isize),
+{
     let start_cost_remaining = dists.get(&track.start).unwrap();
     for (pos, pos_cost_remaining) in dists.iter() {
         for o in offsets.iter() {
-            let cheat_dest = Loc { x: pos.x + o.0, y: pos.y + o.1 };
-            match track.m.get(&cheat_dest) {
+            let cheat_dest = offset(*pos, *o);
+            match track.tile_at(cheat_dest) {
                 Some(Tile::Vacant) => {
                     // test for savings
                     let steps_taken = start_cost_remaining - pos_cost_remaining;
                     let cheat_cost_remaining = dists.get(&cheat_dest).unwrap();
-                    let cheat_cost = steps_taken + cheat_cost_remaining + cheat_ps;
+                    let cheat_cost = steps_taken + cheat_cost_remaining + (o.0.abs() + o.1.abs());
                     let savings = start_cost_remaining - cheat_cost;
                     if  savings > 0 {
-                        result.entry(savings).or_insert(0);
-                        *result.get_mut(&savings).unwrap() += 1;
+                        found(pos, savings);
                     }
                 },
                 Some(Tile::Wall) => {
@@ -176,18 +145,20 @@ pub fn get_cheats(track: &Track, dists: &FxHashMap<Loc, isize>, cheat_ps: isize)
             };
         }
     }
-    result
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input, 100)
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input, &Config::default())?)
 }
 
-pub fn _solution2(input: &String, min_savings_ps: isize) -> isize {
-    let track = Track::from(&input);
-    let dists = compute_goal_distances(&track);
-    let cheats = get_cheats2(&track, &dists, 20);
+pub fn solution2_str(input: &str, config: &Config) -> Result<isize, ParseError> {
+    Ok(count_long_cheats(&Track::from(input)?, config.min_savings_ps))
+}
+
+fn count_long_cheats(track: &Track, min_savings_ps: isize) -> isize {
+    let dists = compute_goal_distances(track);
+    let cheats = get_cheats2(track, &dists, 20);
 
     let mut result = 0;
     for (ps, count) in cheats.iter() {
@@ -210,47 +181,62 @@ pub fn get_cheat_offsets2(pico_s: isize) -> FxHashSet<(isize, isize)> {
     result
 }
 
-pub fn get_cheats2(track: &Track, dists: &FxHashMap<Loc, isize>, cheat_ps: isize) -> FxHashMap<isize, isize> {
-    let offsets = get_cheat_offsets2(cheat_ps);
+pub fn get_cheats2(track: &Track, dists: &FxHashMap<Ipos, isize>, cheat_ps: isize) -> FxHashMap<isize, isize> {
     let mut result = FxHashMap::default();
-    let start_cost_remaining = dists.get(&track.start).unwrap();
-    for (pos, pos_cost_remaining) in dists.iter() {
-        for o in offsets.iter() {
-            let cheat_dest = Loc { x: pos.x + o.0, y: pos.y + o.1 };
-            match track.m.get(&cheat_dest) {
-                Some(Tile::Vacant) => {
-                    // test for savings
-                    let steps_taken = start_cost_remaining - pos_cost_remaining;
-                    let cheat_cost_remaining = dists.get(&cheat_dest).unwrap();
-                    let cheat_cost = steps_taken + cheat_cost_remaining + (o.0.abs() + o.1.abs());
-                    let savings = start_cost_remaining - cheat_cost;
-                    if  savings > 0 {
-                        result.entry(savings).or_insert(0);
-                        *result.get_mut(&savings).unwrap() += 1;
-                    }
-                },
-                Some(Tile::Wall) => {
-                    // invalid cheat
-                },
-                None => {
-                    // off the map
-                },
-            };
+    for_each_cheat(track, dists, &get_cheat_offsets2(cheat_ps), |_, savings| {
+        *result.entry(savings).or_insert(0) += 1;
+    });
+    result
+}
+
+// how many cheats of up to cheat_ps that save at least min_savings_ps start from each tile of the track,
+// None for the walls
+pub fn cheat_heatmap(track: &Track, cheat_ps: isize, min_savings_ps: isize) -> Grid<Option<usize>> {
+    let dists = compute_goal_distances(track);
+    let mut heat = track.m.map(|tile| match tile {
+        Tile::Vacant => Some(0),
+        Tile::Wall => None,
+    });
+    for_each_cheat(track, &dists, &get_cheat_offsets2(cheat_ps), |pos, savings| {
+        if savings >= min_savings_ps {
+            if let Some(Some(count)) = heat.get_mut(*pos) {
+                *count += 1;
+            }
         }
+    });
+    heat
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Track;
+    type Config = Config;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Track::from(input)
+    }
+
+    fn part1(track: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_short_cheats(track, config.min_savings_ps).into())
+    }
+
+    fn part2(track: &Self::Input, config: &Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_long_cheats(track, config.min_savings_ps).into())
     }
-    result
 }
 
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn day_20_1_1() {
         let path = common::get_test_data_path("day20/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let track = Track::from(&input);
+        let track = Track::from(&input).unwrap();
         let dists = compute_goal_distances(&track);
         let cheats = get_cheats(&track, &dists, 2);
         let expected: FxHashMap<isize, isize> = Vec::from([
@@ -276,7 +262,7 @@ mod tests  {
     fn day_20_1_2() {
         let path = common::get_test_data_path("day20/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let result = _solution1(&input, 10);
+        let result = solution1_str(&input, &Config { min_savings_ps: 10 }).unwrap();
         assert_eq!(result, 10);
     }
 
@@ -284,7 +270,7 @@ mod tests  {
     fn day_20_2_1() {
         let path = common::get_test_data_path("day20/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let track = Track::from(&input);
+        let track = Track::from(&input).unwrap();
         let dists = compute_goal_distances(&track);
         let cheats = get_cheats2(&track, &dists, 20);
         let mut gte50_savings_cheats = FxHashMap::default();
@@ -319,7 +305,23 @@ mod tests  {
     fn day_20_2_2() {
         let path = common::get_test_data_path("day20/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let result = _solution2(&input, 74);
+        let result = solution2_str(&input, &Config { min_savings_ps: 74 }).unwrap();
         assert_eq!(result, 7);
     }
+
+    #[test]
+    fn day_20_heatmap() {
+        let path = common::get_test_data_path("day20/case1.txt").unwrap();
+        let track = Track::from(&fs::read_to_string(&path).unwrap()).unwrap();
+        let heat = cheat_heatmap(&track, 20, 74);
+        assert_eq!(heat.iter().filter_map(|(_, count)| *count).sum::<usize>(), 7);
+        assert_eq!(heat[(0, 0)], None);
+        assert_eq!(cheat_heatmap(&track, 2, 10).iter().filter_map(|(_, count)| *count).sum::<usize>(), 10);
+    }
+
+    #[test]
+    fn day_20_missing_end() {
+        let err = Track::from(&String::from("#####\n#S..#\n#####\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::MissingMarker('E'));
+    }
 }
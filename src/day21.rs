@@ -1,8 +1,14 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 
 use rustc_hash::FxHashMap;
 
+use crate::common::search;
+use crate::config::NoConfig;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
 pub enum Key {
     A,
@@ -240,59 +246,29 @@ impl RobotController {
     }
 }
 
-pub fn _best_paths(keypad: &Keypad, src_key: &Key, dest_key: &Key, best_paths: &mut FxHashMap<Key, Vec<Vec<ArmOp>>>) {
-    if *src_key == *dest_key {
-        return;
-    }
-    let mut next_src_keys: Vec<Key> = vec![];
-    let src_bests = best_paths.get(&src_key).unwrap().clone();
-    for (dir, key) in keypad.links.get(src_key).unwrap().iter() {
-        if best_paths.contains_key(&key) {
-            let existing_bests = best_paths.get(&key).unwrap().clone();
-            if src_bests[0].len() + 1 < existing_bests[0].len() {
-                let mut current_bests = src_bests.clone();
-                for i in 0..current_bests.len() {
-                    current_bests[i].push(dir.clone());
-                }
-                next_src_keys.push(key.clone());
-                best_paths.entry(key.clone()).and_modify(|b| *b = current_bests);
-            } else if src_bests[0].len() + 1 == existing_bests[0].len() {
-                let mut current_bests = src_bests.clone();
-                let mut to_add_bests = vec![];
-                for i in 0..current_bests.len() {
-                    current_bests[i].push(dir.clone());
-                    if !best_paths.get(&key.clone()).unwrap().contains(&current_bests[i]) {
-                        to_add_bests.push(current_bests[i].clone());
-                    }
-                }
-                best_paths.entry(key.clone()).and_modify(|b| b.append(&mut to_add_bests));
-                next_src_keys.push(key.clone());
-            }
-        } else {
-            let mut current_bests = src_bests.clone();
-            for i in 0..current_bests.len() {
-                current_bests[i].push(dir.clone());
-            }
-            best_paths.entry(key.clone()).or_insert(current_bests);
-            next_src_keys.push(key.clone());
-        }
-    }
-    for key in next_src_keys.iter() {
-        _best_paths(&keypad, &key, &dest_key, best_paths);
-    }
+fn op_between(keypad: &Keypad, src_key: &Key, dest_key: &Key) -> ArmOp {
+    let links = keypad.links.get(src_key).unwrap();
+    links.iter().find(|(_, key)| *key == dest_key).unwrap().0.clone()
 }
 
-pub fn shortest_paths(keypad: &Keypad, src_key: &Key, dest_key: &Key) -> Vec<Vec<ArmOp>> {
-    let mut best_paths: FxHashMap<Key, Vec<Vec<ArmOp>>> = FxHashMap::default();
-    best_paths.entry(src_key.clone()).or_insert(vec![vec![]]);
+// every fewest-press route from src to dest, as arm ops
+pub fn _best_paths(keypad: &Keypad, src_key: &Key, dest_key: &Key) -> Vec<Vec<ArmOp>> {
+    let dag = search::all_shortest_paths(src_key.clone(), |key| {
+        keypad.links.get(key).unwrap().values().map(|next| (next.clone(), 1))
+    }).expect("every press costs 1");
+    dag.paths_to(dest_key).iter()
+        .map(|keys| keys.windows(2).map(|w| op_between(keypad, &w[0], &w[1])).collect())
+        .collect()
+}
 
-    _best_paths(&keypad, &src_key, &dest_key, &mut best_paths);
+pub fn shortest_paths(keypad: &Keypad, src_key: &Key, dest_key: &Key) -> Vec<Vec<ArmOp>> {
+    let best_paths = _best_paths(keypad, src_key, dest_key);
 
     let mut min_directional_changes = isize::MAX;
-    if best_paths.get(&dest_key).unwrap()[0].len() == 0 {
-        return best_paths.get(&dest_key).unwrap().clone();
+    if best_paths[0].is_empty() {
+        return best_paths;
     }
-    for p in best_paths.get(&dest_key).unwrap().iter() {
+    for p in best_paths.iter() {
         let mut dir_changes = 0;
         let mut last_d = &p[0];
         for d in p[1..].iter() {
@@ -307,7 +283,7 @@ pub fn shortest_paths(keypad: &Keypad, src_key: &Key, dest_key: &Key) -> Vec<Vec
     }
 
     let mut result = vec![];
-    for p in best_paths.get(&dest_key).unwrap().iter() {
+    for p in best_paths.iter() {
         let mut dir_changes = 0;
         let mut last_d = &p[0];
         for d in p[1..].iter() {
@@ -329,9 +305,12 @@ pub mod constants {
     pub const INPUT_PATH: &str = "day21/input.txt";
 }
 
-pub fn parse_keys(code: &String) -> Vec<Key> {
+const DAY: usize = 21;
+
+// line numbers are filled in by parse_codes
+pub fn parse_keys(code: &str) -> Result<Vec<Key>, ParseError> {
     let mut result = vec![];
-    for c in code.chars() {
+    for (col_idx, c) in code.chars().enumerate() {
         let k = match c {
             '0' => Key::Zero,
             '1' => Key::One,
@@ -344,22 +323,29 @@ pub fn parse_keys(code: &String) -> Vec<Key> {
             '8' => Key::Eight,
             '9' => Key::Nine,
             'A' => Key::A,
-            _ => panic!("unknown key"),
+            c => {
+                return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedChar(c))
+                    .at(0, col_idx)
+                    .with_text(code));
+            },
         };
         result.push(k);
     }
-    result
+    Ok(result)
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
+pub fn parse_codes(input: &str) -> Result<Vec<Vec<Key>>, ParseError> {
     let mut codes = vec![];
 
-    for line in input.lines() {
-        codes.push(parse_keys(&line.to_string()));
+    for (line_idx, line) in input.lines().enumerate() {
+        codes.push(parse_keys(line).map_err(|e| e.on_line(line_idx))?);
     }
+    Ok(codes)
+}
 
-    _solution1(&codes)
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
 pub fn numeric_complexity_component(code: &Vec<Key>) -> isize {
@@ -399,24 +385,6 @@ pub fn render_one(ops: &Vec<ArmOp>) -> String {
     result
 }
 
-pub fn render_all(ops: &Vec<Vec<Vec<ArmOp>>>) {
-    for first in ops[0].iter() {
-        for second in ops[1].iter() {
-            for third in ops[2].iter() {
-                for fourth in ops[3].iter() {
-                    println!(
-                        "{}|{}|{}|{}",
-                        render_one(&first),
-                        render_one(&second),
-                        render_one(&third),
-                        render_one(&fourth),
-                    )
-                }
-            }
-        }
-    }
-}
-
 pub fn compute_shortest_seq(controller: &RobotController, code: &Vec<Key>) -> isize {
     let key_solutions = controller.solve_root_seq(&code);
     let mut total_best = 0;
@@ -432,7 +400,7 @@ pub fn compute_shortest_seq(controller: &RobotController, code: &Vec<Key>) -> is
     isize::try_from(total_best).unwrap()
 }
 
-pub fn compute_complexity(controller: &RobotController, codes: &Vec<Vec<Key>>) -> isize {
+pub fn compute_complexity(controller: &RobotController, codes: &[Vec<Key>]) -> isize {
     let mut total_complexity = 0;
     for code in codes.iter() {
         let shortest_seq_len = compute_shortest_seq(&controller, &code);
@@ -442,7 +410,12 @@ pub fn compute_complexity(controller: &RobotController, codes: &Vec<Vec<Key>>) -
     total_complexity
 }
 
-pub fn _solution1(codes: &Vec<Vec<Key>>) -> isize {
+pub fn solution1_str(input: &str) -> Result<isize, ParseError> {
+    Ok(chained_complexity(&parse_codes(input)?))
+}
+
+// a robot on the numeric keypad, driven by two more on directional ones
+pub fn chained_complexity(codes: &[Vec<Key>]) -> isize {
     let numeric_controller = RobotController {
         keypad: Keypad::create_numeric(),
         child: None,
@@ -455,17 +428,12 @@ pub fn _solution1(codes: &Vec<Vec<Key>>) -> isize {
         keypad: Keypad::create_directional(),
         child: Some(Box::new(dir_controller1)),
     };
-    compute_complexity(&dir_controller2, &codes)
+    compute_complexity(&dir_controller2, codes)
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    let mut codes = vec![];
-
-    for line in input.lines() {
-        codes.push(parse_keys(&line.to_string()));
-    }
-    _solution2(&codes)
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone)]
@@ -502,7 +470,7 @@ pub fn find_min_presses(contender: &Vec<ArmOp>, depth: isize, score_cache: &mut
     score
 }
 
-fn solution_for_robots(codes: &Vec<Vec<Key>>, directional_robots: isize) -> isize {
+fn solution_for_robots(codes: &[Vec<Key>], directional_robots: isize) -> isize {
     let controller = RobotController {
         keypad: Keypad::create_numeric(),
         child: None,
@@ -531,8 +499,27 @@ fn solution_for_robots(codes: &Vec<Vec<Key>>, directional_robots: isize) -> isiz
     result
 }
 
-pub fn _solution2(codes: &Vec<Vec<Key>>) -> isize {
-    solution_for_robots(&codes, 25)
+pub fn solution2_str(input: &str) -> Result<isize, ParseError> {
+    Ok(solution_for_robots(&parse_codes(input)?, 25))
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Vec<Key>>;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_codes(input)
+    }
+
+    fn part1(codes: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(chained_complexity(codes).into())
+    }
+
+    fn part2(codes: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(solution_for_robots(codes, 25).into())
+    }
 }
 
 #[cfg(test)]
@@ -606,7 +593,7 @@ mod tests  {
 
     #[test]
     fn day_21_1_3() {
-        let result = _solution1(&vec![
+        let result = chained_complexity(&[
             vec![Key::Zero, Key::Two, Key::Nine, Key::A],
             vec![Key::Nine, Key::Eight, Key::Zero, Key::A],
             vec![Key::One, Key::Seven, Key::Nine, Key::A],
@@ -627,4 +614,11 @@ mod tests  {
         ], 2);
         assert_eq!(result, 126384);
     }
+
+    #[test]
+    fn day_21_bad_key() {
+        let err = parse_codes("029A\n98OA\n").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('O'));
+        assert_eq!((err.line, err.column), (2, 3));
+    }
 }
@@ -1,20 +1,41 @@
-use std::fs;
-use std::path::PathBuf;
+use std::error::Error;
+use std::io::BufRead;
+use std::path::Path;
 
+use rayon::prelude::*;
 use rustc_hash::FxHashMap;
+use log::debug;
+
+use crate::config::NoConfig;
+use crate::error::{parse_num, ParseError};
+use crate::solution::{Answer, Solution};
+use crate::stream::{self, StreamError};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day22/input.txt";
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    let mut init_secrets: Vec<isize> = vec![];
+const DAY: usize = 22;
 
-    for line in input.lines() {
-        init_secrets.push(line.parse::<isize>().unwrap());
-    }
-    _solution1(&init_secrets)
+// buyers worked through at a time when streaming, enough to keep every thread busy
+const BATCH_SIZE: usize = 4096;
+
+fn parse_secret(line_idx: usize, line: &str) -> Result<isize, ParseError> {
+    parse_num::<isize>(DAY, line_idx, line, line)
+}
+
+pub fn parse_secrets(input: &str) -> Result<Vec<isize>, ParseError> {
+    input.lines().enumerate()
+        .map(|(line_idx, line)| parse_secret(line_idx, line))
+        .collect()
+}
+
+fn secret_batches<R: BufRead>(reader: R) -> impl Iterator<Item = Result<Vec<isize>, StreamError>> {
+    stream::batches(stream::records(reader, parse_secret), BATCH_SIZE)
+}
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    Ok(streamed_secret_sum(stream::open(path)?)?)
 }
 
 pub fn get_secret(init: isize, iterations: isize) -> isize {
@@ -79,45 +100,72 @@ pub fn price_diffs(prices: &Vec<isize>) -> Vec<isize> {
     result
 }
 
-pub fn _solution1(input: &Vec<isize>) -> isize {
-    let mut result = 0;
-    for s in input.iter() {
-        result += dbg!(get_secret(*s, 2000));
+pub fn solution1_str(input: &str) -> Result<isize, ParseError> {
+    Ok(secret_sum(&parse_secrets(input)?))
+}
+
+pub fn secret_sum(input: &[isize]) -> isize {
+    input.par_iter()
+        .map(|s| get_secret(*s, 2000))
+        .sum()
+}
+
+// the same sum, a batch of buyers at a time as they're read
+pub fn streamed_secret_sum<R: BufRead>(reader: R) -> Result<isize, StreamError> {
+    let mut sum = 0;
+    for init_secrets in secret_batches(reader) {
+        sum += secret_sum(&init_secrets?);
     }
-    result
+    Ok(sum)
 }
 
-pub fn solution2(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    let mut init_secrets: Vec<isize> = vec![];
+pub fn solution2(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    Ok(streamed_most_bananas(stream::open(path)?)?)
+}
 
-    for line in input.lines() {
-        init_secrets.push(line.parse::<isize>().unwrap());
+type PriceChanges = (isize, isize, isize, isize);
+
+// what one buyer pays the first time each sequence of four changes comes up
+fn buyer_prices(init_secret: isize) -> FxHashMap<PriceChanges, isize> {
+    let mut secret_totals: FxHashMap<PriceChanges, isize> = FxHashMap::default();
+    let secrets = get_all_secrets(init_secret, 2000);
+    let prices = secrets_to_prices(&secrets);
+    let price_diffs = price_diffs(&prices);
+    for i in 3..price_diffs.len() {
+        let k = (price_diffs[i-3], price_diffs[i-2], price_diffs[i-1], price_diffs[i]);
+        secret_totals.entry(k).or_insert(prices[i+1]);
     }
-    _solution2(&init_secrets)
-}
-
-pub fn _solution2(init_secrets: &Vec<isize>) -> isize {
-    let mut totals: FxHashMap<(isize, isize, isize, isize), isize> = FxHashMap::default();
-    totals.reserve(20*20*20*20);
-
-    for init_secret in init_secrets.iter() {
-        let mut secret_totals: FxHashMap<(isize, isize, isize, isize), isize> = FxHashMap::default();
-        let secrets = get_all_secrets(*init_secret, 2000);
-        let prices = secrets_to_prices(&secrets);
-        let price_diffs = price_diffs(&prices);
-        for i in 3..price_diffs.len() {
-            let k = (price_diffs[i-3], price_diffs[i-2], price_diffs[i-1], price_diffs[i]);
-            if !secret_totals.contains_key(&k) {
-                secret_totals.entry(k.clone()).or_insert(prices[i+1]);
-                if !totals.contains_key(&k) {
-                    totals.entry(k.clone()).or_insert(prices[i+1]);
-                } else {
-                    totals.entry(k).and_modify(|v| *v += prices[i+1]);
-                }
-            }
-        }
+    secret_totals
+}
+
+fn add_prices(mut totals: FxHashMap<PriceChanges, isize>, prices: FxHashMap<PriceChanges, isize>) -> FxHashMap<PriceChanges, isize> {
+    for (k, price) in prices {
+        *totals.entry(k).or_insert(0) += price;
+    }
+    totals
+}
+
+fn price_totals(init_secrets: &[isize]) -> FxHashMap<PriceChanges, isize> {
+    // buyers are independent, each thread totals its share and the shares are merged at the end
+    init_secrets.par_iter()
+        .fold(FxHashMap::default, |totals, init_secret| add_prices(totals, buyer_prices(*init_secret)))
+        .reduce(FxHashMap::default, add_prices)
+}
+
+pub fn solution2_str(input: &str) -> Result<isize, ParseError> {
+    Ok(most_bananas(&price_totals(&parse_secrets(input)?)))
+}
+
+// only the totals for each sequence are kept between batches, never the buyers
+pub fn streamed_most_bananas<R: BufRead>(reader: R) -> Result<isize, StreamError> {
+    let mut totals = FxHashMap::default();
+    for init_secrets in secret_batches(reader) {
+        totals = add_prices(totals, price_totals(&init_secrets?));
     }
+    Ok(most_bananas(&totals))
+}
+
+fn most_bananas(totals: &FxHashMap<PriceChanges, isize>) -> isize {
     let mut max_bananas = 0;
     let mut best_seq = (0, 0, 0 , 0);
     for (seq, val) in totals.iter() {
@@ -126,10 +174,29 @@ pub fn _solution2(init_secrets: &Vec<isize>) -> isize {
             best_seq = *seq;
         }
     }
-    println!("{:?}", best_seq);
+    debug!("{:?}", best_seq);
     max_bananas
 }
 
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<isize>;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_secrets(input)
+    }
+
+    fn part1(init_secrets: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(secret_sum(init_secrets).into())
+    }
+
+    fn part2(init_secrets: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(most_bananas(&price_totals(init_secrets)).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
@@ -138,12 +205,26 @@ mod tests  {
     #[test]
     fn day_22_1_1() {
         let path = common::get_test_data_path("day22/case1.txt").unwrap();
-        assert_eq!(solution1(&path), 37327623);
+        assert_eq!(solution1(&path).unwrap(), 37327623);
     }
 
     #[test]
     fn day_22_2_1() {
         let path = common::get_test_data_path("day22/case2.txt").unwrap();
-        assert_eq!(solution2(&path), 23);
+        assert_eq!(solution2(&path).unwrap(), 23);
+    }
+
+    #[test]
+    fn day_22_bad_secret() {
+        let err = parse_secrets("1\n10\n1oo\n").unwrap_err();
+        assert_eq!(err.kind, crate::error::ParseErrorKind::InvalidNumber);
+        assert_eq!((err.line, err.column), (3, 1));
+    }
+
+    #[test]
+    fn day_22_streamed() {
+        assert_eq!(streamed_secret_sum("1\n10\n100\n2024\n".as_bytes()).unwrap(), 37327623);
+        assert_eq!(streamed_most_bananas("1\n2\n3\n2024\n".as_bytes()).unwrap(), 23);
+        assert!(matches!(streamed_secret_sum("1\n1oo\n".as_bytes()), Err(StreamError::Parse(_))));
     }
 }
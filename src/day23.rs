@@ -1,36 +1,66 @@
+use std::error::Error;
 use std::{fmt::write, fs};
-use std::path::PathBuf;
+use std::io::BufRead;
+use std::path::Path;
 use rustc_hash::{FxHashMap, FxHashSet};
+use log::debug;
+
+use crate::config::NoConfig;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
+use crate::stream::{self, StreamError};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day23/input.txt";
 }
 
-type Graph = FxHashMap<String, FxHashSet<String>>;
+const DAY: usize = 23;
+
+pub type Graph = FxHashMap<String, FxHashSet<String>>;
+
+fn parse_link(line_idx: usize, line: &str) -> Result<(String, String), ParseError> {
+    let (a, b) = line.split_once('-').ok_or_else(|| {
+        ParseError::new(DAY, ParseErrorKind::MissingToken("'-'"))
+            .at(line_idx, line.len())
+            .with_text(line)
+    })?;
+    Ok((a.to_string(), b.to_string()))
+}
+
+fn add_link(g: &mut Graph, a: String, b: String) {
+    g.entry(a.clone()).or_default().insert(b.clone());
+    g.entry(b).or_default().insert(a);
+}
 
-fn graph_from_input(input: &String) -> Graph {
+fn graph_from_input(input: &str) -> Result<Graph, ParseError> {
     let mut result = FxHashMap::default();
-    for line in input.lines() {
-        let (a, b) = line.split_once('-').unwrap();
-        if result.contains_key(a) {
-            result.entry(a.to_string()).and_modify(|m: &mut FxHashSet<String>| { m.insert(b.to_string()); });
-        } else {
-            result.entry(a.to_string()).or_default();
-            result.get_mut(&a.to_string()).unwrap().insert(b.to_string());
-        }
-        if result.contains_key(b) {
-            result.entry(b.to_string()).and_modify(|m: &mut FxHashSet<String>| { m.insert(a.to_string()); });
-        } else {
-            result.entry(b.to_string()).or_default();
-            result.get_mut(&b.to_string()).unwrap().insert(a.to_string());
-        }
+    for (line_idx, line) in input.lines().enumerate() {
+        let (a, b) = parse_link(line_idx, line)?;
+        add_link(&mut result, a, b);
     }
-    result
+    check_not_empty(result)
+}
+
+// there's no largest network to find without any links
+fn check_not_empty(g: Graph) -> Result<Graph, ParseError> {
+    match g.is_empty() {
+        true => Err(ParseError::new(DAY, ParseErrorKind::Empty)),
+        false => Ok(g),
+    }
+}
+
+// builds the graph a link at a time, without holding the text
+pub fn read_graph<R: BufRead>(reader: R) -> Result<Graph, StreamError> {
+    let mut result = FxHashMap::default();
+    for link in stream::records(reader, parse_link) {
+        let (a, b) = link?;
+        add_link(&mut result, a, b);
+    }
+    check_not_empty(result).map_err(StreamError::Parse)
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    Ok(count_t_networks(&read_graph(stream::open(path)?)?))
 }
 
 pub fn find_networks(g: &Graph) -> Vec<(String, String, String)> {
@@ -64,7 +94,7 @@ pub fn find_largest_network(g: &Graph) -> Vec<String> {
         n.sort();
     }
 
-    println!("network ({}) contenders remaining: {}", networks[0].len(), networks.len());
+    debug!("network ({}) contenders remaining: {}", networks[0].len(), networks.len());
 
     loop {
         let mut next_networks = vec![];
@@ -91,7 +121,7 @@ pub fn find_largest_network(g: &Graph) -> Vec<String> {
             }
         }
         networks = next_networks;
-        println!("network ({}) contenders remaining: {}", networks[0].len(), networks.len());
+        debug!("network ({}) contenders remaining: {}", networks[0].len(), networks.len());
         if networks.len() == 1 {
             return networks[0].clone();
         }
@@ -101,10 +131,12 @@ pub fn find_largest_network(g: &Graph) -> Vec<String> {
     }
 }
 
-pub fn _solution1(input: &String) -> isize {
-    let g = graph_from_input(&input);
+pub fn solution1_str(input: &str) -> Result<isize, ParseError> {
+    Ok(count_t_networks(&graph_from_input(input)?))
+}
 
-    let networks = find_networks(&g);
+fn count_t_networks(g: &Graph) -> isize {
+    let networks = find_networks(g);
 
     let mut result = 0;
     for network in networks.iter() {
@@ -115,17 +147,38 @@ pub fn _solution1(input: &String) -> isize {
     result
 }
 
-pub fn solution2(path: &PathBuf) -> String {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<String, Box<dyn Error>> {
+    Ok(lan_party_password(&read_graph(stream::open(path)?)?))
 }
 
-pub fn _solution2(input: &String) -> String {
-    let g = graph_from_input(&input);
-    let network = find_largest_network(&g);
+pub fn solution2_str(input: &str) -> Result<String, ParseError> {
+    Ok(lan_party_password(&graph_from_input(input)?))
+}
+
+fn lan_party_password(g: &Graph) -> String {
+    let network = find_largest_network(g);
     network.join(",").to_string()
 }
 
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Graph;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        graph_from_input(input)
+    }
+
+    fn part1(g: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_t_networks(g).into())
+    }
+
+    fn part2(g: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(lan_party_password(g).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
@@ -135,24 +188,31 @@ mod tests  {
     fn day_23_1_1() {
         let path = common::get_test_data_path("day23/case1.txt").unwrap();
         let input =  fs::read_to_string(path).unwrap();
-        let g = graph_from_input(&input);
+        let g = graph_from_input(&input).unwrap();
         println!("{:#?}", g);
         let networks = find_networks(&g);
         println!("{:#?}", networks);
         assert_eq!(networks.len(), 12);
     }
 
+    #[test]
+    fn day_23_empty() {
+        assert_eq!(solution2_str("").unwrap_err().kind, ParseErrorKind::Empty);
+        let err = read_graph("".as_bytes()).unwrap_err();
+        assert!(matches!(err, StreamError::Parse(ParseError { kind: ParseErrorKind::Empty, .. })));
+    }
+
     #[test]
     fn day_23_1_2() {
         let path = common::get_test_data_path("day23/case1.txt").unwrap();
-        assert_eq!(solution1(&path), 7);
+        assert_eq!(solution1(&path).unwrap(), 7);
     }
 
     #[test]
     fn day_23_2_1() {
         let path = common::get_test_data_path("day23/case1.txt").unwrap();
         let input =  fs::read_to_string(path).unwrap();
-        let g = graph_from_input(&input);
+        let g = graph_from_input(&input).unwrap();
         println!("{:#?}", g);
         let networks = find_largest_network(&g);
         println!("{:#?}", networks);
@@ -162,6 +222,22 @@ mod tests  {
     #[test]
     fn day_23_2_2() {
         let path = common::get_test_data_path("day23/case1.txt").unwrap();
-        assert_eq!(solution2(&path), String::from("co,de,ka,ta"));
+        assert_eq!(solution2(&path).unwrap(), String::from("co,de,ka,ta"));
+    }
+
+    #[test]
+    fn day_23_bad_link() {
+        let err = graph_from_input(&String::from("kh-tc\nqp kh\n")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("'-'"));
+        assert_eq!((err.line, err.column), (2, 6));
+    }
+
+    #[test]
+    fn day_23_streamed() {
+        let path = common::get_test_data_path("day23/case1.txt").unwrap();
+        let input =  fs::read_to_string(path).unwrap();
+        assert_eq!(read_graph(input.as_bytes()).unwrap(), graph_from_input(&input).unwrap());
+        let err = read_graph("kh-tc\nqp kh\n".as_bytes()).unwrap_err();
+        assert!(matches!(err, StreamError::Parse(e) if (e.line, e.column) == (2, 6)));
     }
 }
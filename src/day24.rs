@@ -1,18 +1,27 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 
 use rustc_hash::{FxHashMap, FxHashSet};
 use rand::Rng;
+use log::{debug, info, trace};
+
+use crate::config::NoConfig;
+use crate::error::{column_of, expect_token, parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day24/input.txt";
 }
 
-pub fn solution1(path: &PathBuf) -> isize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+const DAY: usize = 24;
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<isize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
+#[derive(Clone)]
 pub struct Device {
     wires: Vec<Wire>,
     gates: Vec<Gate>,
@@ -61,7 +70,7 @@ false => 0,
 }
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Wire {
     id: String,
     output_val: isize,
@@ -82,32 +91,41 @@ pub enum GateKind {
     AND,
 }
 
-pub fn parse(input: &String) -> Device {
-    let wire_lines = input.lines().take_while(|&l| l != "");
-    let gate_lines = input.lines().rev().take_while(|&l| l != "");
+pub fn parse(input: &str) -> Result<Device, ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let wire_lines = lines.iter().enumerate().take_while(|(_, l)| !l.is_empty());
+    let gate_lines = lines.iter().enumerate().rev().take_while(|(_, l)| !l.is_empty());
 
     let mut wires = vec![];
-    for wl in wire_lines {
-        let (id, output_val) = wl.split_once(": ").unwrap();
+    for (line_idx, wl) in wire_lines {
+        let (id, output_val) = wl.split_once(": ").ok_or_else(|| {
+            ParseError::new(DAY, ParseErrorKind::MissingToken("': '"))
+                .at(line_idx, wl.len())
+                .with_text(wl)
+        })?;
         wires.push(Wire {
             id: id.to_string(),
-            output_val: output_val.parse::<isize>().unwrap(),
+            output_val: parse_num::<isize>(DAY, line_idx, wl, output_val)?,
         });
     }
 
     let mut gates = vec![];
-    for gl in gate_lines {
+    for (line_idx, gl) in gate_lines {
         let mut components = gl.split_whitespace();
-        let input1 = components.next().unwrap().to_string();
-        let op = components.next().unwrap();
-        let input2 = components.next().unwrap().to_string();
-        let _arrow = components.next().unwrap();
-        let output = components.next().unwrap().to_string();
+        let input1 = expect_token(DAY, line_idx, gl, components.next(), "input wire")?.to_string();
+        let op = expect_token(DAY, line_idx, gl, components.next(), "gate")?;
+        let input2 = expect_token(DAY, line_idx, gl, components.next(), "input wire")?.to_string();
+        let _arrow = expect_token(DAY, line_idx, gl, components.next(), "'->'")?;
+        let output = expect_token(DAY, line_idx, gl, components.next(), "output wire")?.to_string();
         let gatekind = match op {
             "XOR" => GateKind::XOR,
             "OR" => GateKind::OR,
             "AND" => GateKind::AND,
-            _ => panic!("unexpected gatekind"),
+            _ => {
+                return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedToken("XOR, OR or AND"))
+                    .at(line_idx, column_of(gl, op))
+                    .with_text(gl));
+            },
         };
         gates.push(Gate {
             input1,
@@ -117,11 +135,11 @@ pub fn parse(input: &String) -> Device {
         })
     }
 
-    Device {
+    Ok(Device {
         wires,
         gates,
         output_vals: FxHashMap::default(),
-    }
+    })
 }
 
 pub fn compute_z_output(device: &mut Device) -> isize {
@@ -168,14 +186,14 @@ pub fn compute_z_output(device: &mut Device) -> isize {
     result
 }
 
-pub fn _solution1(input: &String) -> isize {
-    let mut device = parse(&input);
-    compute_z_output(&mut device)
+pub fn solution1_str(input: &str) -> Result<isize, ParseError> {
+    let mut device = parse(input)?;
+    Ok(compute_z_output(&mut device))
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
 //x08 y08 -> z08
@@ -206,7 +224,7 @@ fn render_as_zs(num: isize) {
     for i in 0..45 {
         let bit = bit_at(num, i);
         if bit == 1 {
-            println!("\tz{:02} = {}", i, bit);
+            debug!("\tz{:02} = {}", i, bit);
         }
     }
 }
@@ -231,17 +249,17 @@ fn rand_test(device: &Device, gate_overrides: &Vec<Gate>, iterations: isize) ->
 fn search_for_corruption(device: &Device) {
     let mut total_fails = 0;
     for i in 0..45 {
-        println!("====== i = {} =======", i);
+        debug!("====== i = {} =======", i);
         {
             let x = 1 << i;
             let y = 1 << i;
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 0), z{:02} == 1", i, i, i, i + 1);
+            trace!("x{:02} + y{:02}  == (z{:02} = 0), z{:02} == 1", i, i, i, i + 1);
             if output == 2*(1 << i) {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -252,10 +270,10 @@ fn search_for_corruption(device: &Device) {
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
+            trace!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
             if output == 1 << i {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -266,10 +284,10 @@ fn search_for_corruption(device: &Device) {
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
+            trace!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
             if output == 1 << i {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -286,10 +304,10 @@ fn get_potential_swaps(device: &Device, shifts: &Vec<isize>, tainted_inputs: &Ve
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 0), z{:02} == 1", i, i, i, i + 1);
+            trace!("x{:02} + y{:02}  == (z{:02} = 0), z{:02} == 1", i, i, i, i + 1);
             if output == 2*(1 << i) {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -300,10 +318,10 @@ fn get_potential_swaps(device: &Device, shifts: &Vec<isize>, tainted_inputs: &Ve
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
+            trace!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
             if output == 1 << i {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -314,10 +332,10 @@ fn get_potential_swaps(device: &Device, shifts: &Vec<isize>, tainted_inputs: &Ve
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
+            trace!("x{:02} + y{:02}  == (z{:02} = 1)", i, i, i);
             if output == 1 << i {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -328,10 +346,10 @@ fn get_potential_swaps(device: &Device, shifts: &Vec<isize>, tainted_inputs: &Ve
             let mut d = Device::from_sum(device.gates.clone(), x, y);
             d.seed_wire_outputs();
             let output = compute_z_output(&mut d);
-            println!("x{:02} + y{:02}  == (z{:02} = 0)", i, i, i);
+            trace!("x{:02} + y{:02}  == (z{:02} = 0)", i, i, i);
             if output == 0 {
             } else {
-                println!("\tFAIL");
+                debug!("\tFAIL");
                 render_as_zs(output);
                 total_fails += 1;
             }
@@ -363,7 +381,7 @@ fn get_potential_swaps(device: &Device, shifts: &Vec<isize>, tainted_inputs: &Ve
         }
         tainted = new_tainted;
     }
-    println!("num maybe corrupt: {}", maybe_corrupt.len());
+    debug!("num maybe corrupt: {}", maybe_corrupt.len());
 
     let maybe_corrupt: Vec<Gate> = maybe_corrupt.into_iter().collect();
     let mut potential_swaps = vec![];
@@ -452,8 +470,11 @@ fn get_potential_swaps(device: &Device, shifts: &Vec<isize>, tainted_inputs: &Ve
     results
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let device = parse(&input);
+pub fn solution2_str(input: &str) -> Result<usize, ParseError> {
+    Ok(find_swapped_wires(&parse(input)?))
+}
+
+fn find_swapped_wires(device: &Device) -> usize {
     search_for_corruption(&device);
 
     let test_groups = vec![
@@ -512,8 +533,8 @@ pub fn _solution2(input: &String) -> usize {
             found = false;
         }
     }
-    println!("potential swaps");
-    println!("{:#?}", potential_swaps);
+    debug!("potential swaps");
+    debug!("{:#?}", potential_swaps);
     if !found {
         panic!("failed to find possible swaps in each group");
     }
@@ -556,7 +577,7 @@ pub fn _solution2(input: &String) -> usize {
 
                     let fail_count = rand_test(&device, &swapped_gates, 10000);
                     if fail_count == 0 {
-                        println!("found solution");
+                        info!("found solution");
                         let mut v = vec![
                             swapped_gates[*s1_g1_idx].output.clone(),
                             swapped_gates[*s1_g2_idx].output.clone(),
@@ -568,13 +589,13 @@ pub fn _solution2(input: &String) -> usize {
                             swapped_gates[*s4_g2_idx].output.clone(),
                         ];
                         v.sort();
-                        println!("{}", v.join(","));
+                        info!("{}", v.join(","));
                     } else {
-                        println!("failed {}", fail_count);
-                        println!("{:#?}", s1);
-                        println!("{:#?}", s2);
-                        println!("{:#?}", s3);
-                        println!("{:#?}", s4);
+                        debug!("failed {}", fail_count);
+                        trace!("{:#?}", s1);
+                        trace!("{:#?}", s2);
+                        trace!("{:#?}", s3);
+                        trace!("{:#?}", s4);
                     }
                 }
             }
@@ -585,6 +606,25 @@ pub fn _solution2(input: &String) -> usize {
 
 }
 
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Device;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(device: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(compute_z_output(&mut device.clone()).into())
+    }
+
+    fn part2(device: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(find_swapped_wires(device).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
@@ -593,13 +633,21 @@ mod tests  {
     #[test]
     fn day24_1_1() {
         let path = common::get_test_data_path("day24/case1.txt").unwrap();
-        assert_eq!(solution1(&path), 2024);
+        assert_eq!(solution1(&path).unwrap(), 2024);
     }
 
     #[test]
     fn day24_2_1() {
         assert!(false, "todo")
     }
+
+    #[test]
+    fn day24_bad_gate() {
+        let input = String::from("x00: 1\ny00: 0\n\nx00 NAND y00 -> z00\n");
+        let err = parse(&input).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedToken("XOR, OR or AND"));
+        assert_eq!((err.line, err.column), (4, 5));
+    }
 }
 
 //z08
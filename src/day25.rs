@@ -1,5 +1,11 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
+
+use crate::common::grid::{Grid, TileMap};
+use crate::config::NoConfig;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Lock {
@@ -11,85 +17,59 @@ pub struct Key {
     tooth_heights: Vec<isize>,
 }
 
-enum ParseStage {
-    Lock,
-    Key,
-    Nothing,
-}
-
 pub mod constants {
     pub const INPUT_PATH: &str = "day25/input.txt";
 }
 
-fn combine_heights(line: &str, heights: &mut Vec<isize>) {
-    for (i, c) in line.chars().enumerate() {
-        match c {
-            '#' => heights[i] += 1,
-            '.' => (),
-            _ => panic!("unexpected input found"),
-        }
-    }
+const DAY: usize = 25;
+
+const TILES: TileMap<bool> = TileMap {
+    tiles: &[('#', true), ('.', false)],
+    markers: &[],
+};
+
+// filled cells in each column, not counting the lock's top row or the key's bottom one
+fn heights(schematic: &Grid<bool>) -> Vec<isize> {
+    (0..schematic.width())
+        .map(|x| (0..schematic.height()).filter(|y| schematic[(x, *y)]).count() as isize - 1)
+        .collect()
 }
 
-fn parse(input: &String) -> (Vec<Key>, Vec<Lock>) {
+fn parse(input: &str) -> Result<(Vec<Key>, Vec<Lock>), ParseError> {
     let mut keys = vec![];
     let mut locks = vec![];
 
-    let mut current_key: Option<Key> = None;
-    let mut current_lock: Option<Lock> = None;
-
-    let mut parse_stage = ParseStage::Nothing;
-    for l in input.lines() {
-        match parse_stage {
-            ParseStage::Key => {
-                if l == "" {
-                    parse_stage = ParseStage::Nothing;
-                    keys.push(current_key.clone().unwrap().clone());
-                    current_key = None;
-                } else {
-                    combine_heights(l, &mut current_key.as_mut().unwrap().tooth_heights);
-                }
-            },
-            ParseStage::Lock => {
-                if l == "" {
-                    parse_stage = ParseStage::Nothing;
-                    locks.push(current_lock.clone().unwrap().clone());
-                    current_lock = None;
-                } else {
-                    combine_heights(l, &mut current_lock.as_mut().unwrap().pin_heights);
-                }
-            },
-            ParseStage::Nothing => {
-                if l.starts_with('#') {
-                    parse_stage = ParseStage::Lock;
-                    current_lock = Some(Lock { pin_heights: vec![0; l.len()] });
-                } else {
-                    parse_stage = ParseStage::Key;
-                    current_key = Some(Key { tooth_heights: vec![-1; l.len()] });
-                }
-            },
+    let mut lines = input.lines().enumerate().peekable();
+    while let Some(&(start, _)) = lines.peek() {
+        let block: Vec<&str> = lines.by_ref().map(|(_, l)| l).take_while(|l| !l.is_empty()).collect();
+        if block.is_empty() {
+            continue;
+        }
+        let schematic = TILES.parse_lines(DAY, block).map_err(|e| e.offset_lines(start))?.grid;
+        // locks hang from a filled top row, keys stand on a filled bottom one
+        match schematic[(0, 0)] {
+            true => locks.push(Lock { pin_heights: heights(&schematic) }),
+            false => keys.push(Key { tooth_heights: heights(&schematic) }),
         }
     }
-    match (&mut current_key, &mut current_lock) {
-        (Some(_key), _) => {
-            keys.push(current_key.clone().unwrap().clone());
-        },
-        (_, Some(_lock)) => {
-            locks.push(current_lock.clone().unwrap().clone());
-        },
-        _ => panic!("should be processing a key or lock at eof"),
+
+    if keys.is_empty() && locks.is_empty() {
+        return Err(ParseError::new(DAY, ParseErrorKind::Empty));
     }
+    Ok((keys, locks))
+}
 
-    (keys, locks)
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1_str(input: &str) -> Result<usize, ParseError> {
+    let (keys, locks) = parse(input)?;
+    Ok(count_fits(&keys, &locks))
 }
 
-pub fn _solution1(input: &String) -> usize {
-    let (keys, locks) = parse(&input);
+fn count_fits(keys: &[Key], locks: &[Lock]) -> usize {
     let mut fit_count = 0;
     let key_height = 7;
 
@@ -111,15 +91,35 @@ pub fn _solution1(input: &String) -> usize {
     fit_count
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input))
 }
 
-pub fn _solution2(input: &String) -> usize {
+pub fn solution2_str(_input: &str) -> usize {
     0
 }
 
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = (Vec<Key>, Vec<Lock>);
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1((keys, locks): &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_fits(keys, locks).into())
+    }
+
+    // there is no second puzzle on the last day
+    fn part2(_input: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(0usize.into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
@@ -128,14 +128,14 @@ mod tests  {
     #[test]
     fn day25_1_1() {
         let path = common::get_test_data_path("day25/case1.txt").unwrap();
-        assert_eq!(solution1(&path), 3);
+        assert_eq!(solution1(&path).unwrap(), 3);
     }
 
     #[test]
     fn day25_1_parse() {
         let path = common::get_test_data_path("day25/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let (keys, locks) = parse(&input);
+        let (keys, locks) = parse(&input).unwrap();
         assert_eq!(keys, vec![
             Key { tooth_heights: vec![5, 0, 2, 1, 3]},
             Key { tooth_heights: vec![4, 3, 4, 0, 2]},
@@ -151,4 +151,16 @@ mod tests  {
     fn day25_2_1() {
         assert!(false, "todo")
     }
+
+    #[test]
+    fn day25_bad_pin() {
+        let input = String::from("#####\n.####\n.##o#\n");
+        let err = parse(&input).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('o'));
+        assert_eq!((err.line, err.column), (3, 4));
+
+        let err = parse(&String::from("#####\n.####\n\n.....\n.#.#\n")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::RaggedRow { expected: 5, found: 4 });
+        assert_eq!(err.line, 5);
+    }
 }
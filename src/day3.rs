@@ -1,75 +1,242 @@
 use std::error::Error;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::Path;
 use std::fs;
-use regex::Regex;
+use log::trace;
+use rustc_hash::FxHashMap;
+
+use crate::config::NoConfig;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const MEMORY_FILE_PATH: &str = "day3/memory.txt";
 }
 
 
-pub fn multiply(memory_file: PathBuf) -> Result<u64, Box<dyn Error>> {
-    let memory = fs::read_to_string(memory_file)?;
-    Ok(multiply_str(&memory))
+// the most digits an argument can have
+const MAX_ARG_DIGITS: usize = 3;
+
+// what instructions can change as the memory runs. registered instructions keep anything of
+// their own in values, under whatever names they like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub enabled: bool,
+    pub values: FxHashMap<String, u64>,
 }
 
-pub fn multiply_str(memory: &String) -> u64 {
-    let rx = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").expect("invalid regex");
-    let mut operands = vec![];
-    let mut count = 0;
-    let mut result = 0;
-    for (_, [num1, num2]) in rx.captures_iter(&memory).map(|c| c.extract()) {
-        let num1 = num1.parse::<u64>().unwrap();
-        let num2 = num2.parse::<u64>().unwrap();
-        count += 1;
-        operands.push((num1, num2));
-        println!("{} mul({}, {})", count, num1, num2);
-        result += num1*num2;
+impl Default for State {
+    fn default() -> Self {
+        Self { enabled: true, values: FxHashMap::default() }
     }
-    result
 }
 
-enum MultMode {
-    ENABLED,
-    DISABLED,
+// runs an instruction with its arguments, returning what it adds to the result, if anything
+pub type Handler = Box<dyn Fn(&mut State, &[u64]) -> Option<u64>>;
+
+// an instruction is written name(arg,...) with exactly arity arguments of 1-3 digits each,
+// anything else in the memory is corruption and skipped over
+pub struct Instruction {
+    pub name: String,
+    pub arity: usize,
+    handler: Handler,
 }
 
-pub fn cond_multiply(memory_file: PathBuf) -> Result<u64, Box<dyn Error>> {
-    let memory = fs::read_to_string(memory_file)?;
-    Ok(cond_multiply_str(&memory))
-}
-
-pub fn cond_multiply_str(memory: &String) -> u64 {
-    const ENABLE_MUL_TOKEN: &str = "do()";
-    const DISABLE_MUL_TOKEN: &str = "don't()";
-
-    let rx = Regex::new(r"do\(\)|don't\(\)|mul\((\d{1,3}),(\d{1,3})\)").expect("invalid regex");
-    let mut mode = MultMode::ENABLED;
-    let mut result = 0;
-
-    for capture in rx.captures_iter(&memory) {
-        let full_match = &capture[0];
-        if full_match == ENABLE_MUL_TOKEN {
-            mode = MultMode::ENABLED;
-        } else if full_match == DISABLE_MUL_TOKEN {
-            mode = MultMode::DISABLED;
-        } else {
-            match mode {
-                MultMode::ENABLED => {
-                    let num1 = capture[1].parse::<u64>().unwrap();
-                    let num2 = capture[2].parse::<u64>().unwrap();
-                    result += num1 * num2;
-                },
-                MultMode::DISABLED => {
-                   // do nothing 
+impl Instruction {
+    pub fn new<F>(name: &str, arity: usize, handler: F) -> Self
+    where
+        F: Fn(&mut State, &[u64]) -> Option<u64> + 'static,
+    {
+        Self { name: name.to_string(), arity, handler: Box::new(handler) }
+    }
+
+    pub fn mul() -> Self {
+        Self::new("mul", 2, |state, args| state.enabled.then(|| args[0] * args[1]))
+    }
+
+    pub fn add() -> Self {
+        Self::new("add", 2, |state, args| state.enabled.then(|| args[0] + args[1]))
+    }
+
+    // do()
+    pub fn enable() -> Self {
+        Self::new("do", 0, |state, _| {
+            state.enabled = true;
+            None
+        })
+    }
+
+    // don't()
+    pub fn disable() -> Self {
+        Self::new("don't", 0, |state, _| {
+            state.enabled = false;
+            None
+        })
+    }
+
+    // the arguments and length of a call to this instruction at the start of text
+    fn parse_call(&self, text: &str) -> Option<(Vec<u64>, usize)> {
+        let mut rest = text.strip_prefix(self.name.as_str())?.strip_prefix('(')?;
+        let mut args = Vec::with_capacity(self.arity);
+        for i in 0..self.arity {
+            if i > 0 {
+                rest = rest.strip_prefix(',')?;
+            }
+            let digits = rest.bytes().take_while(|b| b.is_ascii_digit()).count();
+            if digits == 0 || digits > MAX_ARG_DIGITS {
+                return None;
+            }
+            args.push(rest[..digits].parse().ok()?);
+            rest = &rest[digits..];
+        }
+        rest = rest.strip_prefix(')')?;
+        Some((args, text.len() - rest.len()))
+    }
+}
+
+// a call to one of the interpreter's instructions, found offset bytes into the memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub offset: usize,
+    pub len: usize,
+    // index into the interpreter's instructions
+    pub instruction: usize,
+    pub args: Vec<u64>,
+}
+
+// a token that was run, and what it added to the result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub offset: usize,
+    pub text: String,
+    pub value: Option<u64>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.value {
+            Some(value) => write!(f, "offset {}: {} = {}", self.offset, self.text, value),
+            None => write!(f, "offset {}: {}", self.offset, self.text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub result: u64,
+    pub trace: Vec<Step>,
+    // as the last instruction left it
+    pub state: State,
+}
+
+// the values added up went past u64::MAX, at the instruction offset bytes into the memory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overflow {
+    pub offset: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the result overflowed at offset {}", self.offset)
+    }
+}
+
+impl Error for Overflow {}
+
+#[derive(Default)]
+pub struct Interpreter {
+    instructions: Vec<Instruction>,
+}
+
+impl Interpreter {
+    // part 1, where only mul does anything
+    pub fn multiply() -> Self {
+        Self::default().register(Instruction::mul())
+    }
+
+    // part 2, where do() and don't() turn mul on and off
+    pub fn conditional() -> Self {
+        Self::multiply()
+            .register(Instruction::enable())
+            .register(Instruction::disable())
+    }
+
+    // an instruction with the same name as one already registered replaces it
+    pub fn register(mut self, instruction: Instruction) -> Self {
+        self.instructions.retain(|i| i.name != instruction.name);
+        self.instructions.push(instruction);
+        self
+    }
+
+    // scans the memory a byte at a time for calls, skipping past each one found
+    pub fn tokenize(&self, memory: &str) -> Vec<Token> {
+        let mut tokens = vec![];
+        let mut offset = 0;
+        while offset < memory.len() {
+            let call = self.instructions.iter().enumerate()
+                .find_map(|(idx, instruction)| instruction.parse_call(&memory[offset..]).map(|call| (idx, call)));
+            match call {
+                Some((instruction, (args, len))) => {
+                    tokens.push(Token { offset, len, instruction, args });
+                    offset += len;
                 },
-            };
+                None => offset += memory[offset..].chars().next().map_or(1, char::len_utf8),
+            }
         }
+        tokens
     }
 
-    result
+    pub fn run(&self, memory: &str) -> Result<Run, Overflow> {
+        let mut state = State::default();
+        let mut result: u64 = 0;
+        let mut trace = vec![];
+        for token in self.tokenize(memory) {
+            let text = &memory[token.offset..token.offset + token.len];
+            let value = (self.instructions[token.instruction].handler)(&mut state, &token.args);
+            trace!("{} {} {:?}", token.offset, text, value);
+            result = result.checked_add(value.unwrap_or(0)).ok_or(Overflow { offset: token.offset })?;
+            trace.push(Step { offset: token.offset, text: text.to_string(), value });
+        }
+        Ok(Run { result, trace, state })
+    }
+}
+
+pub fn multiply(memory_file: impl AsRef<Path>) -> Result<u64, Box<dyn Error>> {
+    let memory = fs::read_to_string(memory_file)?;
+    Ok(multiply_str(&memory)?)
 }
 
+pub fn multiply_str(memory: &str) -> Result<u64, Overflow> {
+    Ok(Interpreter::multiply().run(memory)?.result)
+}
+
+pub fn cond_multiply(memory_file: impl AsRef<Path>) -> Result<u64, Box<dyn Error>> {
+    let memory = fs::read_to_string(memory_file)?;
+    Ok(cond_multiply_str(&memory)?)
+}
+
+pub fn cond_multiply_str(memory: &str) -> Result<u64, Overflow> {
+    Ok(Interpreter::conditional().run(memory)?.result)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(memory: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(multiply_str(memory)?.into())
+    }
+
+    fn part2(memory: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(cond_multiply_str(memory)?.into())
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -78,12 +245,62 @@ mod tests {
     #[test]
     fn test_example() {
         let s = String::from(r"xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))");
-        assert_eq!(multiply_str(&s), 161, "correctly computes example result");
+        assert_eq!(multiply_str(&s).unwrap(), 161, "correctly computes example result");
     }
 
     #[test]
     fn test_conditional_example() {
         let s = String::from(r"xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))");
-        assert_eq!(cond_multiply_str(&s), 48, "correctly computes example result");
+        assert_eq!(cond_multiply_str(&s).unwrap(), 48, "correctly computes example result");
+    }
+
+    #[test]
+    fn test_tokens() {
+        let tokens = Interpreter::conditional().tokenize("mul(1,22)undo()mul(4444,1)mul(3 ,1)don't()");
+        let found: Vec<_> = tokens.iter().map(|t| (t.offset, t.len, t.args.clone())).collect();
+        assert_eq!(found, vec![(0, 9, vec![1, 22]), (11, 4, vec![]), (35, 7, vec![])]);
+    }
+
+    #[test]
+    fn test_trace() {
+        let run = Interpreter::conditional().run("mul(2,4)don't()mul(5,5)do()mul(8,5)").unwrap();
+        assert_eq!(run.result, 48);
+        let trace: Vec<_> = run.trace.iter().map(|step| step.to_string()).collect();
+        assert_eq!(trace, vec![
+            "offset 0: mul(2,4) = 8",
+            "offset 8: don't()",
+            "offset 15: mul(5,5)",
+            "offset 23: do()",
+            "offset 27: mul(8,5) = 40",
+        ]);
+    }
+
+    #[test]
+    fn test_registered_instructions() {
+        let interpreter = Interpreter::conditional()
+            .register(Instruction::add())
+            .register(Instruction::new("neg", 1, |_, _| None))
+            .register(Instruction::new("mul", 3, |state, args| state.enabled.then(|| args[0] * args[1] * args[2])));
+        let run = interpreter.run("add(1,2)mul(2,3)mul(2,3,4)don't()add(5,5)").unwrap();
+        assert_eq!(run.result, 27);
+        assert_eq!(run.trace.len(), 4);
+    }
+
+    #[test]
+    fn test_handler_state() {
+        // handlers can capture what they're registered with and keep values of their own
+        let scale = 10;
+        let interpreter = Interpreter::multiply()
+            .register(Instruction::new("scaled", 1, move |_, args| Some(args[0] * scale)))
+            .register(Instruction::new("count", 0, |state, _| {
+                *state.values.entry(String::from("count")).or_default() += 1;
+                None
+            }));
+        let run = interpreter.run("scaled(4)count()mul(2,3)count()").unwrap();
+        assert_eq!(run.result, 46);
+        assert_eq!(run.state.values["count"], 2);
+
+        let interpreter = Interpreter::default().register(Instruction::new("max", 0, |_, _| Some(u64::MAX)));
+        assert_eq!(interpreter.run("max()xmax()").unwrap_err(), Overflow { offset: 6 });
     }
 }
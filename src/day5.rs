@@ -1,16 +1,23 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::Path;
 use std::slice::Iter;
 use std::fmt;
 use std::fs;
 use std::cmp::Ordering;
 use std::cmp;
 use std::rc::Rc;
+use std::error::Error;
+
+use crate::config::NoConfig;
+use crate::error::{parse_num, ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const PRINTER_UPDATES: &str = "day5/ordering-updates.txt";
 }
 
+const DAY: usize = 5;
+
 pub struct OrderRule {
     before: usize,
     after: usize,
@@ -36,16 +43,19 @@ impl OrderingRules {
         }
     }
 
-    pub fn from_str(&mut self, line: &str) {
-        let page_numbers: Vec<usize> = line.split("|").map(|digits| digits.parse::<usize>().unwrap()).collect();
+    pub fn from_str(&mut self, line: &str) -> Result<(), ParseError> {
+        let (before, after) = line.split_once('|').ok_or_else(|| {
+            ParseError::new(DAY, ParseErrorKind::MissingToken("'|'")).at(0, line.len()).with_text(line)
+        })?;
         let rule = Rc::new(OrderRule {
-            before: page_numbers[0],
-            after: page_numbers[1],
+            before: parse_num(DAY, 0, line, before)?,
+            after: parse_num(DAY, 0, line, after)?,
         });
         self.rules.push(rule.clone());
         let min = cmp::min(rule.before, rule.after);
         let max = cmp::max(rule.before, rule.after);
         self.rules_map.entry((min, max)).or_insert(rule.clone());
+        Ok(())
     }
 
     pub fn iter(&self) -> Iter<Rc<OrderRule>> {
@@ -88,6 +98,10 @@ pub struct Update {
 
 impl Update {
 
+    pub fn middle_page(&self) -> usize {
+        self.page_numbers[self.page_numbers.len()/2]
+    }
+
     pub fn get_pairs(&self) -> Vec<(usize, usize)> {
         let mut pairs = Vec::new();
         for i in 0..self.page_numbers.len()-1 {
@@ -122,9 +136,13 @@ impl PrintUpdates {
         }
     }
 
-    pub fn from_str(&mut self, line: &str) {
-        let page_numbers: Vec<usize> = line.split(",").map(|digits| digits.parse::<usize>().unwrap()).collect();
+    pub fn from_str(&mut self, line: &str) -> Result<(), ParseError> {
+        let mut page_numbers: Vec<usize> = Vec::new();
+        for digits in line.split(',') {
+            page_numbers.push(parse_num(DAY, 0, line, digits)?);
+        }
         self.updates.push(Update { page_numbers });
+        Ok(())
     }
 
     pub fn add(&mut self, update: Update) {
@@ -157,24 +175,26 @@ pub struct PrintInstructions {
 
 impl PrintInstructions {
 
-    pub fn from(raw_details: &String) -> Self {
+    pub fn from(raw_details: &str) -> Result<Self, ParseError> {
         let mut rules = OrderingRules::new();
         let mut updates = PrintUpdates::new();
 
         let mut break_encountered = false;
-        for line in raw_details.lines() {
-            if line == "" {
+        for (line_idx, line) in raw_details.lines().enumerate() {
+            let parsed = if line.is_empty() {
                 break_encountered = true;
+                Ok(())
             } else if break_encountered {
-                updates.from_str(line);
+                updates.from_str(line)
             } else {
-                rules.from_str(line);
-            }
+                rules.from_str(line)
+            };
+            parsed.map_err(|e| e.on_line(line_idx))?;
         }
-        Self {
+        Ok(Self {
             rules,
             updates,
-        }
+        })
     }
 
     pub fn get_ordered_update(&self, update: &Update) -> Update {
@@ -184,21 +204,20 @@ impl PrintInstructions {
             page_numbers,
         }
     }
-
-    pub fn print(&self) {
-        println!("{}", self.rules.to_string());
-        println!("{}", self.updates.to_string());
-    }
 }
 
-pub fn compute_part1_solution(path: &PathBuf) -> usize {
-    let raw_details =  fs::read_to_string(path).unwrap();
-    _compute_part1_solution(&raw_details)
+pub fn compute_part1_solution(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let raw_details =  fs::read_to_string(path)?;
+    Ok(compute_part1_solution_str(&raw_details)?)
 }
 
-pub fn _compute_part1_solution(raw_details: &String) -> usize {
-    let instructions = PrintInstructions::from(&raw_details);
+pub fn compute_part1_solution_str(raw_details: &str) -> Result<usize, ParseError> {
+    let instructions = PrintInstructions::from(raw_details)?;
+    Ok(sum_correct_updates(&instructions))
+}
 
+// the updates already in the order the rules want
+pub fn correct_updates(instructions: &PrintInstructions) -> Vec<&Update> {
     let mut correct_updates: Vec<&Update> = Vec::new();
     for update in instructions.updates.iter() {
         let expected_update = instructions.get_ordered_update(&update);
@@ -206,35 +225,56 @@ pub fn _compute_part1_solution(raw_details: &String) -> usize {
             correct_updates.push(&update);
         }
     }
-    let mut result = 0;
-    for update in correct_updates {
-        let num_pages = update.page_numbers.len();
-        result += update.page_numbers[num_pages/2];
-    }
-    result
+    correct_updates
 }
 
-pub fn compute_part2_solution(path: &PathBuf) -> usize {
-    let raw_details =  fs::read_to_string(path).unwrap();
-    _compute_part2_solution(&raw_details)
+pub fn sum_correct_updates(instructions: &PrintInstructions) -> usize {
+    correct_updates(instructions).iter().map(|update| update.middle_page()).sum()
 }
 
-pub fn _compute_part2_solution(raw_details: &String) -> usize {
-    let instructions = PrintInstructions::from(&raw_details);
+pub fn compute_part2_solution(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let raw_details =  fs::read_to_string(path)?;
+    Ok(compute_part2_solution_str(&raw_details)?)
+}
 
-    let mut corrected_updates: Vec<Update> = Vec::new();
+pub fn compute_part2_solution_str(raw_details: &str) -> Result<usize, ParseError> {
+    let instructions = PrintInstructions::from(raw_details)?;
+    Ok(sum_corrected_updates(&instructions))
+}
+
+// the updates that were out of order, each with its corrected order
+pub fn corrected_updates(instructions: &PrintInstructions) -> Vec<(&Update, Update)> {
+    let mut corrected_updates: Vec<(&Update, Update)> = Vec::new();
     for update in instructions.updates.iter() {
         let expected_update = instructions.get_ordered_update(&update);
         if update.page_numbers != expected_update.page_numbers {
-            corrected_updates.push(expected_update);
+            corrected_updates.push((update, expected_update));
         }
     }
-    let mut result = 0;
-    for update in corrected_updates {
-        let num_pages = update.page_numbers.len();
-        result += update.page_numbers[num_pages/2];
+    corrected_updates
+}
+
+pub fn sum_corrected_updates(instructions: &PrintInstructions) -> usize {
+    corrected_updates(instructions).iter().map(|(_, corrected)| corrected.middle_page()).sum()
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = PrintInstructions;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        PrintInstructions::from(input)
+    }
+
+    fn part1(instructions: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(sum_correct_updates(instructions).into())
+    }
+
+    fn part2(instructions: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(sum_corrected_updates(instructions).into())
     }
-    result
 }
 
 #[cfg(test)]
@@ -245,15 +285,22 @@ mod tests  {
     #[test]
     fn test_example() {
         let path = common::get_test_data_path("day5/case1.txt").unwrap();
-        let result = compute_part1_solution(&path);
+        let result = compute_part1_solution(&path).unwrap();
         assert_eq!(result, 143, "computes ordering and sums correctly pt1");
     }
 
     #[test]
     fn test_example_day2() {
         let path = common::get_test_data_path("day5/case1.txt").unwrap();
-        let result = compute_part2_solution(&path);
+        let result = compute_part2_solution(&path).unwrap();
         assert_eq!(result, 123, "computes ordering and sums correctly pt2");
     }
+
+    #[test]
+    fn test_bad_rule() {
+        let err = compute_part1_solution_str(&String::from("47|53\n97-13\n\n75,47\n")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("'|'"));
+        assert_eq!(err.line, 2);
+    }
 }
 
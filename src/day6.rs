@@ -1,17 +1,24 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::fmt;
 use std::collections::VecDeque;
-use std::collections::hash_map::Entry;
-use rustc_hash::{FxHashMap, FxHashSet};
+use std::iter;
+use rayon::prelude::*;
+use rustc_hash::FxHashSet;
+
+use crate::common::grid::{to_ipos, Grid, Ipos, TileMap, Upos};
+use crate::config::NoConfig;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
 pub mod constants {
     pub const INPUT_PATH: &str = "day6/input.txt";
 }
 
-type Pos = (usize, usize);
-type IPos = (isize, isize);
+const DAY: usize = 6;
 
+#[derive(Clone)]
 enum Occupant {
     Guard(Guard),
     Obstacle(Obstacle),
@@ -26,6 +33,7 @@ enum Orientation {
     Right
 }
 
+#[derive(Clone)]
 struct Guard {
     orientation: Orientation,
 }
@@ -51,6 +59,7 @@ impl Guard {
     }
 }
 
+#[derive(Clone)]
 struct Obstacle {
 }
 
@@ -60,6 +69,7 @@ impl Obstacle {
     }
 }
 
+#[derive(Clone)]
 struct CustomObstacle {
 }
 
@@ -69,6 +79,7 @@ impl CustomObstacle {
     }
 }
 
+#[derive(Clone)]
 struct Tile {
     occupant: Option<Occupant>,
 }
@@ -96,74 +107,40 @@ impl Tile {
     }
 }
 
-struct LabMap {
-    tiles: FxHashMap<Pos, Tile>,
-    visits: FxHashSet<Pos>,
-    num_rows: usize,
-    num_cols: usize,
-    guard_pos: Option<Pos>,
+const TILES: TileMap<Tile> = TileMap {
+    tiles: &[
+        ('.', Tile { occupant: None }),
+        ('#', Tile { occupant: Some(Occupant::Obstacle(Obstacle {})) }),
+    ],
+    markers: &[('^', Tile { occupant: Some(Occupant::Guard(Guard { orientation: Orientation::Up })) })],
+};
+
+#[derive(Clone)]
+pub struct LabMap {
+    tiles: Grid<Tile>,
+    visits: FxHashSet<Upos>,
+    guard_pos: Option<Upos>,
 }
 
 impl LabMap {
 
-    fn new(num_cols: usize, num_rows: usize) -> Self {
-        Self {
-            tiles: FxHashMap::default(),
-            num_rows,
-            num_cols,
-            guard_pos: None,
-            visits: FxHashSet::default(),
-        }        
-    }
-
-    fn place_tile(&mut self, pos: Pos) {
-        let x_pos = pos.0;
-        let y_pos = pos.1;
-
-        assert!(
-            x_pos < self.num_cols,
-            "tile pos ({}, {}) out of expected x bounds [0, {}]",
-            x_pos,
-            y_pos,
-            self.num_cols - 1,
-        );
-        assert!(
-            y_pos < self.num_rows,
-            "tile pos ({}, {}) out of expected y bounds [0, {}]",
-            x_pos,
-            y_pos,
-            self.num_rows - 1,
-        );
-
-        match self.tiles.entry(pos) {
-            Entry::Vacant(entry) => entry.insert(Tile { occupant: None }),
-            Entry::Occupied(_entry) => panic!("attempting to place tile where another tile exists ({}, {})", x_pos, y_pos),
-        };
-    }
-
-    fn place(&mut self, pos: Pos, occupant: Occupant) {
-        let tile = self.tiles.get_mut(&pos).unwrap();
-        tile.place(occupant);
+    fn place(&mut self, pos: Upos, occupant: Occupant) {
+        self.tiles[pos].place(occupant);
     }
 
-    fn place_guard(&mut self, pos: Pos, guard: Guard) {
+    fn place_guard(&mut self, pos: Upos, guard: Guard) {
         self.place(pos, Occupant::Guard(guard));
         self.visits.insert(pos);
         self.guard_pos = Some(pos);
     }
 
-    fn place_obstacle(&mut self, pos: Pos, obstacle: Obstacle) {
-        self.place(pos, Occupant::Obstacle(obstacle))
-    }
-
-    fn place_custom_obstacle(&mut self, pos: Pos, obstacle: CustomObstacle) {
+    fn place_custom_obstacle(&mut self, pos: Upos, obstacle: CustomObstacle) {
         self.place(pos, Occupant::CustomObstacle(obstacle))
     }
 
-    fn take_guard(&mut self, pos: Pos) -> Guard {
-        let tile = self.tiles.get_mut(&pos).unwrap();
+    fn take_guard(&mut self, pos: Upos) -> Guard {
         self.guard_pos = None;
-        match tile.take() {
+        match self.tiles[pos].take() {
             Occupant::Guard(guard) => guard,
             _ => panic!("guard not occupying tile for take"),
         }
@@ -175,74 +152,40 @@ impl LabMap {
         }
     }
 
-    fn take_obstacle(&mut self, pos: Pos) -> Obstacle {
-        let tile = self.tiles.get_mut(&pos).unwrap();
-         match tile.take() {
+    fn take_obstacle(&mut self, pos: Upos) -> Obstacle {
+         match self.tiles[pos].take() {
             Occupant::Obstacle(obs) => obs,
             _ => panic!("obstacle not occupying tile for take"),
         }
     }
 
-    fn take_custom_obstacle(&mut self, pos: Pos) -> CustomObstacle {
-        let tile = self.tiles.get_mut(&pos).unwrap();
-         match tile.take() {
+    fn take_custom_obstacle(&mut self, pos: Upos) -> CustomObstacle {
+         match self.tiles[pos].take() {
             Occupant::CustomObstacle(obs) => obs,
             _ => panic!("custom obstacle not occupying tile for take"),
         }
     }
 
-    fn in_bounds_at(&self, ipos: IPos) -> bool {
-        let x_pos = ipos.0;
-        let y_pos = ipos.1;
-
-        let (num_cols, num_rows) = to_ipos((self.num_cols, self.num_rows));
-
-        let x_in_bounds = x_pos >= 0 && x_pos < num_cols;
-        let y_in_bounds = y_pos >= 0 && y_pos < num_rows;
-        
-        x_in_bounds && y_in_bounds
+    fn vacant_at(&self, pos: Upos) -> bool {
+        self.tiles[pos].occupant.is_none()
     }
 
-    fn vacant_at(&self, pos: Pos) -> bool {
-        match self.tiles.get(&pos).unwrap().occupant {
-            None => true,
-            _ => false,
-        }
-    }
-
-    fn from_str(input: &String) -> Self {
-        let num_cols = input.lines().count();
-        let num_rows = input.lines().nth(0).unwrap().len();
-
-        let mut map = Self::new(num_rows, num_cols);
-        
-        for (row_idx, row) in input.lines().enumerate() {
-
-            for (col_idx, byte) in row.as_bytes().into_iter().enumerate() {
-                let pos = (col_idx, row_idx);
-                map.place_tile(pos);
+    fn from_str(input: &str) -> Result<Self, ParseError> {
+        let map = TILES.parse(DAY, input)?;
+        let guard_pos = map.marker('^');
 
-                match char::try_from(*byte).unwrap() {
-                    '.' => (),
-                    '#' => {
-                        map.place_obstacle(pos, Obstacle {});
-                    },
-                    '^' => {
-                        map.guard_pos = Some(pos);
-                        map.place_guard(pos, Guard { orientation: Orientation::Up });
-                    },
-                    c => panic!("encountered unknown char: {}", c),
-                };
-            }
-        }
-        map
+        Ok(Self {
+            tiles: map.grid,
+            visits: FxHashSet::from_iter([guard_pos]),
+            guard_pos: Some(guard_pos),
+        })
     }
 
     fn clear_visits(&mut self) {
         self.visits.drain();
     }
 
-    fn sync_state_history(&mut self, history: &Vec<GuardState>) {
+    fn sync_state_history(&mut self, history: &[GuardState]) {
         self.clear_visits();
         self.clear_guard();
         self.visits.reserve(history.len());
@@ -254,25 +197,15 @@ impl LabMap {
     }
 
     fn count_tiles(&self, filter: Box<dyn Fn(&Tile) -> bool>) -> usize {
-        let mut count = 0;
-        for y in 0..self.num_rows {
-            for x in 0..self.num_cols {
-                let tile = self.tiles.get(&(x, y)).unwrap();
-                if filter(tile) {
-                    count += 1;
-                }
-            }
-        }
-        count
+        self.tiles.iter().filter(|(_, tile)| filter(tile)).count()
     }
 }
 
 impl fmt::Display for LabMap {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        for y in 0..self.num_rows {
-            for x in 0..self.num_cols {
+        for (y, row) in self.tiles.rows().enumerate() {
+            for (x, tile) in row.iter().enumerate() {
                 let pos = (x, y);
-                let tile = self.tiles.get(&pos).unwrap();
                 let c = match &tile.occupant {
                     None => match self.visits.contains(&pos) {
                         true => 'X',
@@ -291,24 +224,12 @@ impl fmt::Display for LabMap {
 }
 
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
-}
-
-fn to_ipos(pos: Pos) -> IPos {
-    let x = isize::try_from(pos.0).unwrap();
-    let y = isize::try_from(pos.1).unwrap();
-    (x, y)
-}
-
-fn to_pos(ipos: IPos) -> Pos {
-    let x = usize::try_from(ipos.0).unwrap();
-    let y = usize::try_from(ipos.1).unwrap();
-    (x, y)
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-fn get_forward_step_pos(guard_pos: Pos, guard: &Guard) -> (isize, isize) {
+fn get_forward_step_pos(guard_pos: Upos, guard: &Guard) -> Ipos {
     let ipos = to_ipos(guard_pos);
     let (offset_x, offset_y) = match guard.orientation {
         Orientation::Left => (-1, 0),
@@ -319,31 +240,44 @@ fn get_forward_step_pos(guard_pos: Pos, guard: &Guard) -> (isize, isize) {
     (ipos.0 + offset_x, ipos.1 + offset_y)
 }
 
-pub fn _solution1(input: &String) -> usize {
-    let mut map = LabMap::from_str(&input);
-    'walk_path: loop {
-        let guard_pos = map.guard_pos.clone().unwrap();
-        let mut guard = map.take_guard(guard_pos);
+pub fn solution1_str(input: &str) -> Result<usize, ParseError> {
+    Ok(count_visits(LabMap::from_str(input)?))
+}
 
-        'next_step: loop {
-            let next_ipos = get_forward_step_pos(guard_pos, &guard);
-            if map.in_bounds_at(next_ipos) {
-                let next_pos = to_pos(next_ipos);
-                if map.vacant_at(next_pos) {
-                    map.place_guard(next_pos, guard);
-                    break 'next_step;
-                } else {
-                    guard.turn_right();
-                }
-            } else {
-                // guard walked off the map
-                break 'walk_path;
-            }
+// moves the guard forward a tile, turning first if it's blocked. false once the guard has walked off the map
+fn step_guard(map: &mut LabMap) -> bool {
+    let guard_pos = map.guard_pos.unwrap();
+    let mut guard = map.take_guard(guard_pos);
+    loop {
+        let next_pos = match map.tiles.to_upos(get_forward_step_pos(guard_pos, &guard)) {
+            Some(pos) => pos,
+            None => return false,
         };
-    };
+        if map.vacant_at(next_pos) {
+            map.place_guard(next_pos, guard);
+            return true;
+        }
+        guard.turn_right();
+    }
+}
+
+fn count_visits(mut map: LabMap) -> usize {
+    while step_guard(&mut map) {}
     map.visits.len()
 }
 
+// the map before the guard moves and after every step, the last one with the guard gone
+pub fn walk_frames(mut map: LabMap) -> impl Iterator<Item = String> {
+    let mut walking = true;
+    iter::once(map.to_string()).chain(iter::from_fn(move || {
+        if !walking {
+            return None;
+        }
+        walking = step_guard(&mut map);
+        Some(map.to_string())
+    }))
+}
+
 //pub fn solution1(path: &PathBuf) -> usize {
 //    let input =  fs::read_to_string(path).unwrap();
 //    _solution1(&input)
@@ -365,23 +299,23 @@ pub fn _solution1(input: &String) -> usize {
 //    }))
 //}
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
 struct GuardState {
-    pos: Pos,
+    pos: Upos,
     orientation: Orientation,
 }
 
-fn resume_walk(map: &mut LabMap, history: &Vec<GuardState>) -> Option<VecDeque<GuardState>> {
+fn resume_walk(map: &mut LabMap, history: &[GuardState]) -> Option<VecDeque<GuardState>> {
     //println!("RESUME");
     //println!("history({})", history.len());
     map.sync_state_history(history);
     //println!("{}", map);
     let mut rest_of_walk = VecDeque::new();
-    let mut visits: FxHashSet<(Pos, Orientation)> = FxHashSet::default();
+    let mut visits: FxHashSet<(Upos, Orientation)> = FxHashSet::default();
     //let start_pos = history.last().unwrap().pos;
     //let start_orientation = history.last().unwrap().orientation;
     'walk_path: loop {
@@ -389,9 +323,7 @@ fn resume_walk(map: &mut LabMap, history: &Vec<GuardState>) -> Option<VecDeque<G
         let mut guard = map.take_guard(guard_pos);
 
         'next_step: loop {
-            let next_ipos = get_forward_step_pos(guard_pos, &guard);
-            if map.in_bounds_at(next_ipos) {
-                let next_pos = to_pos(next_ipos);
+            if let Some(next_pos) = map.tiles.to_upos(get_forward_step_pos(guard_pos, &guard)) {
                 if map.vacant_at(next_pos) {
                     rest_of_walk.push_back(GuardState{
                         pos: next_pos,
@@ -420,38 +352,68 @@ fn resume_walk(map: &mut LabMap, history: &Vec<GuardState>) -> Option<VecDeque<G
     Some(rest_of_walk)
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let mut map = LabMap::from_str(&input);
+pub fn solution2_str(input: &str) -> Result<usize, ParseError> {
+    Ok(count_cycles(LabMap::from_str(input)?))
+}
+
+fn count_cycles(map: LabMap) -> usize {
+    loop_obstacles(map).len()
+}
+
+// every tile where one more obstacle would send the guard round in a loop, as (x, y)
+pub fn loop_obstacles(mut map: LabMap) -> Vec<(usize, usize)> {
     let guard_pos = map.guard_pos.clone().unwrap();
     let guard = map.take_guard(guard_pos);
-    let mut history = vec![GuardState { pos: guard_pos, orientation: guard.orientation }];
-    let mut rest_of_walk = resume_walk(&mut map, &history).unwrap();
-    let mut num_cycles = 0;
+    let mut walk = vec![GuardState { pos: guard_pos, orientation: guard.orientation }];
+    walk.extend(resume_walk(&mut map, &walk).unwrap());
+
+    // an obstacle can only go where the guard walks, and only the first time the guard gets there
+    // matters, afterwards it would have changed the walk that led there
     let mut invalid_placements = FxHashSet::default();
-    invalid_placements.reserve(rest_of_walk.len());
-    invalid_placements.insert(history[0].pos);
-    while rest_of_walk.len() != 0 {
-        let obs_pos = rest_of_walk[0].pos;
-        if invalid_placements.contains(&obs_pos) {
-            history.push(rest_of_walk.pop_front().unwrap());
-            continue;
-        }
-        map.place_custom_obstacle(obs_pos, CustomObstacle {});
-        match resume_walk(&mut map, &history) {
-            None => num_cycles += 1,
-            _ => (), // completed, throw away the walk
-        }
-        map.take_custom_obstacle(obs_pos);
-        invalid_placements.insert(obs_pos);
-        history.push(rest_of_walk.pop_front().unwrap());
+    invalid_placements.reserve(walk.len());
+    invalid_placements.insert(walk[0].pos);
+    let candidates: Vec<usize> = (1..walk.len())
+        .filter(|i| invalid_placements.insert(walk[*i].pos))
+        .collect();
+
+    // each thread gets its own copy of the map to place obstacles on
+    candidates.par_iter()
+        .map_init(|| map.clone(), |map, i| {
+            let obs_pos = walk[*i].pos;
+            map.place_custom_obstacle(obs_pos, CustomObstacle {});
+            let cycled = resume_walk(map, &walk[..*i]).is_none();
+            map.take_custom_obstacle(obs_pos);
+            cycled.then_some(obs_pos)
+        })
+        .flatten()
+        .collect()
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = LabMap;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        LabMap::from_str(input)
+    }
+
+    // both parts walk the guard around, so they each get their own map
+    fn part1(map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_visits(map.clone()).into())
+    }
+
+    fn part2(map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_cycles(map.clone()).into())
     }
-    num_cycles
 }
 
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn test_example_day1() {
@@ -461,14 +423,46 @@ mod tests  {
         //println!("{}", map);
         //map.move_guard();
         let path = common::get_test_data_path("day6/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 41, "counts guard path correctly")
     }
 
     #[test]
     fn test_example_day2() {
         let path = common::get_test_data_path("day6/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 6, "counts guard path correctly")
     }
+
+    #[test]
+    fn test_walk_frames() {
+        let input = fs::read_to_string(common::get_test_data_path("day6/case1.txt").unwrap()).unwrap();
+        let frames: Vec<String> = walk_frames(LabMap::from_str(&input).unwrap()).collect();
+        assert_eq!(frames[0], input);
+        assert_eq!(frames[1].lines().nth(5), Some("....^....."));
+        assert_eq!(frames[1].lines().nth(6), Some(".#..X....."));
+        let last = frames.last().unwrap();
+        assert!(!last.contains('^') && !last.contains('v'));
+        assert_eq!(last.matches('X').count(), 41);
+    }
+
+    #[test]
+    fn test_unknown_tile() {
+        let err = solution1_str(&String::from("..#\n.^x\n...")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('x'));
+        assert_eq!((err.line, err.column), (2, 3));
+    }
+
+    #[test]
+    fn test_missing_guard() {
+        let err = solution1_str(&String::from("..#\n...")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::MissingMarker('^'));
+    }
+
+    #[test]
+    fn test_two_guards() {
+        let err = solution1_str(&String::from("..#\n.^.\n^..")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::DuplicateMarker('^'));
+        assert_eq!((err.line, err.column), (3, 1));
+    }
 }
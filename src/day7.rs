@@ -1,6 +1,15 @@
-use std::fs;
-use std::path::PathBuf;
+use std::error::Error;
+use std::io::BufRead;
+use std::path::Path;
 use std::collections::VecDeque;
+use std::fmt;
+use log::{debug, trace};
+use rayon::prelude::*;
+
+use crate::config::NoConfig;
+use crate::error::{expect_token, parse_num, ParseError};
+use crate::solution::{Answer, Solution};
+use crate::stream::{self, StreamError};
 
 #[derive(Clone)]
 pub enum Operator {
@@ -9,34 +18,61 @@ pub enum Operator {
     Concat,
 }
 
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operator::Plus => write!(f, "+"),
+            Operator::Mult => write!(f, "*"),
+            Operator::Concat => write!(f, "||"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct EqData {
     solution: u128,
     operands: Vec<u128>,
 }
 
+impl EqData {
+    pub fn solution(&self) -> u128 {
+        self.solution
+    }
+}
+
 pub mod constants {
     pub const INPUT_PATH: &str = "day7/input.txt";
 }
 
-pub fn solution1(path: &PathBuf) -> u128 {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+const DAY: usize = 7;
+
+// equations solved at a time when streaming, enough to keep every thread busy
+const BATCH_SIZE: usize = 4096;
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<u128, Box<dyn Error>> {
+    let ops = vec![Operator::Plus, Operator::Mult];
+    Ok(streamed_calibration_total(stream::open(path)?, &ops)?)
 }
 
-fn parse_input(input: &String) -> Vec<EqData> {
-    let mut eq_data = Vec::new();
-    for line in input.lines() {
-        let line_parts: Vec<&str> = line.split(": ").collect();
-        assert_eq!(line_parts.len(), 2, "expecting result and operands");
-        let solution = line_parts[0].parse::<u128>().unwrap();
-        let operands: Vec<u128> = line_parts[1].split(" ").map(|s| s.parse::<u128>().unwrap()).collect();
-        eq_data.push(EqData{
-            solution,
-            operands,
-        });
+fn parse_equation(line_idx: usize, line: &str) -> Result<EqData, ParseError> {
+    let mut line_parts = line.splitn(2, ": ");
+    let solution = expect_token(DAY, line_idx, line, line_parts.next(), "result")?;
+    let operands = expect_token(DAY, line_idx, line, line_parts.next(), "operands")?;
+    let solution = parse_num(DAY, line_idx, line, solution)?;
+    let mut parsed_operands = Vec::new();
+    for operand in operands.split(' ') {
+        parsed_operands.push(parse_num(DAY, line_idx, line, operand)?);
     }
-    eq_data
+    Ok(EqData{
+        solution,
+        operands: parsed_operands,
+    })
+}
+
+fn parse_input(input: &str) -> Result<Vec<EqData>, ParseError> {
+    input.lines().enumerate()
+        .map(|(line_idx, line)| parse_equation(line_idx, line))
+        .collect()
 }
 
 fn _find_op_sequence(eq_data: &EqData, valid_ops: &Vec<Operator>, acc: u128, remaining: &mut VecDeque<u128>, ops: &mut Vec<Operator>) -> Option<Vec<Operator>> {
@@ -61,14 +97,14 @@ fn _find_op_sequence(eq_data: &EqData, valid_ops: &Vec<Operator>, acc: u128, rem
                 // assume that any operators that overflow usize
                 // will result in no solution
                 if is_overflow {
-                    println!("overflowing add");
+                    trace!("overflowing add");
                 }
             },
             Operator::Mult => {
                 (next_acc, is_overflow) = acc.overflowing_mul(next_operand);
 
                 if is_overflow {
-                    println!("overflowing mult");
+                    trace!("overflowing mult");
                 }
             },
             Operator::Concat => {
@@ -123,63 +159,67 @@ pub fn find_op_sequence(eq_data: &EqData, valid_ops: &Vec<Operator>) -> Option<V
 //    false
 //}
 
-pub fn _solution1(input: &String) -> u128 {
-    let test_eqs = parse_input(&input);
-    println!("found {} equations", test_eqs.len());
+// the equation with its operators filled in, e.g. 190 = 10 * 19
+pub fn show_solved(eq_data: &EqData, op_seq: &[Operator]) -> String {
+    let mut shown = format!("{} = {}", eq_data.solution, eq_data.operands[0]);
+    for (op, operand) in op_seq.iter().zip(&eq_data.operands[1..]) {
+        shown.push_str(&format!(" {} {}", op, operand));
+    }
+    shown
+}
+
+pub fn calibration_total(test_eqs: &[EqData], ops: &Vec<Operator>) -> u128 {
+    test_eqs.par_iter()
+        .filter(|test_eq| find_op_sequence(test_eq, ops).is_some())
+        .map(|test_eq| test_eq.solution)
+        .sum()
+}
+
+pub fn solution1_str(input: &str) -> Result<u128, ParseError> {
+    let test_eqs = parse_input(input)?;
+    debug!("found {} equations", test_eqs.len());
     let ops = vec![Operator::Plus, Operator::Mult];
-    let mut result = 0u128;
-    for test_eq in test_eqs.iter() {
-        //if rand_find_match(&test_eq, &ops) {
-        //    let result_part = BigUint::from(test_eq.solution);
-        //    result += result_part;
-        //    match find_op_sequence(&test_eq, &ops) {
-        //        None => {
-        //            println!("BAD CASE: {}", test_eq.solution);
-        //        }
-        //        _ => (),
-        //    }
-        //}
-        match find_op_sequence(&test_eq, &ops) {
-            Some(_op_seq) => {
-                //check_solution(&test_eq, &_op_seq);
-                result += test_eq.solution;
-            },
-            _ => (),
-        }
+    Ok(calibration_total(&test_eqs, &ops))
+}
+
+// the same total, solving the equations a batch at a time as they're read
+pub fn streamed_calibration_total<R: BufRead>(reader: R, ops: &Vec<Operator>) -> Result<u128, StreamError> {
+    let mut total = 0;
+    for test_eqs in stream::batches(stream::records(reader, parse_equation), BATCH_SIZE) {
+        total += calibration_total(&test_eqs?, ops);
     }
-    result
+    Ok(total)
 }
 
-pub fn solution2(path: &PathBuf) -> u128 {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<u128, Box<dyn Error>> {
+    let ops = vec![Operator::Plus, Operator::Mult, Operator::Concat];
+    Ok(streamed_calibration_total(stream::open(path)?, &ops)?)
 }
 
-pub fn _solution2(input: &String) -> u128 {
-    let test_eqs = parse_input(&input);
-    println!("found {} equations", test_eqs.len());
+pub fn solution2_str(input: &str) -> Result<u128, ParseError> {
+    let test_eqs = parse_input(input)?;
+    debug!("found {} equations", test_eqs.len());
     let ops = vec![Operator::Plus, Operator::Mult, Operator::Concat];
-    let mut result = 0u128;
-    for test_eq in test_eqs.iter() {
-        //if rand_find_match(&test_eq, &ops) {
-        //    let result_part = BigUint::from(test_eq.solution);
-        //    result += result_part;
-        //    match find_op_sequence(&test_eq, &ops) {
-        //        None => {
-        //            println!("BAD CASE: {}", test_eq.solution);
-        //        }
-        //        _ => (),
-        //    }
-        //}
-        match find_op_sequence(&test_eq, &ops) {
-            Some(_op_seq) => {
-                //check_solution(&test_eq, &_op_seq);
-                result += test_eq.solution;
-            },
-            _ => (),
-        }
+    Ok(calibration_total(&test_eqs, &ops))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<EqData>;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
+
+    fn part1(test_eqs: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(calibration_total(test_eqs, &vec![Operator::Plus, Operator::Mult]).into())
+    }
+
+    fn part2(test_eqs: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(calibration_total(test_eqs, &vec![Operator::Plus, Operator::Mult, Operator::Concat]).into())
     }
-    result
 }
 
 #[cfg(test)]
@@ -190,7 +230,7 @@ mod tests  {
     #[test]
     fn test_parse_input() {
         let input = String::from("5: 1 2 3\n4: 5 6 7");
-        let parsed = parse_input(&input);
+        let parsed = parse_input(&input).unwrap();
         assert_eq!(
             parsed,
             vec![
@@ -209,14 +249,20 @@ mod tests  {
     #[test]
     fn test_solution1() {
         let path = common::get_test_data_path("day7/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 3749);
     }
     
     #[test]
     fn test_solution2() {
         let path = common::get_test_data_path("day7/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 11387);
     }
+
+    #[test]
+    fn test_missing_operands() {
+        let err = parse_input(&String::from("5: 1 2 3\n4")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
 }
@@ -1,60 +1,46 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::Path;
 use std::collections::{HashMap, HashSet};
 
+use crate::common::grid::{to_ipos, Grid, Ipos};
+use crate::config::NoConfig;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
+
 pub mod constants {
     pub const INPUT_PATH: &str = "day8/input.txt";
 }
 
-type Ipos = (isize, isize);
-type Upos = (usize, usize);
+const DAY: usize = 8;
+
 type Antenna = char;
 
-struct CityMap {
-    coords: HashMap<Ipos, Option<Antenna>>,
+pub struct CityMap {
+    coords: Grid<Option<Antenna>>,
     antenna_coords: HashMap<Antenna, Vec<Ipos>>,
-    num_rows: isize,
-    num_cols: isize,
-}
-
-fn to_ipos(upos: Upos) -> Ipos {
-    (isize::try_from(upos.0).unwrap(), isize::try_from(upos.1).unwrap())
-}
-
-fn to_isize(i: usize) -> isize {
-    isize::try_from(i).unwrap()
 }
 
 impl CityMap {
 
-    pub fn from(input: &String) -> Self {
-        let mut coords: HashMap<Ipos, Option<Antenna>> = HashMap::new();
-        let mut antenna_coords: HashMap<Antenna, Vec<Ipos>> = HashMap::new();
+    pub fn from(input: &str) -> Result<Self, ParseError> {
+        let coords = Grid::parse(DAY, input, |c| match c {
+            '.' => Some(None),
+            c if c.is_ascii_alphanumeric() => Some(Some(c)),
+            _ => None,
+        })?;
 
-        let num_rows = to_isize(input.lines().count());
-        let num_cols = to_isize(input.lines().next().unwrap().bytes().len());
-
-        for (y, line) in input.lines().enumerate() {
-            for (x, c) in line.bytes().enumerate() {
-                let ipos = to_ipos((x, y));
-                let c = char::try_from(c).unwrap();
-                let to_insert = match c {
-                    '.' => None,
-                    _ => {
-                        let c_coords = antenna_coords.entry(c).or_insert(Vec::new());
-                        c_coords.push(ipos);
-                        Some(c)
-                    },
-                };
-                coords.entry(ipos).or_insert(to_insert);
+        let mut antenna_coords: HashMap<Antenna, Vec<Ipos>> = HashMap::new();
+        for (upos, antenna) in coords.iter() {
+            if let Some(antenna) = antenna {
+                antenna_coords.entry(*antenna).or_default().push(to_ipos(upos));
             }
         }
-        CityMap {
+
+        Ok(CityMap {
             coords,
             antenna_coords,
-            num_rows,
-            num_cols,
-        }
+        })
     }
 
     pub fn antenna_iter(&self) -> impl Iterator<Item=&Antenna> {
@@ -140,7 +126,7 @@ impl CityMap {
     }
 
     pub fn contains(&self, pos: Ipos) -> bool {
-        self.coords.contains_key(&pos)
+        self.coords.contains(pos)
     }
 }
 
@@ -150,20 +136,23 @@ impl CityMap {
     // the antennas form a line
 
 // parse the input
-    // internally, hashmap<Pos, Option<char>>
+    // internally, grid of Option<char>
 // create a hashmap<char, Vec<Pos>>
 // for each char
     // generate unique pairs
     // find the antinode locations for each pair
     // record them in a hashset<Pos> (solution wants unique locations)
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
-pub fn _solution1(input: &String) -> usize {
-    let city_map = CityMap::from(input);
+pub fn solution1_str(input: &str) -> Result<usize, ParseError> {
+    Ok(count_antinodes_1(&CityMap::from(input)?))
+}
+
+fn count_antinodes_1(city_map: &CityMap) -> usize {
     let mut antinode_locations: HashSet<Ipos> = HashSet::new();
 
     for antenna in city_map.antenna_iter() {
@@ -186,13 +175,16 @@ pub fn _solution1(input: &String) -> usize {
     antinode_locations.len()
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
+}
+
+pub fn solution2_str(input: &str) -> Result<usize, ParseError> {
+    Ok(count_antinodes_2(&CityMap::from(input)?))
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let city_map = CityMap::from(input);
+fn count_antinodes_2(city_map: &CityMap) -> usize {
     let mut antinode_locations: HashSet<Ipos> = HashSet::new();
 
     for antenna in city_map.antenna_iter() {
@@ -214,22 +206,49 @@ pub fn _solution2(input: &String) -> usize {
     antinode_locations.len()
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = CityMap;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        CityMap::from(input)
+    }
+
+    fn part1(city_map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_antinodes_1(city_map).into())
+    }
+
+    fn part2(city_map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        Ok(count_antinodes_2(city_map).into())
+    }
+}
+
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::error::ParseErrorKind;
 
     #[test]
     fn test_example_day8_1() {
         let path = common::get_test_data_path("day8/case1.txt").unwrap();
-        let result = solution1(&path);
+        let result = solution1(&path).unwrap();
         assert_eq!(result, 14);
     }
 
     #[test]
     fn test_example_day8_2() {
         let path = common::get_test_data_path("day8/case1.txt").unwrap();
-        let result = solution2(&path);
+        let result = solution2(&path).unwrap();
         assert_eq!(result, 34);
     }
+
+    #[test]
+    fn test_bad_antenna() {
+        let err = solution1_str(&String::from("..a.\n.#..\n")).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('#'));
+        assert_eq!((err.line, err.column), (2, 2));
+    }
 }
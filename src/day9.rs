@@ -1,18 +1,26 @@
+use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::iter;
+use std::path::Path;
 use std::rc::Rc;
 use std::fmt;
 use std::collections::HashMap;
 
+use crate::config::NoConfig;
+use crate::error::{ParseError, ParseErrorKind};
+use crate::solution::{Answer, Solution};
+
 pub mod constants {
     pub const INPUT_PATH: &str = "day9/input.txt";
 }
 
+const DAY: usize = 9;
+
 type FileId = usize;
 type BlockId = usize;
 type BlockIndex = usize;
 
-struct DiskMap {
+pub struct DiskMap {
     entries: Vec<DiskMapEntry>,
 }
 
@@ -24,11 +32,22 @@ enum DiskMapEntry {
 }
 
 impl DiskMap {
-    fn from(input: &String) -> Self {
+    fn from(input: &str) -> Result<Self, ParseError> {
         let mut entries = Vec::new();
         let mut is_file = true;
-        for c in input.chars().take(input.len() - 1) {
-            let num = usize::try_from(c.to_digit(10).unwrap()).unwrap();
+        let line = input.lines().next().unwrap_or("");
+        if line.is_empty() {
+            return Err(ParseError::new(DAY, ParseErrorKind::Empty).at(0, 0));
+        }
+        for (col_idx, c) in line.chars().enumerate() {
+            let num = match c.to_digit(10) {
+                Some(d) => usize::try_from(d).unwrap(),
+                None => {
+                    return Err(ParseError::new(DAY, ParseErrorKind::UnexpectedChar(c))
+                        .at(0, col_idx)
+                        .with_text(line));
+                },
+            };
             if is_file {
                 entries.push(DiskMapEntry::File(num));
             } else {
@@ -36,9 +55,9 @@ impl DiskMap {
             }
             is_file = !is_file;
         }
-        Self {
+        Ok(Self {
             entries
-        }
+        })
     }
 }
 
@@ -100,10 +119,8 @@ impl Disk {
         disk
     }
 
+    // None past the end too, a disk of empty files has no blocks at all
     pub fn find_first_free(&self, starting_block: usize) -> Option<usize> {
-        if starting_block >= self.blocks.len() {
-            panic!("attempting to access out of bounds block");
-        }
         for i in starting_block..self.blocks.len() {
             match *self.blocks[i] {
                 Block::Free => return Some(i),
@@ -135,7 +152,7 @@ impl Disk {
     }
 
     pub fn find_last_data(&self, end_block: usize) -> Option<usize> {
-        if end_block > self.blocks.len() || end_block == 0 {
+        if end_block > self.blocks.len() {
             panic!("attempting to access out of bounds block");
         }
 
@@ -175,38 +192,38 @@ impl Disk {
         self.file_ptrs.entry(file_id).and_modify(|ptr| *ptr = new_idx);
     }
 
+    // the first free block from free_from and the last file block before data_to, while the
+    // free one comes first and swapping them still compresses the disk
+    fn next_swap(&self, free_from: usize, data_to: usize) -> Option<(usize, usize)> {
+        match (self.find_first_free(free_from), self.find_last_data(data_to)) {
+            (Some(free_ptr), Some(data_ptr)) if free_ptr < data_ptr => Some((free_ptr, data_ptr)),
+            _ => None,
+        }
+    }
+
     pub fn compress(&mut self) {
-        match (self.find_first_free(0), self.find_last_data(self.blocks.len())) {
-            (Some(mut free_ptr), Some(mut data_ptr)) => {
-                while free_ptr < data_ptr {
-                    self.swap_blocks(free_ptr, data_ptr);
-                    match (self.find_first_free(free_ptr + 1), self.find_last_data(data_ptr)) {
-                        (Some(next_free), Some(next_data)) => {
-                            free_ptr = next_free;
-                            data_ptr = next_data;
-                        },
-                        _ => return,
-                    }
-                }
-            },
-            _ => (),
+        let mut swap = self.next_swap(0, self.blocks.len());
+        while let Some((free_ptr, data_ptr)) = swap {
+            self.swap_blocks(free_ptr, data_ptr);
+            swap = self.next_swap(free_ptr + 1, data_ptr);
+        }
+    }
+
+    // moves the i-th file into the first gap before it that fits, if there is one
+    fn mv_file_left(&mut self, i: usize) {
+        let free_at = self.find_first_contiguous_free(
+            self.files[i].blocks.len(),
+            *self.file_ptrs.get(&self.files[i].id).unwrap(),
+        );
+        if let Some(free_at) = free_at {
+            let file_id = self.files[i].id;
+            self.mv_file(file_id, free_at);
         }
     }
 
     pub fn compress_no_frag(&mut self) {
         for i in (0..self.files.len()).rev() {
-            let free_at = self.find_first_contiguous_free(
-                self.files[i].blocks.len(),
-                *self.file_ptrs.get(&self.files[i].id).unwrap(),
-            );
-            match free_at {
-                Some(free_at) => {
-                    let file_id = self.files[i].id;
-                    self.mv_file(file_id, free_at);
-                },
-                None => (),
-            }
-
+            self.mv_file_left(i);
         }
     }
 }
@@ -275,39 +292,128 @@ need to be able to do a checksum of the disk
   * need to know where the next free space is (and how much?)
 */
 
-pub fn solution1(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution1(&input)
+// the disk before compressing, then after each block moved
+pub fn compress_frames(disk_map: &DiskMap) -> impl Iterator<Item = String> {
+    let mut disk = Disk::from(disk_map);
+    let mut swap = disk.next_swap(0, disk.blocks.len());
+    iter::once(disk.to_string()).chain(iter::from_fn(move || {
+        let (free_ptr, data_ptr) = swap?;
+        disk.swap_blocks(free_ptr, data_ptr);
+        swap = disk.next_swap(free_ptr + 1, data_ptr);
+        Some(disk.to_string())
+    }))
+}
+
+// the disk before compressing, then after each file has had its chance to move, highest id first
+pub fn compress_no_frag_frames(disk_map: &DiskMap) -> impl Iterator<Item = String> {
+    let mut disk = Disk::from(disk_map);
+    let first = disk.to_string();
+    let mut files = (0..disk.files.len()).rev();
+    iter::once(first).chain(iter::from_fn(move || {
+        disk.mv_file_left(files.next()?);
+        Some(disk.to_string())
+    }))
+}
+
+pub fn solution1(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution1_str(&input)?)
 }
 
 
-pub fn _solution1(input: &String) -> usize {
-    let disk_map = DiskMap::from(&input);
+pub fn solution1_str(input: &str) -> Result<usize, ParseError> {
+    let disk_map = DiskMap::from(input)?;
     let mut disk = Disk::from(&disk_map);
     disk.compress();
-    disk.checksum()
+    Ok(disk.checksum())
 }
 
-pub fn solution2(path: &PathBuf) -> usize {
-    let input =  fs::read_to_string(path).unwrap();
-    _solution2(&input)
+pub fn solution2(path: impl AsRef<Path>) -> Result<usize, Box<dyn Error>> {
+    let input =  fs::read_to_string(path)?;
+    Ok(solution2_str(&input)?)
 }
 
-pub fn _solution2(input: &String) -> usize {
-    let disk_map = DiskMap::from(&input);
+pub fn solution2_str(input: &str) -> Result<usize, ParseError> {
+    let disk_map = DiskMap::from(input)?;
     let mut disk = Disk::from(&disk_map);
     disk.compress_no_frag();
-    disk.checksum()
+    Ok(disk.checksum())
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = DiskMap;
+    type Config = NoConfig;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        DiskMap::from(input)
+    }
+
+    fn part1(disk_map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        let mut disk = Disk::from(disk_map);
+        disk.compress();
+        Ok(disk.checksum().into())
+    }
+
+    fn part2(disk_map: &Self::Input, _config: &Self::Config) -> Result<Answer, Box<dyn Error>> {
+        let mut disk = Disk::from(disk_map);
+        disk.compress_no_frag();
+        Ok(disk.checksum().into())
+    }
 }
 
 #[cfg(test)]
 mod tests  {
     use super::*;
     use crate::common;
+    use crate::common::prop::{self, in_range};
+    use quickcheck::{Arbitrary, Gen};
+
+    // a disk map line, short enough to simulate block by block
+    #[derive(Debug, Clone)]
+    struct SmallDiskMap(String);
+
+    impl Arbitrary for SmallDiskMap {
+        fn arbitrary(g: &mut Gen) -> Self {
+            let len = in_range(g, 1..=20);
+            Self((0..len).map(|_| char::from(b'0' + u8::try_from(in_range(g, 0..=9)).unwrap())).collect())
+        }
+    }
+
+    // moves whole files into the leftmost gap before them that fits, highest id first,
+    // on a plain list of blocks
+    fn simulate_no_frag(disk_map: &str) -> usize {
+        let mut blocks: Vec<Option<usize>> = vec![];
+        for (i, c) in disk_map.chars().enumerate() {
+            let len = usize::try_from(c.to_digit(10).unwrap()).unwrap();
+            let block = if i % 2 == 0 { Some(i / 2) } else { None };
+            blocks.extend(std::iter::repeat_n(block, len));
+        }
+
+        let num_files = disk_map.len().div_ceil(2);
+        for id in (0..num_files).rev() {
+            let Some(start) = blocks.iter().position(|b| *b == Some(id)) else {
+                continue;
+            };
+            let len = blocks.iter().filter(|b| **b == Some(id)).count();
+            // a gap that starts before the file and is all free has to end before it too
+            let gap = (0..start).find(|g| blocks[*g..*g + len].iter().all(|b| b.is_none()));
+            if let Some(gap) = gap {
+                for offset in 0..len {
+                    blocks.swap(gap + offset, start + offset);
+                }
+            }
+        }
+
+        blocks.iter().enumerate()
+            .map(|(idx, b)| b.map_or(0, |id| idx * id))
+            .sum()
+    }
 
     #[test]
     fn example_day9_disk_map() {
-        let disk_map = DiskMap::from(&String::from("12345\n"));
+        let disk_map = DiskMap::from(&String::from("12345\n")).unwrap();
         assert_eq!(
             disk_map.entries,
             vec![
@@ -322,7 +428,7 @@ mod tests  {
 
     #[test]
     fn example_day9_disk() {
-        let disk_map = DiskMap::from(&String::from("12345\n"));
+        let disk_map = DiskMap::from(&String::from("12345\n")).unwrap();
         let disk = Disk::from(&disk_map);
         assert_eq!(
             disk,
@@ -383,21 +489,62 @@ mod tests  {
     fn example_day9_1_compress() {
         let path = common::get_test_data_path("day9/case1.txt").unwrap();
         let input = fs::read_to_string(&path).unwrap();
-        let disk_map = DiskMap::from(&input);
+        let disk_map = DiskMap::from(&input).unwrap();
         let mut disk = Disk::from(&disk_map);
         disk.compress();
-        assert_eq!(solution1(&path), 1928);
+        assert_eq!(solution1(&path).unwrap(), 1928);
     }
 
     #[test]
     fn example_day9_1() {
         let path = common::get_test_data_path("day9/case1.txt").unwrap();
-        assert_eq!(solution1(&path), 1928);
+        assert_eq!(solution1(&path).unwrap(), 1928);
     }
 
     #[test]
     fn example_day9_2() {
         let path = common::get_test_data_path("day9/case1.txt").unwrap();
-        assert_eq!(solution2(&path), 99999);
+        assert_eq!(solution2(&path).unwrap(), 2858);
+    }
+
+    #[test]
+    fn example_day9_frames() {
+        let frames: Vec<String> = compress_frames(&DiskMap::from("12345\n").unwrap()).collect();
+        assert_eq!(frames, vec![
+            "0..111....22222",
+            "02.111....2222.",
+            "022111....222..",
+            "0221112...22...",
+            "02211122..2....",
+            "022111222......",
+        ]);
+
+        let input = fs::read_to_string(common::get_test_data_path("day9/case1.txt").unwrap()).unwrap();
+        let frames: Vec<String> = compress_no_frag_frames(&DiskMap::from(&input).unwrap()).collect();
+        assert_eq!(frames.len(), 11);
+        assert_eq!(frames[0], "00...111...2...333.44.5555.6666.777.888899");
+        assert_eq!(frames[1], "0099.111...2...333.44.5555.6666.777.8888..");
+        assert_eq!(frames[10], "00992111777.44.333....5555.6666.....8888..");
+    }
+
+    #[test]
+    fn compress_no_frag_matches_simulation() {
+        prop::check(|disk_map: &SmallDiskMap| {
+            let mut disk = Disk::from(&DiskMap::from(&disk_map.0).unwrap());
+            disk.compress_no_frag();
+            disk.checksum() == simulate_no_frag(&disk_map.0)
+        });
+    }
+
+    #[test]
+    fn example_day9_bad_digit() {
+        let err = DiskMap::from(&String::from("12a45\n")).err().unwrap();
+        assert_eq!(err.kind, ParseErrorKind::UnexpectedChar('a'));
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(DiskMap::from("").err().unwrap().kind, ParseErrorKind::Empty);
+        // only empty files, so no blocks
+        let mut disk = Disk::from(&DiskMap::from("00").unwrap());
+        disk.compress();
+        assert_eq!(disk.checksum(), 0);
     }
 }
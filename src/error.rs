@@ -0,0 +1,168 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Empty,
+    UnexpectedChar(char),
+    InvalidNumber,
+    MissingToken(&'static str),
+    UnexpectedToken(&'static str),
+    RaggedRow { expected: usize, found: usize },
+    MissingMarker(char),
+    DuplicateMarker(char),
+    MissingColumn(String),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseErrorKind::Empty => write!(f, "input is empty"),
+            ParseErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::MissingToken(what) => write!(f, "missing {}", what),
+            ParseErrorKind::UnexpectedToken(expected) => write!(f, "expected {}", expected),
+            ParseErrorKind::RaggedRow { expected, found } => {
+                write!(f, "row is {} wide, expected {}", found, expected)
+            },
+            ParseErrorKind::MissingMarker(c) => write!(f, "no {:?} found in input", c),
+            ParseErrorKind::DuplicateMarker(c) => write!(f, "more than one {:?} in input", c),
+            ParseErrorKind::MissingColumn(name) => write!(f, "no column named {:?} in the header", name),
+        }
+    }
+}
+
+// line and column are 1-based, 0 means the error isn't tied to a position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: usize,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    pub kind: ParseErrorKind,
+}
+
+impl ParseError {
+    pub fn new(day: usize, kind: ParseErrorKind) -> Self {
+        Self {
+            day,
+            line: 0,
+            column: 0,
+            text: String::new(),
+            kind,
+        }
+    }
+
+    // takes 0-based indices, as you'd get from enumerate()
+    pub fn at(mut self, line_idx: usize, col_idx: usize) -> Self {
+        self.line = line_idx + 1;
+        self.column = col_idx + 1;
+        self
+    }
+
+    // for errors raised by a single-line parser that doesn't know where it is
+    pub fn on_line(mut self, line_idx: usize) -> Self {
+        self.line = line_idx + 1;
+        self
+    }
+
+    // for errors from a parser that was only handed the section of the input starting at `line_idx`
+    pub fn offset_lines(mut self, line_idx: usize) -> Self {
+        if self.line > 0 {
+            self.line += line_idx;
+        }
+        self
+    }
+
+    pub fn with_text(mut self, text: &str) -> Self {
+        self.text = text.to_string();
+        self
+    }
+}
+
+impl Error for ParseError {}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "day {}", self.day)?;
+        if self.line > 0 {
+            write!(f, ", line {}", self.line)?;
+        }
+        if self.column > 0 {
+            write!(f, ", column {}", self.column)?;
+        }
+        write!(f, ": {}", self.kind)?;
+        if !self.text.is_empty() {
+            write!(f, " in {:?}", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+// byte offset of `part` within `line`, when `part` was sliced out of it
+pub fn column_of(line: &str, part: &str) -> usize {
+    let base = line.as_ptr() as usize;
+    let start = part.as_ptr() as usize;
+    if start >= base && start + part.len() <= base + line.len() {
+        start - base
+    } else {
+        0
+    }
+}
+
+pub fn parse_num<T: FromStr>(day: usize, line_idx: usize, line: &str, token: &str) -> Result<T, ParseError> {
+    token.trim().parse::<T>().map_err(|_| {
+        ParseError::new(day, ParseErrorKind::InvalidNumber)
+            .at(line_idx, column_of(line, token))
+            .with_text(token)
+    })
+}
+
+pub fn expect_token<'a>(
+    day: usize,
+    line_idx: usize,
+    line: &str,
+    token: Option<&'a str>,
+    what: &'static str,
+) -> Result<&'a str, ParseError> {
+    token.ok_or_else(|| {
+        ParseError::new(day, ParseErrorKind::MissingToken(what))
+            .at(line_idx, line.len())
+            .with_text(line)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_at_bad_number() {
+        let line = "3   x4";
+        let token = line.split_whitespace().nth(1).unwrap();
+        let err = parse_num::<u64>(1, 2, line, token).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 5));
+        assert_eq!(err.kind, ParseErrorKind::InvalidNumber);
+        assert_eq!(err.to_string(), "day 1, line 3, column 5: invalid number in \"x4\"");
+    }
+
+    #[test]
+    fn positionless_errors() {
+        let err = ParseError::new(6, ParseErrorKind::MissingMarker('^'));
+        assert_eq!(err.to_string(), "day 6: no '^' found in input");
+    }
+
+    #[test]
+    fn line_only_errors() {
+        let err = ParseError::new(13, ParseErrorKind::MissingToken("prize line")).on_line(4);
+        assert_eq!(err.to_string(), "day 13, line 5: missing prize line");
+    }
+
+    #[test]
+    fn missing_token_points_past_end() {
+        let err = expect_token(7, 0, "190:", None, "operands").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 5));
+        assert_eq!(err.kind, ParseErrorKind::MissingToken("operands"));
+    }
+}
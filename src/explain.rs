@@ -0,0 +1,196 @@
+use std::error::Error;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::config::Overrides;
+use crate::solution::{Answer, Solution};
+use crate::{day2, day3, day5, day6, day7};
+
+// for days where an answer is a count or a sum of things, the things themselves, so a wrong
+// answer can be traced back to the ones that shouldn't (or should) be in it. the answer comes
+// from the same things, so the two always agree
+
+// a line per thing that went into the answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Explanation {
+    // what the lines are of
+    pub of: &'static str,
+    pub lines: Vec<String>,
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} ({}):", self.of, self.lines.len())?;
+        for line in self.lines.iter() {
+            writeln!(f, "  {}", line)?;
+        }
+        Ok(())
+    }
+}
+
+type ExplainFn = fn(&str, &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>>;
+
+pub struct Explainer {
+    pub day: usize,
+    pub part: usize,
+    // what the explanation lists, for the usage text
+    pub shows: &'static str,
+    explain: ExplainFn,
+}
+
+impl Explainer {
+    pub fn explain(&self, input: &str, overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+        (self.explain)(input, overrides)
+    }
+}
+
+pub fn all() -> Vec<Explainer> {
+    vec![
+        Explainer { day: 2, part: 1, shows: "the safe reports", explain: day2_part1 },
+        Explainer { day: 2, part: 2, shows: "the safe reports, and the levels removed from them", explain: day2_part2 },
+        Explainer { day: 3, part: 1, shows: "each instruction run, and what it added", explain: day3_part1 },
+        Explainer { day: 3, part: 2, shows: "each instruction run, and what it added", explain: day3_part2 },
+        Explainer { day: 5, part: 1, shows: "the updates already in order", explain: day5_part1 },
+        Explainer { day: 5, part: 2, shows: "the updates out of order, and their fixed order", explain: day5_part2 },
+        Explainer { day: 6, part: 2, shows: "where an obstacle would trap the guard in a loop", explain: day6_part2 },
+        Explainer { day: 7, part: 1, shows: "the operators that make each equation true", explain: day7_part1 },
+        Explainer { day: 7, part: 2, shows: "the operators that make each equation true", explain: day7_part2 },
+    ]
+}
+
+pub fn find(day: usize, part: usize) -> Option<Explainer> {
+    all().into_iter().find(|e| e.day == day && e.part == part)
+}
+
+// reports are numbered by their line in the input, levels by their index in the report
+fn safe_reports(input: &str, config: &day2::Config, max_removals: usize) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let lines: Vec<String> = day2::Day2::parse(input)?.iter().enumerate()
+        .filter_map(|(i, report)| {
+            let removed = report.removals_to_safe(config);
+            match removed.len() {
+                0 => Some(format!("line {}: {}", i + 1, report)),
+                n if n <= max_removals => Some(format!("line {}: {} (removed index {:?})", i + 1, report, removed)),
+                _ => None,
+            }
+        })
+        .collect();
+    Ok((lines.len().into(), Explanation { of: "safe reports", lines }))
+}
+
+fn day2_part1(input: &str, overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    safe_reports(input, &overrides.for_day::<day2::Config>(2)?, 0)
+}
+
+fn day2_part2(input: &str, overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let config = overrides.for_day::<day2::Config>(2)?;
+    safe_reports(input, &config, config.max_removals)
+}
+
+// offsets are in bytes from the start of the memory
+fn instruction_trace(input: &str, interpreter: day3::Interpreter) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let run = interpreter.run(input)?;
+    let lines = run.trace.iter().map(|step| step.to_string()).collect();
+    Ok((run.result.into(), Explanation { of: "instructions", lines }))
+}
+
+fn day3_part1(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    instruction_trace(input, day3::Interpreter::multiply())
+}
+
+fn day3_part2(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    instruction_trace(input, day3::Interpreter::conditional())
+}
+
+fn day5_part1(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let instructions = day5::Day5::parse(input)?;
+    let updates = day5::correct_updates(&instructions);
+    let answer: usize = updates.iter().map(|update| update.middle_page()).sum();
+    let lines = updates.iter()
+        .map(|update| format!("{} middle {}", update, update.middle_page()))
+        .collect();
+    Ok((answer.into(), Explanation { of: "updates in order", lines }))
+}
+
+fn day5_part2(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let instructions = day5::Day5::parse(input)?;
+    let updates = day5::corrected_updates(&instructions);
+    let answer: usize = updates.iter().map(|(_, corrected)| corrected.middle_page()).sum();
+    let lines = updates.iter()
+        .map(|(update, corrected)| format!("{} -> {} middle {}", update, corrected, corrected.middle_page()))
+        .collect();
+    Ok((answer.into(), Explanation { of: "corrected updates", lines }))
+}
+
+fn day6_part2(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let mut obstacles = day6::loop_obstacles(day6::Day6::parse(input)?);
+    obstacles.sort_by_key(|(x, y)| (*y, *x));
+    let lines = obstacles.iter().map(|(x, y)| format!("x {} y {}", x, y)).collect();
+    Ok((obstacles.len().into(), Explanation { of: "loop obstacles", lines }))
+}
+
+fn true_equations(input: &str, ops: Vec<day7::Operator>) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    let solved: Vec<(u128, String)> = day7::Day7::parse(input)?.iter()
+        .filter_map(|eq_data| {
+            day7::find_op_sequence(eq_data, &ops).map(|op_seq| (eq_data.solution(), day7::show_solved(eq_data, &op_seq)))
+        })
+        .collect();
+    let answer: u128 = solved.iter().map(|(solution, _)| solution).sum();
+    let lines = solved.into_iter().map(|(_, line)| line).collect();
+    Ok((answer.into(), Explanation { of: "true equations", lines }))
+}
+
+fn day7_part1(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    true_equations(input, vec![day7::Operator::Plus, day7::Operator::Mult])
+}
+
+fn day7_part2(input: &str, _overrides: &Overrides) -> Result<(Answer, Explanation), Box<dyn Error>> {
+    true_equations(input, vec![day7::Operator::Plus, day7::Operator::Mult, day7::Operator::Concat])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::common::get_data_path;
+    use crate::registry;
+
+    const EQUATIONS: &str = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n";
+
+    #[test]
+    fn explains_equations() {
+        let (answer, explanation) = find(7, 1).unwrap().explain(EQUATIONS, &Overrides::default()).unwrap();
+        assert_eq!(answer, Answer::Int(3457));
+        assert_eq!(explanation.lines, vec!["190 = 10 * 19", "3267 = 81 + 40 * 27"]);
+        let (answer, explanation) = find(7, 2).unwrap().explain(EQUATIONS, &Overrides::default()).unwrap();
+        assert_eq!(answer, Answer::Int(3613));
+        assert_eq!(explanation.lines, vec!["190 = 10 * 19", "3267 = 81 + 40 * 27", "156 = 15 || 6"]);
+    }
+
+    #[test]
+    fn explains_reports() {
+        let input = "7 6 4 2 1\n1 2 7 8 9\n1 3 2 4 5\n";
+        let (answer, explanation) = find(2, 2).unwrap().explain(input, &Overrides::default()).unwrap();
+        assert_eq!(answer, Answer::Int(2));
+        assert_eq!(explanation.lines, vec!["line 1: 7 6 4 2 1", "line 3: 1 3 2 4 5 (removed index [2])"]);
+        assert_eq!(explanation.to_string(), "safe reports (2):\n  line 1: 7 6 4 2 1\n  line 3: 1 3 2 4 5 (removed index [2])\n");
+    }
+
+    #[test]
+    fn explained_answers_match_solutions() {
+        for explainer in all() {
+            let entry = registry::find(explainer.day, explainer.part).unwrap();
+            let input = fs::read_to_string(get_data_path(entry.input_path).unwrap()).unwrap();
+            let (answer, _) = explainer.explain(&input, &Overrides::default()).unwrap();
+            assert_eq!(answer, entry.solve(&input, &Overrides::default()).unwrap(), "day {} part {}", entry.day, entry.part);
+        }
+    }
+
+    #[test]
+    fn explanations_are_of_solutions() {
+        for explainer in all() {
+            assert!(registry::find(explainer.day, explainer.part).is_some());
+        }
+        assert!(find(4, 1).is_none());
+    }
+}
@@ -0,0 +1,414 @@
+use std::error::Error;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rustc_hash::FxHashSet;
+
+use crate::common::grid::{Grid, Upos, OFFSETS_4};
+use crate::common::search;
+use crate::config::Overrides;
+use crate::{day14, day18};
+
+// synthetic puzzle inputs, for profiling well past the size of the real ones.
+// every generated input parses, and anything a solution relies on beyond the format
+// (a way through a maze, a byte that eventually blocks the exit) holds too.
+// the days whose space comes from their Config build the input for the same Config,
+// so solve it with the overrides it was generated with
+
+pub struct Generator {
+    pub day: usize,
+    // what `size` counts, for the usage text
+    pub size_of: &'static str,
+    // roughly the size of the real input
+    pub default_size: usize,
+    generate: GenerateFn,
+}
+
+type GenerateFn = fn(&mut StdRng, usize, &Overrides) -> Result<String, Box<dyn Error>>;
+
+impl Generator {
+    pub fn generate(&self, size: usize, seed: u64, overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+        (self.generate)(&mut StdRng::seed_from_u64(seed), size, overrides)
+    }
+}
+
+pub fn all() -> Vec<Generator> {
+    vec![
+        Generator { day: 1, size_of: "location pairs", default_size: 1000, generate: day1 },
+        Generator { day: 2, size_of: "reports", default_size: 1000, generate: day2 },
+        Generator { day: 7, size_of: "equations", default_size: 850, generate: day7 },
+        Generator { day: 9, size_of: "disk map digits", default_size: 19999, generate: day9 },
+        Generator { day: 11, size_of: "stones", default_size: 8, generate: day11 },
+        Generator { day: 13, size_of: "claw machines", default_size: 320, generate: day13 },
+        Generator { day: 14, size_of: "robots", default_size: 500, generate: day14 },
+        Generator { day: 16, size_of: "maze width and height", default_size: 141, generate: day16 },
+        Generator { day: 18, size_of: "falling bytes", default_size: 3450, generate: day18 },
+        Generator { day: 19, size_of: "designs", default_size: 400, generate: day19 },
+        Generator { day: 20, size_of: "track width and height", default_size: 141, generate: day20 },
+        Generator { day: 22, size_of: "buyers", default_size: 2000, generate: day22 },
+    ]
+}
+
+pub fn find(day: usize) -> Option<Generator> {
+    all().into_iter().find(|g| g.day == day)
+}
+
+fn lines<F>(size: usize, mut line: F) -> String
+where
+    F: FnMut() -> String,
+{
+    (0..size).map(|_| line() + "\n").collect()
+}
+
+fn day1(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    Ok(lines(size, || format!("{}   {}", rng.gen_range(10000..100000), rng.gen_range(10000..100000))))
+}
+
+// mostly gentle slopes, so some reports are safe and some need the dampener
+fn day2(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    Ok(lines(size, || {
+        let len = rng.gen_range(5..=8);
+        let direction = if rng.gen_bool(0.5) { 1 } else { -1 };
+        let mut level: isize = rng.gen_range(20..80);
+        let mut levels = vec![];
+        for _ in 0..len {
+            levels.push(level.to_string());
+            let step = match rng.gen_range(0..10) {
+                0 => rng.gen_range(-3..=0),
+                1 => rng.gen_range(4..=6),
+                _ => rng.gen_range(1..=3),
+            };
+            level = (level + direction * step).clamp(1, 99);
+        }
+        levels.join(" ")
+    }))
+}
+
+// totals come from a random mix of operators so most lines have an answer, then some are nudged off it
+fn day7(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    const MAX_TOTAL: u128 = 1_000_000_000_000_000;
+    Ok(lines(size, || {
+        let len = rng.gen_range(3..=12);
+        let operands: Vec<u128> = (0..len).map(|_| rng.gen_range(1..1000)).collect();
+        let mut total = operands[0];
+        for operand in operands[1..].iter() {
+            let next = match rng.gen_range(0..3) {
+                0 => total * operand,
+                1 => format!("{}{}", total, operand).parse().unwrap(),
+                _ => total + operand,
+            };
+            total = if next <= MAX_TOTAL { next } else { total + operand };
+        }
+        if rng.gen_bool(0.3) {
+            total += 1;
+        }
+        let operands: Vec<String> = operands.iter().map(|o| o.to_string()).collect();
+        format!("{}: {}", total, operands.join(" "))
+    }))
+}
+
+// files at the even positions, never empty, and the map ends on a file
+fn day9(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let size = if size.is_multiple_of(2) { size + 1 } else { size };
+    let digits: String = (0..size)
+        .map(|i| match i % 2 {
+            0 => rng.gen_range(1..=9),
+            _ => rng.gen_range(0..=9),
+        })
+        .map(|d: u32| char::from_digit(d, 10).unwrap())
+        .collect();
+    Ok(digits + "\n")
+}
+
+fn day11(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let stones: Vec<String> = (0..size.max(1)).map(|_| rng.gen_range(0..1_000_000).to_string()).collect();
+    Ok(stones.join(" ") + "\n")
+}
+
+// half the prizes can be won, half are wherever they land
+fn day13(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let machines: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let (ax, ay) = (rng.gen_range(10..100), rng.gen_range(10..100));
+            let (bx, by) = (rng.gen_range(10..100), rng.gen_range(10..100));
+            let (px, py) = if rng.gen_bool(0.5) {
+                let (a, b) = (rng.gen_range(0..=100), rng.gen_range(0..=100));
+                (a*ax + b*bx, a*ay + b*by)
+            } else {
+                (rng.gen_range(1000..20000), rng.gen_range(1000..20000))
+            };
+            format!("Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n", ax, ay, bx, by, px, py)
+        })
+        .collect();
+    Ok(machines.join("\n"))
+}
+
+fn day14(rng: &mut StdRng, size: usize, overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let config = overrides.for_day::<day14::Config>(14)?;
+    let (width, height) = (config.width, config.height);
+    Ok(lines(size, || {
+        format!(
+            "p={},{} v={},{}",
+            rng.gen_range(0..width),
+            rng.gen_range(0..height),
+            rng.gen_range(-width + 1..width),
+            rng.gen_range(-height + 1..height),
+        )
+    }))
+}
+
+// a perfect maze, walled in, with a passage everywhere both coordinates are odd.
+// S is bottom left and E is top right, like the real inputs
+fn carve_maze(rng: &mut StdRng, size: usize) -> (Grid<char>, Upos, Upos) {
+    let size = size.max(5) | 1;
+    let mut maze = Grid::new(size, size, '#');
+    let start = (1, size - 2);
+    let end = (size - 2, 1);
+
+    maze[start] = '.';
+    let mut stack = vec![start];
+    while let Some(&(x, y)) = stack.last() {
+        let unvisited: Vec<(Upos, Upos)> = OFFSETS_4.iter()
+            .filter_map(|(dx, dy)| {
+                let next = (x.checked_add_signed(2 * dx)?, y.checked_add_signed(2 * dy)?);
+                let wall = (x.checked_add_signed(*dx)?, y.checked_add_signed(*dy)?);
+                let inside = next.0 < size - 1 && next.1 < size - 1;
+                (inside && maze[next] == '#').then_some((wall, next))
+            })
+            .collect();
+        match unvisited.choose(rng) {
+            Some(&(wall, next)) => {
+                maze[wall] = '.';
+                maze[next] = '.';
+                stack.push(next);
+            },
+            None => {
+                stack.pop();
+            },
+        }
+    }
+    (maze, start, end)
+}
+
+// a perfect maze with some walls knocked through, so there's more than one way to E
+fn day16(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let (mut maze, start, end) = carve_maze(rng, size);
+    let (width, height) = (maze.width(), maze.height());
+    for _ in 0..width * height / 30 {
+        let pos = (rng.gen_range(1..width - 1), rng.gen_range(1..height - 1));
+        let joins_across = maze[(pos.0 - 1, pos.1)] == '.' && maze[(pos.0 + 1, pos.1)] == '.';
+        let joins_down = maze[(pos.0, pos.1 - 1)] == '.' && maze[(pos.0, pos.1 + 1)] == '.';
+        if joins_across != joins_down {
+            maze[pos] = '.';
+        }
+    }
+    maze[start] = 'S';
+    maze[end] = 'E';
+    Ok(maze.to_string())
+}
+
+// bytes land everywhere except the corners, in an order where the first bytes_fallen still leave
+// a way out. the list always runs at least as far as the byte that finally blocks it
+fn day18(rng: &mut StdRng, size: usize, overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let config = overrides.for_day::<day18::Config>(18)?;
+    let x_max = usize::try_from(config.x_max).unwrap();
+    let y_max = usize::try_from(config.y_max).unwrap();
+    let fallen = usize::try_from(config.bytes_fallen).unwrap();
+    let start = (0, 0);
+    let goal = (x_max, y_max);
+
+    // a random staircase from corner to corner stays clear of the first bytes_fallen,
+    // the rest of the bytes land in any order after them
+    if x_max + y_max < 2 {
+        return Err(Box::from(format!("a {}x{} space has no room to block the way out", x_max + 1, y_max + 1)));
+    }
+    let mut way_out = FxHashSet::default();
+    let mut step = start;
+    while step != goal {
+        if step != start {
+            way_out.insert(step);
+        }
+        step = match step.0 < x_max && (step.1 == y_max || rng.gen_bool(0.5)) {
+            true => (step.0 + 1, step.1),
+            false => (step.0, step.1 + 1),
+        };
+    }
+    let (mut later, mut cells): (Vec<Upos>, Vec<Upos>) = (0..=y_max)
+        .flat_map(|y| (0..=x_max).map(move |x| (x, y)))
+        .filter(|pos| *pos != start && *pos != goal)
+        .partition(|pos| way_out.contains(pos));
+    if fallen > cells.len() {
+        return Err(Box::from(format!(
+            "bytes_fallen = {} leaves no way out of a {}x{} space", fallen, x_max + 1, y_max + 1,
+        )));
+    }
+    cells.shuffle(rng);
+    later.extend(cells.drain(fallen..));
+    later.shuffle(rng);
+    cells.extend(later);
+
+    let escapes = |bytes: &[Upos]| {
+        let mut memory = Grid::new(x_max + 1, y_max + 1, true);
+        for byte in bytes {
+            memory[*byte] = false;
+        }
+        search::bfs_to(start, |pos| memory.neighbors4_u(*pos).filter(|n| memory[*n]).collect::<Vec<_>>(), |pos| *pos == goal)
+            .is_some()
+    };
+
+    // once the way out is gone more bytes can't bring it back, so search for the first
+    // number of bytes that blocks it. with every byte fallen it's certainly blocked
+    let counts: Vec<usize> = (fallen..=cells.len()).collect();
+    let blocked_at = counts[counts.partition_point(|n| escapes(&cells[..*n]))];
+    let len = size.max(blocked_at).min(cells.len());
+    Ok(cells[..len].iter()
+        .map(|(x, y)| format!("{},{}\n", x, y))
+        .collect())
+}
+
+// every single-letter towel but one, so some designs can't be made
+fn day19(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let colors = ['w', 'u', 'b', 'r', 'g'];
+    let missing = *colors.choose(rng).unwrap();
+
+    let mut towels: Vec<String> = colors.iter().filter(|c| **c != missing).map(|c| c.to_string()).collect();
+    while towels.len() < 100 {
+        let len = rng.gen_range(2..=8);
+        let towel: String = (0..len).map(|_| *colors.choose(rng).unwrap()).collect();
+        if !towels.contains(&towel) {
+            towels.push(towel);
+        }
+    }
+    towels.shuffle(rng);
+
+    let designs = lines(size, || {
+        let len = rng.gen_range(20..=60);
+        let mut design = String::new();
+        while design.len() < len {
+            match rng.gen_bool(0.8) {
+                true => design.push_str(towels.choose(rng).unwrap()),
+                false => design.push(*colors.choose(rng).unwrap()),
+            }
+        }
+        design
+    });
+    Ok(format!("{}\n\n{}", towels.join(", "), designs))
+}
+
+// the only way from S to E through a perfect maze, with everything else walled up
+fn day20(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    let (maze, start, end) = carve_maze(rng, size);
+    let (_, path) = search::astar(
+        start,
+        |pos| maze.neighbors4_u(*pos).filter(|n| maze[*n] == '.').map(|n| (n, 1)).collect::<Vec<_>>(),
+        |_| 0,
+        |pos| *pos == end,
+    ).unwrap();
+
+    let mut track = Grid::new(maze.width(), maze.height(), '#');
+    for pos in path {
+        track[pos] = '.';
+    }
+    track[start] = 'S';
+    track[end] = 'E';
+    Ok(track.to_string())
+}
+
+fn day22(rng: &mut StdRng, size: usize, _overrides: &Overrides) -> Result<String, Box<dyn Error>> {
+    Ok(lines(size, || rng.gen_range(1..16_777_216).to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::{Answer, Solution};
+    use crate::{day1, day2, day7, day9, day11, day13, day14, day16, day18, day19, day20, day22};
+
+    fn parses(day: usize, input: &str) -> bool {
+        match day {
+            1 => day1::Day1::parse(input).is_ok(),
+            2 => day2::Day2::parse(input).is_ok(),
+            7 => day7::Day7::parse(input).is_ok(),
+            9 => day9::Day9::parse(input).is_ok(),
+            11 => day11::Day11::parse(input).is_ok(),
+            13 => day13::Day13::parse(input).is_ok(),
+            14 => day14::Day14::parse(input).is_ok(),
+            16 => day16::Day16::parse(input).is_ok(),
+            18 => day18::Day18::parse(input).is_ok(),
+            19 => day19::Day19::parse(input).is_ok(),
+            20 => day20::Day20::parse(input).is_ok(),
+            22 => day22::Day22::parse(input).is_ok(),
+            _ => panic!("no parser listed for day {}", day),
+        }
+    }
+
+    #[test]
+    fn generated_inputs_parse() {
+        for generator in all() {
+            for size in [1, 2, 15] {
+                let input = generator.generate(size, 7, &Overrides::default()).unwrap();
+                assert!(parses(generator.day, &input), "day {} size {}:\n{}", generator.day, size, input);
+            }
+        }
+    }
+
+    #[test]
+    fn seeds_repeat() {
+        let generator = find(16).unwrap();
+        let overrides = Overrides::default();
+        assert_eq!(generator.generate(21, 3, &overrides).unwrap(), generator.generate(21, 3, &overrides).unwrap());
+        assert_ne!(generator.generate(21, 3, &overrides).unwrap(), generator.generate(21, 4, &overrides).unwrap());
+    }
+
+    #[test]
+    fn mazes_can_be_solved() {
+        let maze = find(16).unwrap().generate(21, 1, &Overrides::default()).unwrap();
+        let cost = day16::Day16::part1(&day16::Day16::parse(&maze).unwrap(), &day16::Config::default()).unwrap();
+        assert!(matches!(cost, Answer::Int(cost) if cost > 0), "{}", maze);
+
+        // the track is a single corridor, so every open tile but S and E has exactly two open neighbors
+        let track = find(20).unwrap().generate(21, 1, &Overrides::default()).unwrap();
+        let grid = Grid::parse(20, &track, Some).unwrap();
+        for (pos, tile) in grid.iter() {
+            let open = grid.neighbors4_u(pos).filter(|n| grid[*n] != '#').count();
+            match tile {
+                '.' => assert_eq!(open, 2, "{:?}\n{}", pos, track),
+                'S' | 'E' => assert_eq!(open, 1, "{:?}\n{}", pos, track),
+                _ => (),
+            }
+        }
+    }
+
+    #[test]
+    fn bytes_block_eventually() {
+        let bytes = find(18).unwrap().generate(10, 1, &Overrides::default()).unwrap();
+        let bytes = day18::Day18::parse(&bytes).unwrap();
+        let config = day18::Config::default();
+        assert!(bytes.len() > usize::try_from(config.bytes_fallen).unwrap());
+        // part 2 gives up with an error once every byte has fallen and the path is still open
+        let blocking = day18::Day18::part2(&bytes, &config).unwrap().to_string();
+        let (x, y) = blocking.split_once(',').unwrap();
+        let blocking = (x.parse().unwrap(), y.parse().unwrap());
+        let fallen = bytes.iter().position(|pos| *pos == blocking).unwrap();
+        assert!(fallen >= usize::try_from(config.bytes_fallen).unwrap());
+    }
+
+    #[test]
+    fn spaces_follow_the_config() {
+        let mut overrides = Overrides::from("[day18]\nx_max = 150\ny_max = 120\nbytes_fallen = 17000\n").unwrap();
+        let bytes = find(18).unwrap().generate(10, 1, &overrides).unwrap();
+        let bytes = day18::Day18::parse(&bytes).unwrap();
+        assert!(bytes.iter().any(|(x, _)| *x > 70));
+        let config = overrides.for_day::<day18::Config>(18).unwrap();
+        assert!(matches!(day18::Day18::part1(&bytes, &config).unwrap(), Answer::Int(steps) if steps >= 270));
+
+        overrides.set(18, "bytes_fallen=18001").unwrap();
+        assert!(find(18).unwrap().generate(10, 1, &overrides).is_err());
+
+        let overrides = Overrides::from("[day14]\nwidth = 1001\nheight = 7\n").unwrap();
+        let robots = find(14).unwrap().generate(50, 1, &overrides).unwrap();
+        let robots = day14::Day14::parse(&robots).unwrap();
+        assert_eq!(robots.len(), 50);
+    }
+}
@@ -0,0 +1,391 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use rustc_hash::FxHashMap;
+
+use crate::common::grid::Grid;
+use crate::config::Overrides;
+use crate::solution::Solution;
+use crate::visualize::Playback;
+use crate::{day6, day12, day14, day15, day16, day20};
+
+// the grid days as pictures, a pixel block per tile. most are drawn from the text the day
+// already renders, colored by a palette for the day, a sequence of them makes an animated gif
+
+pub type Rgb = [u8; 3];
+
+pub type Pictures = Box<dyn Iterator<Item = Grid<Rgb>>>;
+
+type PicturesFn = fn(&str, &Overrides) -> Result<Pictures, Box<dyn Error>>;
+
+pub struct Picture {
+    pub day: usize,
+    pub part: usize,
+    // what the pictures are of, for the usage text
+    pub shows: &'static str,
+    // the pictures never run out, so an export needs the end of the range
+    pub endless: bool,
+    pictures: PicturesFn,
+}
+
+impl Picture {
+    pub fn pictures(&self, input: &str, overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+        (self.pictures)(input, overrides)
+    }
+}
+
+pub fn all() -> Vec<Picture> {
+    vec![
+        Picture { day: 6, part: 1, shows: "the guard walking off the map", endless: false, pictures: day6_walk },
+        Picture { day: 12, part: 1, shows: "the garden, a color per plant", endless: false, pictures: day12_garden },
+        Picture { day: 14, part: 1, shows: "the robots for the first `seconds`", endless: false, pictures: day14_part1 },
+        Picture { day: 14, part: 2, shows: "the robots, second after second", endless: true, pictures: day14_part2 },
+        Picture { day: 15, part: 1, shows: "the robot pushing boxes", endless: false, pictures: day15_part1 },
+        Picture { day: 15, part: 2, shows: "the robot pushing wide boxes", endless: false, pictures: day15_part2 },
+        Picture { day: 16, part: 2, shows: "the tiles on a best path", endless: false, pictures: day16_best_paths },
+        Picture { day: 20, part: 1, shows: "how many 2ps cheats start on each tile", endless: false, pictures: day20_part1 },
+        Picture { day: 20, part: 2, shows: "how many 20ps cheats start on each tile", endless: false, pictures: day20_part2 },
+    ]
+}
+
+pub fn find(day: usize, part: usize) -> Option<Picture> {
+    all().into_iter().find(|p| p.day == day && p.part == part)
+}
+
+// what each char a day renders is drawn as. chars that aren't listed get a color of their own,
+// picked from the char so it's the same in every picture
+pub struct Palette {
+    pub colors: &'static [(char, Rgb)],
+}
+
+const DARK: Rgb = [24, 24, 32];
+const WALL: Rgb = [110, 110, 120];
+const RED: Rgb = [220, 50, 50];
+const GOLD: Rgb = [235, 200, 60];
+const GREEN: Rgb = [80, 210, 100];
+const BROWN: Rgb = [180, 120, 60];
+
+const LAB: Palette = Palette {
+    colors: &[('.', DARK), ('#', WALL), ('X', GOLD), ('O', BROWN), ('^', RED), ('v', RED), ('<', RED), ('>', RED)],
+};
+
+const GARDEN: Palette = Palette { colors: &[] };
+
+const LOBBY: Palette = Palette {
+    colors: &[
+        ('.', DARK), ('1', GREEN), ('2', GREEN), ('3', GREEN), ('4', GREEN),
+        ('5', GREEN), ('6', GREEN), ('7', GREEN), ('8', GREEN), ('9', GREEN),
+    ],
+};
+
+const WAREHOUSE: Palette = Palette {
+    colors: &[('.', DARK), ('#', WALL), ('O', BROWN), ('[', BROWN), (']', BROWN), ('@', RED)],
+};
+
+const MAZE: Palette = Palette {
+    colors: &[('.', DARK), ('#', WALL), ('O', GOLD), ('S', GREEN), ('E', RED)],
+};
+
+impl Palette {
+    pub fn color(&self, c: char) -> Rgb {
+        match self.colors.iter().find(|(tile, _)| *tile == c) {
+            Some((_, color)) => *color,
+            None => color_of(c),
+        }
+    }
+}
+
+// hues a golden ratio apart, so neighboring chars don't look alike
+fn color_of(c: char) -> Rgb {
+    let hue = (f64::from(u32::from(c)) * 0.618_033_988_75).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [r, g, b].map(|v: f64| 50 + (v * 190.0) as u8)
+}
+
+// a rendered grid, a line per row. short lines are padded out with the palette's color for ' '
+pub fn from_text(text: &str, palette: &Palette) -> Grid<Rgb> {
+    let lines: Vec<&str> = text.trim_end_matches('\n').lines().collect();
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+    let mut picture = Grid::new(width, lines.len(), palette.color(' '));
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            picture[(x, y)] = palette.color(c);
+        }
+    }
+    picture
+}
+
+// from dark blue for 0 up to yellow for max, in a few dozen steps so a gif's palette can hold them
+pub fn heat(value: usize, max: usize) -> Rgb {
+    const STEPS: usize = 32;
+    const COLD: Rgb = [30, 40, 110];
+    const HOT: Rgb = [250, 230, 60];
+    let step = (value * STEPS).checked_div(max).unwrap_or(0).min(STEPS);
+    let mut color = COLD;
+    for i in 0..3 {
+        let (cold, hot) = (usize::from(COLD[i]), usize::from(HOT[i]));
+        color[i] = u8::try_from((cold * (STEPS - step) + hot * step) / STEPS).unwrap();
+    }
+    color
+}
+
+fn day6_walk(input: &str, _overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let frames = day6::walk_frames(day6::Day6::parse(input)?);
+    Ok(Box::new(frames.map(|frame| from_text(&frame, &LAB))))
+}
+
+fn day12_garden(input: &str, _overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let garden = day12::Day12::parse(input)?;
+    Ok(Box::new(std::iter::once(from_text(&garden.to_string(), &GARDEN))))
+}
+
+fn day14_part1(input: &str, overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let config = overrides.for_day::<day14::Config>(14)?;
+    let renders = day14::lobby_renders(day14::Day14::parse(input)?, &config).take(config.seconds + 1);
+    Ok(Box::new(renders.map(|render| from_text(&render, &LOBBY))))
+}
+
+fn day14_part2(input: &str, overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let config = overrides.for_day::<day14::Config>(14)?;
+    let renders = day14::lobby_renders(day14::Day14::parse(input)?, &config);
+    Ok(Box::new(renders.map(|render| from_text(&render, &LOBBY))))
+}
+
+fn day15_part1(input: &str, _overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let (wh, _, moves) = day15::Day15::parse(input)?;
+    Ok(Box::new(day15::push_frames(wh, moves).map(|frame| from_text(&frame, &WAREHOUSE))))
+}
+
+fn day15_part2(input: &str, _overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let (_, wide_wh, moves) = day15::Day15::parse(input)?;
+    Ok(Box::new(day15::push_frames(wide_wh, moves).map(|frame| from_text(&frame, &WAREHOUSE))))
+}
+
+fn day16_best_paths(input: &str, overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    let config = overrides.for_day::<day16::Config>(16)?;
+    let render = day16::render_best_paths(&day16::Day16::parse(input)?, &config);
+    Ok(Box::new(std::iter::once(from_text(&render, &MAZE))))
+}
+
+fn day20_heatmap(input: &str, overrides: &Overrides, cheat_ps: isize) -> Result<Pictures, Box<dyn Error>> {
+    let config = overrides.for_day::<day20::Config>(20)?;
+    let heat_counts = day20::cheat_heatmap(&day20::Day20::parse(input)?, cheat_ps, config.min_savings_ps);
+    let max = heat_counts.iter().filter_map(|(_, count)| *count).max().unwrap_or(0);
+    let picture = heat_counts.map(|count| match count {
+        Some(count) => heat(*count, max),
+        None => WALL,
+    });
+    Ok(Box::new(std::iter::once(picture)))
+}
+
+fn day20_part1(input: &str, overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    day20_heatmap(input, overrides, 2)
+}
+
+fn day20_part2(input: &str, overrides: &Overrides) -> Result<Pictures, Box<dyn Error>> {
+    day20_heatmap(input, overrides, 20)
+}
+
+// the picture's pixels blown up to scale x scale blocks, as rgb bytes
+fn scaled_bytes(picture: &Grid<Rgb>, scale: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(picture.width() * picture.height() * scale * scale * 3);
+    for row in picture.rows() {
+        for _ in 0..scale {
+            for pixel in row {
+                for _ in 0..scale {
+                    bytes.extend_from_slice(pixel);
+                }
+            }
+        }
+    }
+    bytes
+}
+
+pub fn write_png<W: Write>(out: W, picture: &Grid<Rgb>, scale: usize) -> Result<(), Box<dyn Error>> {
+    let width = u32::try_from(picture.width() * scale)?;
+    let height = u32::try_from(picture.height() * scale)?;
+    let mut encoder = png::Encoder::new(out, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&scaled_bytes(picture, scale))?;
+    Ok(())
+}
+
+// every picture is a frame of a looping gif. the palettes are small, so frames are indexed by
+// their own colors rather than quantized, which only happens if a frame has more than 256
+pub fn write_gif<W, I>(out: W, pictures: I, scale: usize, fps: f64) -> Result<(), Box<dyn Error>>
+where
+    W: Write,
+    I: Iterator<Item = Grid<Rgb>>,
+{
+    let mut pictures = pictures.peekable();
+    let first = pictures.peek().ok_or("no frames to write")?;
+    let too_big = |_| format!("{}x{} tiles at scale {} is too big for a gif", first.width(), first.height(), scale);
+    let width = u16::try_from(first.width() * scale).map_err(too_big)?;
+    let height = u16::try_from(first.height() * scale).map_err(too_big)?;
+    // gifs count delays in hundredths of a second
+    let delay = (100.0 / fps).round().clamp(1.0, f64::from(u16::MAX)) as u16;
+
+    let mut encoder = gif::Encoder::new(out, width, height, &[])?;
+    encoder.set_repeat(gif::Repeat::Infinite)?;
+    for picture in pictures {
+        if (picture.width() * scale, picture.height() * scale) != (usize::from(width), usize::from(height)) {
+            return Err(Box::from("every frame of a gif has to be the same size"));
+        }
+        let bytes = scaled_bytes(&picture, scale);
+        let mut colors: FxHashMap<Rgb, u8> = FxHashMap::default();
+        let mut indices = Vec::with_capacity(bytes.len() / 3);
+        for pixel in bytes.chunks_exact(3) {
+            // past 256 colors the indices are never used
+            let next = colors.len();
+            indices.push(*colors.entry([pixel[0], pixel[1], pixel[2]]).or_insert(next as u8));
+        }
+        let mut frame = match colors.len() {
+            0..=256 => {
+                let mut palette = vec![0; colors.len() * 3];
+                for (color, i) in colors.iter() {
+                    palette[usize::from(*i) * 3..][..3].copy_from_slice(color);
+                }
+                gif::Frame::from_palette_pixels(width, height, indices, palette, None)
+            },
+            _ => gif::Frame::from_rgb_speed(width, height, &bytes, 10),
+        };
+        frame.delay = delay;
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+// a png of the last picture in the range, or a gif of all of them, going by the extension
+pub fn export(pictures: Pictures, path: &Path, scale: usize, playback: &Playback) -> Result<(), Box<dyn Error>> {
+    let extension = path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase());
+    if !matches!(extension.as_deref(), Some("png" | "gif")) {
+        return Err(Box::from(format!("can't tell the format of {}, it should end in .png or .gif", path.display())));
+    }
+    let mut pictures = playback.range.select(pictures).map(|(_, picture)| picture).peekable();
+    if pictures.peek().is_none() {
+        return Err(Box::from("no frames in the range"));
+    }
+    // the picture is written next to path and only moved over it once it's whole, so a frame
+    // that fails partway through doesn't leave half a file behind
+    let mut partial = path.as_os_str().to_owned();
+    partial.push(".part");
+    let partial = PathBuf::from(partial);
+    let written = File::create(&partial)
+        .map_err(|e| Box::from(format!("could not create {}: {}", partial.display(), e)))
+        .and_then(|out| {
+            let mut out = BufWriter::new(out);
+            match extension.as_deref() {
+                Some("png") => {
+                    let picture = pictures.last().ok_or("no frames in the range")?;
+                    write_png(&mut out, &picture, scale)?;
+                },
+                _ => write_gif(&mut out, pictures, scale, playback.fps)?,
+            }
+            out.flush()?;
+            Ok(())
+        });
+    match written {
+        Ok(()) => fs::rename(&partial, path)
+            .map_err(|e| Box::from(format!("could not write {}: {}", path.display(), e))),
+        Err(e) => {
+            // the error that stopped the writing matters more than a leftover file
+            let _ = fs::remove_file(&partial);
+            Err(e)
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    #[test]
+    fn text_to_pixels() {
+        let picture = from_text("#.\n#\n\n", &MAZE);
+        assert_eq!((picture.width(), picture.height()), (2, 2));
+        assert_eq!(picture[(0, 0)], WALL);
+        assert_eq!(picture[(1, 0)], DARK);
+        // padding, ' ' isn't in the palette
+        assert_eq!(picture[(1, 1)], color_of(' '));
+        assert_ne!(GARDEN.color('A'), GARDEN.color('B'));
+        assert_eq!(GARDEN.color('A'), GARDEN.color('A'));
+    }
+
+    #[test]
+    fn heat_scale() {
+        assert_eq!(heat(0, 10), [30, 40, 110]);
+        assert_eq!(heat(10, 10), [250, 230, 60]);
+        assert_eq!(heat(0, 0), heat(0, 10));
+        assert!(heat(5, 10)[0] > heat(4, 10)[0]);
+    }
+
+    #[test]
+    fn writes_png() {
+        let mut out = Vec::new();
+        write_png(&mut out, &from_text("#.\n.#\n", &MAZE), 3).unwrap();
+        assert_eq!(&out[1..4], b"PNG");
+
+        let decoder = png::Decoder::new(out.as_slice());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (6, 6));
+    }
+
+    #[test]
+    fn writes_gif() {
+        let frames = ["#.\n", ".#\n", "..\n"].map(|frame| from_text(frame, &MAZE));
+        let mut out = Vec::new();
+        write_gif(&mut out, frames.into_iter(), 2, 5.0).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new().read_info(out.as_slice()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 2));
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 20);
+            count += 1;
+        }
+        assert_eq!(count, 3);
+
+        let sizes = [from_text("#.\n", &MAZE), from_text("#\n", &MAZE)];
+        assert!(write_gif(&mut Vec::new(), sizes.into_iter(), 1, 5.0).is_err());
+    }
+
+    #[test]
+    fn failed_export_leaves_no_file() {
+        let dir = std::env::temp_dir().join(format!("advent-export-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("frames.gif");
+
+        let sizes = [from_text("#.\n", &MAZE), from_text("#\n", &MAZE)];
+        assert!(export(Box::new(sizes.into_iter()), &path, 1, &Playback::default()).is_err());
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        let frames = [from_text("#.\n", &MAZE), from_text(".#\n", &MAZE)];
+        export(Box::new(frames.into_iter()), &path, 1, &Playback::default()).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        assert!(path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn pictures_are_of_solutions() {
+        for picture in all() {
+            assert!(registry::find(picture.day, picture.part).is_some());
+        }
+        assert!(find(20, 2).is_some());
+        assert!(find(20, 3).is_none());
+        assert!(find(14, 2).unwrap().endless);
+    }
+}
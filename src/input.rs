@@ -0,0 +1,143 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use crate::common::get_data_path;
+
+// where a day's puzzle input comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    Path(PathBuf),
+    Stdin,
+    Str(String),
+    Embedded(&'static str),
+}
+
+impl InputSource {
+    // "-" reads from stdin, anything else is a path
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => InputSource::Stdin,
+            path => InputSource::Path(PathBuf::from(path)),
+        }
+    }
+
+    // the data dir wins, the copy compiled into the binary is only a fallback
+    pub fn default_for(rel_path: &str) -> Result<Self, Box<dyn Error>> {
+        match get_data_path(rel_path) {
+            Ok(path) => Ok(InputSource::Path(path)),
+            Err(e) => match embedded(rel_path) {
+                Some(input) => Ok(InputSource::Embedded(input)),
+                None => Err(e),
+            },
+        }
+    }
+
+    pub fn read(&self) -> Result<String, Box<dyn Error>> {
+        match self {
+            InputSource::Path(path) => fs::read_to_string(path)
+                .map_err(|e| Box::from(format!("could not read {}: {}", path.display(), e))),
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)?;
+                Ok(input)
+            },
+            InputSource::Str(input) => Ok(input.clone()),
+            InputSource::Embedded(input) => Ok(input.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputSource::Path(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "<stdin>"),
+            InputSource::Str(_) => write!(f, "<string>"),
+            InputSource::Embedded(_) => write!(f, "<embedded>"),
+        }
+    }
+}
+
+#[cfg(feature = "embed-inputs")]
+macro_rules! embed {
+    ($($path:literal),* $(,)?) => {
+        &[$(($path, include_str!(concat!("../data/", $path)))),*]
+    };
+}
+
+#[cfg(feature = "embed-inputs")]
+static EMBEDDED: &[(&str, &str)] = embed![
+    "day1/locations.txt",
+    "day2/reports.txt",
+    "day3/memory.txt",
+    "day4/word-search.txt",
+    "day5/ordering-updates.txt",
+    "day6/input.txt",
+    "day7/input.txt",
+    "day8/input.txt",
+    "day9/input.txt",
+    "day10/input.txt",
+    "day11/input.txt",
+    "day12/input.txt",
+    "day13/input.txt",
+    "day14/input.txt",
+    "day15/input.txt",
+    "day16/input.txt",
+    "day17/input.txt",
+    "day18/input.txt",
+    "day19/input.txt",
+    "day20/input.txt",
+    "day21/input.txt",
+    "day22/input.txt",
+    "day23/input.txt",
+    "day24/input.txt",
+    "day25/input.txt",
+];
+
+#[cfg(not(feature = "embed-inputs"))]
+static EMBEDDED: &[(&str, &str)] = &[];
+
+// inputs compiled in with the embed-inputs feature, keyed by their path under the data dir
+pub fn embedded(rel_path: &str) -> Option<&'static str> {
+    EMBEDDED.iter().find(|(path, _)| *path == rel_path).map(|(_, input)| *input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dash_means_stdin() {
+        assert_eq!(InputSource::from_arg("-"), InputSource::Stdin);
+        assert_eq!(InputSource::from_arg("in.txt"), InputSource::Path(PathBuf::from("in.txt")));
+    }
+
+    #[test]
+    fn reads_strings() {
+        let source = InputSource::Str(String::from("3   4\n"));
+        assert_eq!(source.read().unwrap(), "3   4\n");
+    }
+
+    #[test]
+    fn missing_path_names_the_file() {
+        let err = InputSource::from_arg("asdf/foo/bar.txt").read().unwrap_err();
+        assert!(err.to_string().starts_with("could not read asdf/foo/bar.txt"));
+    }
+
+    #[test]
+    fn defaults_to_data_dir() {
+        let source = InputSource::default_for("day1/locations.txt").unwrap();
+        assert_eq!(source, InputSource::Path(get_data_path("day1/locations.txt").unwrap()));
+    }
+
+    #[cfg(feature = "embed-inputs")]
+    #[test]
+    fn every_registered_input_is_embedded() {
+        for entry in crate::registry::all() {
+            assert!(embedded(entry.input_path).is_some(), "day {} not embedded", entry.day);
+        }
+    }
+}
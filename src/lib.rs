@@ -1,5 +1,7 @@
 
+pub mod answers;
 pub mod common;
+pub mod config;
 pub mod day1;
 pub mod day2;
 pub mod day3;
@@ -25,3 +27,14 @@ pub mod day22;
 pub mod day23;
 pub mod day24;
 pub mod day25;
+pub mod error;
+pub mod explain;
+pub mod generate;
+pub mod image;
+pub mod input;
+pub mod logging;
+pub mod output;
+pub mod registry;
+pub mod solution;
+pub mod stream;
+pub mod visualize;
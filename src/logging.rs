@@ -0,0 +1,53 @@
+use log::{LevelFilter, Log, Metadata, Record};
+
+// solutions log through the `log` macros instead of printing, so stdout only ever holds the answer.
+// nothing is shown unless the runner installs this logger, which writes to stderr
+struct StderrLogger;
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let target = record.target();
+            let target = target.strip_prefix("advent::").unwrap_or(target);
+            eprintln!("[{} {}] {}", record.level(), target, record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+// one -v shows progress, two show what the solutions are working on, three show everything
+pub fn level_for(verbosity: u8) -> LevelFilter {
+    match verbosity {
+        0 => LevelFilter::Warn,
+        1 => LevelFilter::Info,
+        2 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+pub fn init(verbosity: u8) {
+    // only fails if a logger is already installed, which is fine to keep
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(level_for(verbosity));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbosity_levels() {
+        assert_eq!(level_for(0), LevelFilter::Warn);
+        assert_eq!(level_for(1), LevelFilter::Info);
+        assert_eq!(level_for(2), LevelFilter::Debug);
+        assert_eq!(level_for(3), LevelFilter::Trace);
+        assert_eq!(level_for(9), LevelFilter::Trace);
+    }
+}
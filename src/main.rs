@@ -1,5 +1,642 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 
+use log::info;
+use rayon::prelude::*;
+
+use advent::answers::{Answers, Verdict};
+use advent::common::DATA_DIR_VAR;
+use advent::config::Overrides;
+use advent::day2;
+use advent::explain;
+use advent::generate;
+use advent::image;
+use advent::input::InputSource;
+use advent::output::{self, ErrorRecord, ExplainedRecord, Format, RunRecord};
+use advent::registry::{self, Entry};
+use advent::solution::Answer;
+use advent::visualize::{self, FrameRange, Playback};
+
+const USAGE: &str = "usage:
+    advent run <day> <part> [--input PATH] [--format FORMAT] [--config FILE] [--set KEY=VALUE]...
+    advent run <day> <part> --explain [--input PATH] [--format FORMAT] [--config FILE] [--set KEY=VALUE]...
+    advent run 2 <part> --diagnose [--input PATH] [--format FORMAT] [--config FILE] [--set KEY=VALUE]...
+    advent run <day> <part> --visualize [--fps N] [--frames RANGE] [--input PATH] [--config FILE] [--set KEY=VALUE]...
+    advent run --all [--format FORMAT] [--config FILE]
+    advent run <day> <part> --export FILE [--scale N] [--fps N] [--frames RANGE] [--input PATH] [--config FILE] [--set KEY=VALUE]...
+    advent run --explain --list
+    advent run --visualize --list
+    advent run --export --list
+    advent verify [<day> [<part>]]
+    advent gen <day> [--size N] [--seed N] [--config FILE] [--set KEY=VALUE]...
+    advent gen --list
+
+PATH may be - to read from stdin. by default inputs are read from
+$ADVENT_DATA_DIR, falling back to the data dir of the checkout.
+
+FORMAT is text (the default) or json. json writes one object per part
+with the answer, its type, the solve time and the input's sha256, so
+--all --format json is JSON Lines.
+
+some days have parameters that aren't in the input, like the size of the
+grid on day 18. they default to the real puzzle's. FILE is TOML with a
+table per day, e.g. [day18] with x_max = 6, and --set x_max=6 overrides a
+single one for the day being run. verify always uses the defaults.
+
+--explain prints what went into the answer after it, like the safe
+reports on day 2, run --explain --list shows the days it knows. with
+--format json the answer's object gets an explanation with a line each.
+
+--diagnose prints a table of what's wrong with each of day 2's reports
+instead of the answer: where the levels first turn around, where a step
+is first too small or too big and whether dampening fixes it, followed by
+a count of each kind of failure. with --format json it's one object.
+
+--visualize plays a simulation day step by step instead of printing the
+answer, run --visualize --list shows the days it knows. --fps sets the speed
+(10 by default) and RANGE picks the frames to show, counting from 0: A..B,
+A.., ..B or a single frame. while it plays, space pauses, n steps a frame,
++ and - change the speed and q quits. when stdout isn't a terminal the
+frames are written out one after another, and day 14 part 2 never stops
+on its own, so give it a RANGE.
+
+--export draws the day's grid into FILE instead of printing the answer,
+a PNG of the last frame in RANGE or an animated GIF of all of them, going
+by the extension. run --export --list shows the days it knows. each tile
+is N pixels across (4 by default) and --fps sets the speed of a GIF. day 14
+part 2 never runs out of frames, so RANGE needs an end for it.
+
+gen writes a random input for <day> to stdout, --list shows the days it
+knows and what --size counts for each. the same seed gives the same input,
+without one a seed is picked and logged at -v. days 14 and 18 fit their
+input to the day's parameters, so --config and --set work as for run.
+
+-v, -vv or -vvv (or --verbose, repeated) anywhere on the command line
+logs what the solutions are doing to stderr, more vs for more detail.
+
+--all and verify solve several days at once, and some days spread their
+own work across threads. --jobs N (anywhere on the command line) caps the
+thread count, --jobs 1 gives the most trustworthy times. parts that take
+more than 10s in a release build or don't finish yet (13-2, 14-2, 19-2, 23-2
+and 24-2) are skipped by both, run them on their own with run <day> <part>.";
+
+// what run does with the day once it's parsed
+enum Show {
+    Answer(Format),
+    // the answer followed by what went into it
+    Explain(Format),
+    // why each of day 2's reports is unsafe, rather than the answer
+    Diagnose(Format),
+    // play the frames of the simulation rather than printing the answer
+    Visualize(Playback),
+    // draw the frames into an image file, see image::export
+    Export {
+        path: PathBuf,
+        scale: usize,
+        playback: Playback,
+    },
+}
+
+struct RunArgs {
+    day: usize,
+    part: usize,
+    input: Option<InputSource>,
+    config: Option<PathBuf>,
+    sets: Vec<String>,
+    show: Show,
+}
+
+enum Command {
+    Run(RunArgs),
+    RunAll {
+        format: Format,
+        config: Option<PathBuf>,
+    },
+    Verify {
+        day: Option<usize>,
+        part: Option<usize>,
+    },
+    Gen {
+        day: usize,
+        size: Option<usize>,
+        seed: Option<u64>,
+        config: Option<PathBuf>,
+        sets: Vec<String>,
+    },
+    GenList,
+    ExplainList,
+    VisualizeList,
+    ExportList,
+}
+
+fn parse_num(arg: Option<&String>, name: &str) -> Result<usize, String> {
+    let arg = arg.ok_or(format!("missing <{}>", name))?;
+    arg.parse::<usize>()
+        .map_err(|_| format!("invalid <{}>: {}", name, arg))
+}
+
+// flags that apply to every command
+struct GlobalFlags {
+    verbosity: u8,
+    jobs: Option<usize>,
+}
+
+// global flags can go anywhere, returns them along with the args that are left
+fn take_global_flags(args: Vec<String>) -> Result<(GlobalFlags, Vec<String>), String> {
+    let mut flags = GlobalFlags { verbosity: 0, jobs: None };
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let vs = arg.strip_prefix('-').filter(|v| !v.is_empty() && v.chars().all(|c| c == 'v'));
+        match (arg.as_str(), vs) {
+            ("--verbose", _) => flags.verbosity = flags.verbosity.saturating_add(1),
+            (_, Some(vs)) => flags.verbosity = flags.verbosity.saturating_add(u8::try_from(vs.len()).unwrap_or(u8::MAX)),
+            ("--jobs", _) => {
+                let jobs = parse_num(args.next().as_ref(), "jobs")?;
+                if jobs == 0 {
+                    return Err(String::from("--jobs must be at least 1"));
+                }
+                flags.jobs = Some(jobs);
+            },
+            _ => rest.push(arg),
+        }
+    }
+    Ok((flags, rest))
+}
+
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut args = args.iter();
+    let command = args.next().map(|a| a.as_str());
+    let rest: Vec<&String> = args.collect();
+    match command {
+        Some("run") => parse_run_args(rest),
+        Some("verify") => parse_verify_args(rest),
+        Some("gen") => parse_gen_args(rest),
+        Some(other) => Err(format!("unknown command: {}", other)),
+        None => Err(String::from("missing command")),
+    }
+}
+
+fn parse_verify_args(rest: Vec<&String>) -> Result<Command, String> {
+    if rest.len() > 2 {
+        return Err(format!("unexpected argument: {}", rest[2]));
+    }
+    let mut rest = rest.into_iter();
+    let day = rest.next().map(|a| parse_num(Some(a), "day")).transpose()?;
+    let part = rest.next().map(|a| parse_num(Some(a), "part")).transpose()?;
+    Ok(Command::Verify { day, part })
+}
+
+fn parse_gen_args(rest: Vec<&String>) -> Result<Command, String> {
+    if rest.len() == 1 && rest[0] == "--list" {
+        return Ok(Command::GenList);
+    }
+
+    let mut rest = rest.into_iter();
+    let day = parse_num(rest.next(), "day")?;
+    let mut size = None;
+    let mut seed = None;
+    let mut config = None;
+    let mut sets = Vec::new();
+
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--size" => size = Some(parse_num(rest.next(), "size")?),
+            "--seed" => {
+                let value = rest.next().ok_or("missing value for --seed")?;
+                seed = Some(value.parse::<u64>().map_err(|_| format!("invalid seed: {}", value))?);
+            },
+            "--config" => {
+                let path = rest.next().ok_or("missing value for --config")?;
+                config = Some(PathBuf::from(path));
+            },
+            "--set" => sets.push(rest.next().ok_or("missing value for --set")?.clone()),
+            other => return Err(format!("unexpected argument: {}", other)),
+        }
+    }
+
+    Ok(Command::Gen { day, size, seed, config, sets })
+}
+
+fn parse_run_args(rest: Vec<&String>) -> Result<Command, String> {
+    if rest.len() == 2 && rest[1] == "--list" {
+        match rest[0].as_str() {
+            "--explain" => return Ok(Command::ExplainList),
+            "--visualize" => return Ok(Command::VisualizeList),
+            "--export" => return Ok(Command::ExportList),
+            _ => (),
+        }
+    }
+
+    let mut all = false;
+    let mut explain = false;
+    let mut diagnose = false;
+    let mut visualize = false;
+    let mut export = None;
+    let mut scale = None;
+    let mut fps = None;
+    let mut frames = None;
+    let mut input = None;
+    let mut format = None;
+    let mut config = None;
+    let mut sets = Vec::new();
+    let mut positional = Vec::new();
+
+    let mut rest = rest.into_iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--all" => all = true,
+            "--input" => {
+                let path = rest.next().ok_or("missing value for --input")?;
+                input = Some(InputSource::from_arg(path));
+            },
+            "--format" => {
+                let name = rest.next().ok_or("missing value for --format")?;
+                format = Some(Format::from_arg(name)?);
+            },
+            "--config" => {
+                let path = rest.next().ok_or("missing value for --config")?;
+                config = Some(PathBuf::from(path));
+            },
+            "--set" => sets.push(rest.next().ok_or("missing value for --set")?.clone()),
+            "--explain" => explain = true,
+            "--diagnose" => diagnose = true,
+            "--visualize" => visualize = true,
+            "--export" => {
+                let path = rest.next().ok_or("missing value for --export")?;
+                export = Some(PathBuf::from(path));
+            },
+            "--scale" => {
+                let value = parse_num(rest.next(), "scale")?;
+                if value == 0 {
+                    return Err(String::from("--scale must be at least 1"));
+                }
+                scale = Some(value);
+            },
+            "--fps" => {
+                let value = rest.next().ok_or("missing value for --fps")?;
+                match value.parse::<f64>() {
+                    Ok(value) if value > 0.0 && value.is_finite() => fps = Some(value),
+                    _ => return Err(format!("invalid fps: {}", value)),
+                }
+            },
+            "--frames" => {
+                let range = rest.next().ok_or("missing value for --frames")?;
+                frames = Some(FrameRange::from_arg(range)?);
+            },
+            other if other.starts_with("--") => return Err(format!("unexpected argument: {}", other)),
+            _ => positional.push(arg),
+        }
+    }
+
+    let drawing = visualize || export.is_some();
+    if visualize && export.is_some() {
+        return Err(String::from("--visualize and --export can't be used together"));
+    }
+    if !drawing && (fps.is_some() || frames.is_some()) {
+        return Err(String::from("--fps and --frames only go with --visualize or --export"));
+    }
+    if export.is_none() && scale.is_some() {
+        return Err(String::from("--scale only goes with --export"));
+    }
+    if explain && drawing {
+        return Err(String::from("--explain is for answers, it can't be used with --visualize or --export"));
+    }
+    if diagnose && (explain || drawing) {
+        return Err(String::from("--diagnose can't be used with --explain, --visualize or --export"));
+    }
+    if drawing && format.is_some() {
+        return Err(String::from("--format is for answers, it can't be used with --visualize or --export"));
+    }
+    let format = format.unwrap_or(Format::Text);
+    if all {
+        if drawing || explain || diagnose {
+            return Err(String::from("--explain, --diagnose, --visualize and --export can't be used with --all"));
+        }
+        if let Some(arg) = positional.first() {
+            return Err(format!("unexpected argument: {}", arg));
+        }
+        if input.is_some() {
+            return Err(String::from("--input can't be used with --all"));
+        }
+        if !sets.is_empty() {
+            return Err(String::from("--set can't be used with --all, put the overrides in a --config file"));
+        }
+        return Ok(Command::RunAll { format, config });
+    }
+
+    let mut positional = positional.into_iter();
+    let day = parse_num(positional.next(), "day")?;
+    let part = parse_num(positional.next(), "part")?;
+    if let Some(arg) = positional.next() {
+        return Err(format!("unexpected argument: {}", arg));
+    }
+    let default = Playback::default();
+    let playback = Playback { fps: fps.unwrap_or(default.fps), range: frames.unwrap_or(default.range) };
+    let show = match export {
+        Some(path) => Show::Export { path, scale: scale.unwrap_or(4), playback },
+        None if visualize => Show::Visualize(playback),
+        None if explain => Show::Explain(format),
+        None if diagnose => Show::Diagnose(format),
+        None => Show::Answer(format),
+    };
+    Ok(Command::Run(RunArgs { day, part, input, config, sets, show }))
+}
+
+struct Solved {
+    answer: Answer,
+    elapsed: Duration,
+    input: String,
+}
+
+impl Solved {
+    fn record(&self, entry: &Entry) -> RunRecord {
+        RunRecord::new(entry.day, entry.part, &self.answer, self.elapsed, &self.input)
+    }
+}
+
+fn load_overrides(config: Option<PathBuf>) -> Result<Overrides, Box<dyn Error>> {
+    match config {
+        Some(path) => Overrides::load(&path),
+        None => Ok(Overrides::default()),
+    }
+}
+
+fn read_input(entry: &Entry, input: Option<InputSource>) -> Result<String, Box<dyn Error>> {
+    let source = match input {
+        Some(source) => source,
+        None => InputSource::default_for(entry.input_path)
+            .map_err(|e| format!("{} (set {} to point at your inputs)", e, DATA_DIR_VAR))?,
+    };
+    source.read()
+}
+
+fn solve(entry: &Entry, input: Option<InputSource>, overrides: &Overrides) -> Result<Solved, Box<dyn Error>> {
+    let input = read_input(entry, input)?;
+    let start = Instant::now();
+    let answer = entry.solve(&input, overrides)?;
+    Ok(Solved { answer, elapsed: start.elapsed(), input })
+}
+
+fn run(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let RunArgs { day, part, input, config, sets, show } = args;
+    let entry = registry::find(day, part)
+        .ok_or(format!("no solution registered for day {} part {}", day, part))?;
+    let mut overrides = load_overrides(config)?;
+    for assignment in sets.iter() {
+        overrides.set(day, assignment)?;
+    }
+
+    match show {
+        Show::Answer(format) => {
+            let solved = solve(&entry, input, &overrides)?;
+            match format {
+                Format::Text => println!("{}", solved.answer),
+                Format::Json => println!("{}", output::to_json(&solved.record(&entry))),
+            }
+        },
+        Show::Explain(format) => {
+            let explainer = explain::find(day, part)
+                .ok_or(format!("day {} part {} can't be explained, see run --explain --list", day, part))?;
+            // the answer comes along with the explanation, so the time covers both
+            let input = read_input(&entry, input)?;
+            let start = Instant::now();
+            let (answer, explanation) = explainer.explain(&input, &overrides)?;
+            let solved = Solved { answer, elapsed: start.elapsed(), input };
+            match format {
+                Format::Text => print!("{}\n{}", solved.answer, explanation),
+                Format::Json => {
+                    let record = ExplainedRecord { record: solved.record(&entry), explanation };
+                    println!("{}", output::to_json(&record));
+                },
+            }
+        },
+        Show::Diagnose(format) => {
+            if day != 2 {
+                return Err(Box::from("only day 2 can be diagnosed"));
+            }
+            let config = overrides.for_day::<day2::Config>(2)?;
+            let diagnostics = day2::diagnose_reports(read_input(&entry, input)?.as_bytes(), &config)?;
+            match format {
+                Format::Text => print!("{}", diagnostics),
+                Format::Json => println!("{}", output::to_json(&diagnostics)),
+            }
+        },
+        Show::Visualize(playback) => {
+            let animation = visualize::find(day, part)
+                .ok_or(format!("day {} part {} can't be visualized, see run --visualize --list", day, part))?;
+            let frames = animation.frames(&read_input(&entry, input)?, &overrides)?;
+            visualize::play(frames, &playback)?;
+        },
+        Show::Export { path, scale, playback } => {
+            let picture = image::find(day, part)
+                .ok_or(format!("day {} part {} can't be exported, see run --export --list", day, part))?;
+            if picture.endless && playback.range.end.is_none() {
+                return Err(Box::from(format!("day {} part {} never runs out of frames, give --frames an end", day, part)));
+            }
+            let pictures = picture.pictures(&read_input(&entry, input)?, &overrides)?;
+            image::export(pictures, &path, scale, &playback)?;
+        },
+    }
+    Ok(())
+}
+
+// one failing day shouldn't take the rest of the table down with it
+fn solve_isolated(entry: &Entry, overrides: &Overrides) -> Result<Solved, String> {
+    solve(entry, None, overrides).map_err(|e| format!("error: {}", e))
+}
+
+// solves every entry on the thread pool, handing each result to `report` in the order of `entries`
+// as soon as it and everything before it is done. slow entries aren't solved and get None
+fn solve_all<F>(entries: &[Entry], overrides: &Overrides, mut report: F)
+where
+    F: FnMut(&Entry, Option<Result<Solved, String>>),
+{
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            entries.par_iter().enumerate().for_each_with(tx, |tx, (i, entry)| {
+                // the receiver only goes away if the main thread panicked
+                let _ = tx.send((i, (!entry.slow).then(|| solve_isolated(entry, overrides))));
+            });
+        });
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in rx {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next) {
+                report(&entries[next], result);
+                next += 1;
+            }
+        }
+    });
+}
+
+fn run_all(format: Format, config: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let overrides = load_overrides(config)?;
+    if format == Format::Text {
+        println!("{:>3}  {:>4}  {:>20}  {:>12}", "day", "part", "answer", "time");
+    }
+    solve_all(&registry::all(), &overrides, |entry, result| {
+        match format {
+            Format::Text => {
+                let (answer, elapsed) = match result {
+                    None => (String::from("skipped"), String::from("-")),
+                    Some(Ok(solved)) => (solved.answer.to_string(), format!("{:.3?}", solved.elapsed)),
+                    Some(Err(e)) => (e, String::from("-")),
+                };
+                println!("{:>3}  {:>4}  {:>20}  {:>12}", entry.day, entry.part, answer, elapsed);
+            },
+            Format::Json => {
+                let line = match result {
+                    None => output::to_json(&ErrorRecord { day: entry.day, part: entry.part, error: String::from("skipped") }),
+                    Some(Ok(solved)) => output::to_json(&solved.record(entry)),
+                    Some(Err(error)) => output::to_json(&ErrorRecord { day: entry.day, part: entry.part, error }),
+                };
+                println!("{}", line);
+            },
+        }
+    });
+    Ok(())
+}
+
+// returns whether everything that has a known answer still produces it
+fn verify(day: Option<usize>, part: Option<usize>) -> Result<bool, Box<dyn Error>> {
+    let answers = Answers::load()?;
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+
+    println!("{:>3}  {:>4}  {:<40}  {:>12}", "day", "part", "status", "time");
+    let entries: Vec<Entry> = registry::all().into_iter()
+        .filter(|e| day.is_none_or(|d| e.day == d) && part.is_none_or(|p| e.part == p))
+        .collect();
+    let mut skipped = 0;
+    solve_all(&entries, &Overrides::default(), |entry, result| {
+        let (status, elapsed) = match result {
+            None => {
+                skipped += 1;
+                (String::from("skipped"), String::from("-"))
+            },
+            Some(Ok(Solved { answer, elapsed, .. })) => {
+                let verdict = answers.check(entry.day, entry.part, &answer);
+                match verdict {
+                    Verdict::Pass => passed += 1,
+                    Verdict::Fail { .. } => failed += 1,
+                    Verdict::Missing => missing += 1,
+                }
+                (verdict.to_string(), format!("{:.3?}", elapsed))
+            },
+            Some(Err(e)) => {
+                failed += 1;
+                (e, String::from("-"))
+            },
+        };
+        println!("{:>3}  {:>4}  {:<40}  {:>12}", entry.day, entry.part, status, elapsed);
+    });
+    println!("\n{} passed, {} failed, {} missing, {} skipped", passed, failed, missing, skipped);
+    Ok(failed == 0)
+}
+
+fn gen(
+    day: usize,
+    size: Option<usize>,
+    seed: Option<u64>,
+    config: Option<PathBuf>,
+    sets: Vec<String>,
+) -> Result<(), Box<dyn Error>> {
+    let generator = generate::find(day)
+        .ok_or(format!("no generator for day {}, see gen --list", day))?;
+    let mut overrides = load_overrides(config)?;
+    for assignment in sets.iter() {
+        overrides.set(day, assignment)?;
+    }
+    let seed = seed.unwrap_or_else(rand::random);
+    info!("day {} seed {}", day, seed);
+    print!("{}", generator.generate(size.unwrap_or(generator.default_size), seed, &overrides)?);
+    Ok(())
+}
+
+fn explain_list() {
+    println!("{:>3}  {:>4}  shows", "day", "part");
+    for explainer in explain::all() {
+        println!("{:>3}  {:>4}  {}", explainer.day, explainer.part, explainer.shows);
+    }
+}
+
+fn visualize_list() {
+    println!("{:>3}  {:>4}  shows", "day", "part");
+    for animation in visualize::all() {
+        println!("{:>3}  {:>4}  {}", animation.day, animation.part, animation.shows);
+    }
+}
+
+fn export_list() {
+    println!("{:>3}  {:>4}  shows", "day", "part");
+    for picture in image::all() {
+        println!("{:>3}  {:>4}  {}", picture.day, picture.part, picture.shows);
+    }
+}
+
+fn gen_list() {
+    println!("{:>3}  {:<24}  {:>12}", "day", "size", "default size");
+    for generator in generate::all() {
+        println!("{:>3}  {:<24}  {:>12}", generator.day, generator.size_of, generator.default_size);
+    }
+}
 
 fn main() {
+    let parsed = take_global_flags(env::args().skip(1).collect())
+        .and_then(|(flags, args)| Ok((flags, parse_args(&args)?)));
+    let (flags, command) = match parsed {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        },
+    };
+
+    advent::logging::init(flags.verbosity);
+    if let Some(jobs) = flags.jobs {
+        // only fails if the pool was already built, and nothing has used it yet
+        rayon::ThreadPoolBuilder::new().num_threads(jobs).build_global().unwrap();
+    }
 
+    match command {
+        Command::Run(args) => {
+            if let Err(e) = run(args) {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::RunAll { format, config } => {
+            if let Err(e) = run_all(format, config) {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::Gen { day, size, seed, config, sets } => {
+            if let Err(e) = gen(day, size, seed, config, sets) {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        },
+        Command::GenList => gen_list(),
+        Command::ExplainList => explain_list(),
+        Command::VisualizeList => visualize_list(),
+        Command::ExportList => export_list(),
+        Command::Verify { day, part } => match verify(day, part) {
+            Ok(true) => (),
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            },
+        },
+    }
 }
@@ -0,0 +1,118 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::explain::Explanation;
+use crate::solution::Answer;
+
+// how the runner writes results to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    // one object per solved part, so --all output is JSON Lines
+    Json,
+}
+
+impl Format {
+    pub fn from_arg(arg: &str) -> Result<Self, String> {
+        match arg {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => Err(format!("unknown format: {} (expected text or json)", other)),
+        }
+    }
+}
+
+// a solved part with enough metadata to tell which input produced it.
+// the answer is always a string, `answer_type` says what it was before that
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RunRecord {
+    pub day: usize,
+    pub part: usize,
+    pub answer: String,
+    pub answer_type: &'static str,
+    pub elapsed_ns: u64,
+    pub input_sha256: String,
+}
+
+impl RunRecord {
+    pub fn new(day: usize, part: usize, answer: &Answer, elapsed: Duration, input: &str) -> Self {
+        Self {
+            day,
+            part,
+            answer: answer.to_string(),
+            answer_type: answer.type_name(),
+            elapsed_ns: u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX),
+            input_sha256: sha256_hex(input),
+        }
+    }
+}
+
+// stands in for a RunRecord when a part fails, so a JSON Lines run still has a line per part
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorRecord {
+    pub day: usize,
+    pub part: usize,
+    pub error: String,
+}
+
+// a RunRecord along with what went into the answer, for run --explain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExplainedRecord {
+    #[serde(flatten)]
+    pub record: RunRecord,
+    pub explanation: Explanation,
+}
+
+pub fn to_json<T: Serialize>(record: &T) -> String {
+    // plain structs of strings and numbers, serializing them can't fail
+    serde_json::to_string(record).unwrap()
+}
+
+pub fn sha256_hex(input: &str) -> String {
+    Sha256::digest(input.as_bytes()).iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats() {
+        assert_eq!(Format::from_arg("json"), Ok(Format::Json));
+        assert_eq!(Format::from_arg("text"), Ok(Format::Text));
+        assert!(Format::from_arg("xml").is_err());
+    }
+
+    #[test]
+    fn hashes_input() {
+        assert_eq!(sha256_hex("abc"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+    }
+
+    #[test]
+    fn record_json() {
+        let record = RunRecord::new(17, 1, &Answer::from("4,6,3"), Duration::from_micros(3), "abc");
+        let json: serde_json::Value = serde_json::from_str(&to_json(&record)).unwrap();
+        assert_eq!(json["day"], 17);
+        assert_eq!(json["part"], 1);
+        assert_eq!(json["answer"], "4,6,3");
+        assert_eq!(json["answer_type"], "string");
+        assert_eq!(json["elapsed_ns"], 3000);
+        assert_eq!(json["input_sha256"], sha256_hex("abc"));
+
+        let explained = ExplainedRecord {
+            record,
+            explanation: Explanation { of: "safe reports", lines: vec![String::from("line 1: 1 2 3")] },
+        };
+        let json: serde_json::Value = serde_json::from_str(&to_json(&explained)).unwrap();
+        assert_eq!(json["answer"], "4,6,3");
+        assert_eq!(json["explanation"]["of"], "safe reports");
+        assert_eq!(json["explanation"]["lines"][0], "line 1: 1 2 3");
+
+        let error = ErrorRecord { day: 1, part: 2, error: String::from("panicked") };
+        assert_eq!(to_json(&error), r#"{"day":1,"part":2,"error":"panicked"}"#);
+    }
+}
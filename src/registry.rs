@@ -0,0 +1,155 @@
+use std::error::Error;
+
+use crate::config::Overrides;
+use crate::solution::{Answer, Solution};
+use crate::{
+    day1, day2, day3, day4, day5, day6, day7, day8, day9, day10, day11, day12, day13,
+    day14, day15, day16, day17, day18, day19, day20, day21, day22, day23, day24, day25,
+};
+
+type SolveFn = fn(usize, &str, &Overrides) -> Result<Answer, Box<dyn Error>>;
+
+// parts that take more than 10s on the real input in a release build, or don't finish at all
+// yet. the rest take well under a second. 23-2 keeps its answer in data/answers.toml for when
+// it gets faster, the others are missing from it
+const SLOW: &[(usize, usize)] = &[(13, 2), (14, 2), (19, 2), (23, 2), (24, 2)];
+
+pub struct Entry {
+    pub day: usize,
+    pub part: usize,
+    // relative to the data dir, see common::get_data_path
+    pub input_path: &'static str,
+    // left out of verify and the benches, see SLOW
+    pub slow: bool,
+    solve: SolveFn,
+}
+
+impl Entry {
+    pub fn solve(&self, input: &str, overrides: &Overrides) -> Result<Answer, Box<dyn Error>> {
+        (self.solve)(self.day, input, overrides)
+    }
+}
+
+fn solve_part1<S: Solution>(day: usize, input: &str, overrides: &Overrides) -> Result<Answer, Box<dyn Error>> {
+    let config = overrides.for_day::<S::Config>(day)?;
+    S::part1(&S::parse(input)?, &config)
+}
+
+fn solve_part2<S: Solution>(day: usize, input: &str, overrides: &Overrides) -> Result<Answer, Box<dyn Error>> {
+    let config = overrides.for_day::<S::Config>(day)?;
+    S::part2(&S::parse(input)?, &config)
+}
+
+fn entries<S: Solution>(day: usize, input_path: &'static str) -> [Entry; 2] {
+    [
+        Entry { day, part: 1, input_path, slow: SLOW.contains(&(day, 1)), solve: solve_part1::<S> },
+        Entry { day, part: 2, input_path, slow: SLOW.contains(&(day, 2)), solve: solve_part2::<S> },
+    ]
+}
+
+pub fn all() -> Vec<Entry> {
+    [
+        entries::<day1::Day1>(1, day1::constants::LOCATIONS_FILE_PATH),
+        entries::<day2::Day2>(2, day2::constants::REPORT_FILE_PATH),
+        entries::<day3::Day3>(3, day3::constants::MEMORY_FILE_PATH),
+        entries::<day4::Day4>(4, day4::constants::WORD_SEARCH_PATH),
+        entries::<day5::Day5>(5, day5::constants::PRINTER_UPDATES),
+        entries::<day6::Day6>(6, day6::constants::INPUT_PATH),
+        entries::<day7::Day7>(7, day7::constants::INPUT_PATH),
+        entries::<day8::Day8>(8, day8::constants::INPUT_PATH),
+        entries::<day9::Day9>(9, day9::constants::INPUT_PATH),
+        entries::<day10::Day10>(10, day10::constants::INPUT_PATH),
+        entries::<day11::Day11>(11, day11::constants::INPUT_PATH),
+        entries::<day12::Day12>(12, day12::constants::INPUT_PATH),
+        entries::<day13::Day13>(13, day13::constants::INPUT_PATH),
+        entries::<day14::Day14>(14, day14::constants::INPUT_PATH),
+        entries::<day15::Day15>(15, day15::constants::INPUT_PATH),
+        entries::<day16::Day16>(16, day16::constants::INPUT_PATH),
+        entries::<day17::Day17>(17, day17::constants::INPUT_PATH),
+        entries::<day18::Day18>(18, day18::constants::INPUT_PATH),
+        entries::<day19::Day19>(19, day19::constants::INPUT_PATH),
+        entries::<day20::Day20>(20, day20::constants::INPUT_PATH),
+        entries::<day21::Day21>(21, day21::constants::INPUT_PATH),
+        entries::<day22::Day22>(22, day22::constants::INPUT_PATH),
+        entries::<day23::Day23>(23, day23::constants::INPUT_PATH),
+        entries::<day24::Day24>(24, day24::constants::INPUT_PATH),
+        entries::<day25::Day25>(25, day25::constants::INPUT_PATH),
+    ]
+    .into_iter()
+    .flatten()
+    // day 25 only has the one puzzle
+    .filter(|e| (e.day, e.part) != (25, 2))
+    .collect()
+}
+
+pub fn find(day: usize, part: usize) -> Option<Entry> {
+    all().into_iter().find(|e| e.day == day && e.part == part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::common::{get_data_path, get_test_data_path};
+    use crate::error::ParseError;
+
+    #[test]
+    fn every_day_has_two_parts() {
+        for day in 1..=24 {
+            for part in 1..=2 {
+                assert!(find(day, part).is_some(), "missing day {} part {}", day, part);
+            }
+        }
+        assert!(find(25, 1).is_some());
+        assert!(find(25, 2).is_none());
+        assert_eq!(all().len(), 49);
+    }
+
+    #[test]
+    fn slow_parts() {
+        assert!(find(14, 2).unwrap().slow);
+        assert!(find(23, 2).unwrap().slow);
+        assert!(!find(14, 1).unwrap().slow);
+    }
+
+    #[test]
+    fn input_paths_resolve() {
+        for entry in all() {
+            assert!(get_data_path(entry.input_path).is_ok(), "bad path for day {}", entry.day);
+        }
+    }
+
+    #[test]
+    fn dispatches_to_solution() {
+        let entry = find(1, 1).unwrap();
+        let input = fs::read_to_string(get_test_data_path("day1/case1.txt").unwrap()).unwrap();
+        assert_eq!(entry.solve(&input, &Overrides::default()).unwrap(), Answer::Int(116));
+    }
+
+    #[test]
+    fn string_answers() {
+        let entry = find(23, 2).unwrap();
+        let input = fs::read_to_string(get_test_data_path("day23/case1.txt").unwrap()).unwrap();
+        assert_eq!(entry.solve(&input, &Overrides::default()).unwrap(), Answer::from("co,de,ka,ta"));
+    }
+
+    #[test]
+    fn parse_errors_are_returned() {
+        let entry = find(1, 2).unwrap();
+        let err = entry.solve("3   4\nfour   3\n", &Overrides::default()).unwrap_err();
+        let err = err.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(err.day, 1);
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn overrides_reach_the_day() {
+        let entry = find(18, 1).unwrap();
+        let input = fs::read_to_string(get_test_data_path("day18/case1.txt").unwrap()).unwrap();
+        let overrides = Overrides::from("[day18]\nx_max = 6\ny_max = 6\nbytes_fallen = 12\n").unwrap();
+        assert_eq!(entry.solve(&input, &overrides).unwrap(), Answer::Int(22));
+
+        let overrides = Overrides::from("[day18]\nwidth = 6\n").unwrap();
+        assert!(entry.solve(&input, &overrides).is_err());
+    }
+}
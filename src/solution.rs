@@ -0,0 +1,126 @@
+use std::error::Error;
+use std::fmt;
+
+use num_bigint::BigInt;
+use serde::de::DeserializeOwned;
+
+use crate::error::ParseError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    BigInt(BigInt),
+    Str(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(i) => write!(f, "{}", i),
+            Answer::BigInt(i) => write!(f, "{}", i),
+            Answer::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl Answer {
+    // how the answer is stored, for consumers that can't tell "123" the number from "123" the string
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Answer::Int(_) => "int",
+            Answer::BigInt(_) => "bigint",
+            Answer::Str(_) => "string",
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(i: i64) -> Self {
+        Answer::Int(i)
+    }
+}
+
+impl From<isize> for Answer {
+    fn from(i: isize) -> Self {
+        Answer::Int(i as i64)
+    }
+}
+
+// anything that doesn't fit in an i64 gets promoted to a BigInt
+impl From<u64> for Answer {
+    fn from(u: u64) -> Self {
+        match i64::try_from(u) {
+            Ok(i) => Answer::Int(i),
+            Err(_) => Answer::BigInt(BigInt::from(u)),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(u: usize) -> Self {
+        Answer::from(u as u64)
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(u: u128) -> Self {
+        match i64::try_from(u) {
+            Ok(i) => Answer::Int(i),
+            Err(_) => Answer::BigInt(BigInt::from(u)),
+        }
+    }
+}
+
+impl From<BigInt> for Answer {
+    fn from(i: BigInt) -> Self {
+        Answer::BigInt(i)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+// every day implements this so that runners, benches etc. can treat them uniformly. a part
+// fails when its config or input leaves it without an answer, rather than panicking
+pub trait Solution {
+    type Input;
+    // the puzzle's parameters, see config. the default is the real puzzle, examples override it
+    type Config: Default + DeserializeOwned;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input, config: &Self::Config) -> Result<Answer, Box<dyn Error>>;
+    fn part2(input: &Self::Input, config: &Self::Config) -> Result<Answer, Box<dyn Error>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_ints_stay_ints() {
+        assert_eq!(Answer::from(42usize), Answer::Int(42));
+        assert_eq!(Answer::from(42u128), Answer::Int(42));
+        assert_eq!(Answer::from(-3isize), Answer::Int(-3));
+    }
+
+    #[test]
+    fn large_ints_are_promoted() {
+        let big = u128::from(u64::MAX) + 1;
+        assert_eq!(Answer::from(big), Answer::BigInt(BigInt::from(big)));
+        assert_eq!(Answer::from(big).to_string(), "18446744073709551616");
+    }
+
+    #[test]
+    fn strings_display_as_is() {
+        assert_eq!(Answer::from("6,1").to_string(), "6,1");
+    }
+}
@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::error::ParseError;
+
+// reading a day's input a line at a time instead of all at once, so days whose records each sit
+// on their own line can start on them straight away and don't need the whole file in memory
+
+// a stream can fail reading as well as parsing
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "could not read input: {}", e),
+            StreamError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for StreamError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StreamError::Io(e) => Some(e),
+            StreamError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for StreamError {
+    fn from(e: io::Error) -> Self {
+        StreamError::Io(e)
+    }
+}
+
+impl From<ParseError> for StreamError {
+    fn from(e: ParseError) -> Self {
+        StreamError::Parse(e)
+    }
+}
+
+pub fn open(path: impl AsRef<Path>) -> Result<BufReader<File>, Box<dyn Error>> {
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    Ok(BufReader::new(file))
+}
+
+// parses each line as it's read, see records
+pub struct Records<R, F> {
+    reader: R,
+    parse: F,
+    line: String,
+    line_idx: usize,
+    failed: bool,
+}
+
+// parse gets each line with its 0-based index, like lines().enumerate() gives, and without the line
+// ending. the first error ends the stream
+pub fn records<R, T, F>(reader: R, parse: F) -> Records<R, F>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> Result<T, ParseError>,
+{
+    Records { reader, parse, line: String::new(), line_idx: 0, failed: false }
+}
+
+impl<R, T, F> Iterator for Records<R, F>
+where
+    R: BufRead,
+    F: FnMut(usize, &str) -> Result<T, ParseError>,
+{
+    type Item = Result<T, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        self.line.clear();
+        let record = match self.reader.read_line(&mut self.line) {
+            Ok(0) => return None,
+            Ok(_) => {
+                let line = self.line.strip_suffix('\n').unwrap_or(&self.line);
+                let line = line.strip_suffix('\r').unwrap_or(line);
+                (self.parse)(self.line_idx, line).map_err(StreamError::from)
+            },
+            Err(e) => Err(StreamError::from(e)),
+        };
+        self.line_idx += 1;
+        self.failed = record.is_err();
+        Some(record)
+    }
+}
+
+// up to size records at a time, for days that spread each batch across threads
+pub fn batches<I, T>(mut records: I, size: usize) -> impl Iterator<Item = Result<Vec<T>, StreamError>>
+where
+    I: Iterator<Item = Result<T, StreamError>>,
+{
+    std::iter::from_fn(move || {
+        let batch: Result<Vec<T>, StreamError> = records.by_ref().take(size).collect();
+        match batch {
+            Ok(batch) if batch.is_empty() => None,
+            batch => Some(batch),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{parse_num, ParseErrorKind};
+
+    fn numbers(input: &str) -> impl Iterator<Item = Result<u64, StreamError>> + '_ {
+        records(input.as_bytes(), |line_idx, line| parse_num(0, line_idx, line, line))
+    }
+
+    // hands out its input, then fails
+    struct Broken<'a>(&'a [u8]);
+
+    impl io::Read for Broken<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("disk on fire"));
+            }
+            let n = self.0.len().min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn reads_records() {
+        let parsed: Vec<u64> = numbers("1\n22\r\n333").map(Result::unwrap).collect();
+        assert_eq!(parsed, vec![1, 22, 333]);
+        assert_eq!(numbers("").count(), 0);
+    }
+
+    #[test]
+    fn stops_at_the_first_error() {
+        let parsed: Vec<Result<u64, StreamError>> = numbers("1\nx\n3\n").collect();
+        assert_eq!(parsed.len(), 2);
+        match &parsed[1] {
+            Err(StreamError::Parse(e)) => {
+                assert_eq!(e.kind, ParseErrorKind::InvalidNumber);
+                assert_eq!(e.line, 2);
+            },
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+
+        let reader = BufReader::new(Broken(b"1\n2\n"));
+        let parsed: Vec<Result<u64, StreamError>> = records(reader, |i, line| parse_num(0, i, line, line)).collect();
+        assert_eq!(parsed.len(), 3);
+        assert!(matches!(parsed[2], Err(StreamError::Io(_))));
+    }
+
+    #[test]
+    fn batches_records() {
+        let sizes: Vec<usize> = batches(numbers("1\n2\n3\n4\n5\n"), 2).map(|b| b.unwrap().len()).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        let mut failing = batches(numbers("1\n2\nx\n4\n"), 2);
+        assert!(failing.next().unwrap().is_ok());
+        assert!(failing.next().unwrap().is_err());
+        assert!(failing.next().is_none());
+    }
+}
@@ -0,0 +1,315 @@
+use std::error::Error;
+use std::io::{self, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use termion::clear;
+use termion::cursor::{Goto, HideCursor};
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+
+use crate::config::Overrides;
+use crate::solution::Solution;
+use crate::{day6, day9, day14, day15, day16};
+
+// the simulation days, drawn a step at a time with the renderers they already had for debugging.
+// a frame is whatever the day prints for one step, the player only adds a status line under it
+
+pub type Frames = Box<dyn Iterator<Item = String>>;
+
+type FramesFn = fn(&str, &Overrides) -> Result<Frames, Box<dyn Error>>;
+
+pub struct Animation {
+    pub day: usize,
+    pub part: usize,
+    // what the frames are of, for the usage text
+    pub shows: &'static str,
+    frames: FramesFn,
+}
+
+impl Animation {
+    pub fn frames(&self, input: &str, overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+        (self.frames)(input, overrides)
+    }
+}
+
+pub fn all() -> Vec<Animation> {
+    vec![
+        Animation { day: 6, part: 1, shows: "the guard walking off the map", frames: day6_walk },
+        Animation { day: 9, part: 1, shows: "the disk compressed a block at a time", frames: day9_part1 },
+        Animation { day: 9, part: 2, shows: "the disk compressed a file at a time", frames: day9_part2 },
+        Animation { day: 14, part: 1, shows: "the robots for the first `seconds`", frames: day14_part1 },
+        Animation { day: 14, part: 2, shows: "the robots, second after second", frames: day14_part2 },
+        Animation { day: 15, part: 1, shows: "the robot pushing boxes", frames: day15_part1 },
+        Animation { day: 15, part: 2, shows: "the robot pushing wide boxes", frames: day15_part2 },
+        Animation { day: 16, part: 1, shows: "the deer walking a best path", frames: day16_walk },
+    ]
+}
+
+pub fn find(day: usize, part: usize) -> Option<Animation> {
+    all().into_iter().find(|a| a.day == day && a.part == part)
+}
+
+fn day6_walk(input: &str, _overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    Ok(Box::new(day6::walk_frames(day6::Day6::parse(input)?)))
+}
+
+fn day9_part1(input: &str, _overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    Ok(Box::new(day9::compress_frames(&day9::Day9::parse(input)?)))
+}
+
+fn day9_part2(input: &str, _overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    Ok(Box::new(day9::compress_no_frag_frames(&day9::Day9::parse(input)?)))
+}
+
+fn day14_part1(input: &str, overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    let config = overrides.for_day::<day14::Config>(14)?;
+    let seconds = config.seconds;
+    Ok(Box::new(day14::lobby_frames(day14::Day14::parse(input)?, &config).take(seconds + 1)))
+}
+
+fn day14_part2(input: &str, overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    let config = overrides.for_day::<day14::Config>(14)?;
+    Ok(Box::new(day14::lobby_frames(day14::Day14::parse(input)?, &config)))
+}
+
+fn day15_part1(input: &str, _overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    let (wh, _, moves) = day15::Day15::parse(input)?;
+    Ok(Box::new(day15::push_frames(wh, moves)))
+}
+
+fn day15_part2(input: &str, _overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    let (_, wide_wh, moves) = day15::Day15::parse(input)?;
+    Ok(Box::new(day15::push_frames(wide_wh, moves)))
+}
+
+fn day16_walk(input: &str, overrides: &Overrides) -> Result<Frames, Box<dyn Error>> {
+    let config = overrides.for_day::<day16::Config>(16)?;
+    Ok(Box::new(day16::walk_frames(day16::Day16::parse(input)?, &config)))
+}
+
+// which frames to show, counting from 0 like a Range
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameRange {
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+impl FrameRange {
+    // A..B, A.., ..B or a single frame N
+    pub fn from_arg(arg: &str) -> Result<Self, String> {
+        let bound = |s: &str| s.parse::<usize>().map_err(|_| format!("invalid frame range: {}", arg));
+        let range = match arg.split_once("..") {
+            Some((start, end)) => FrameRange {
+                start: if start.is_empty() { 0 } else { bound(start)? },
+                end: if end.is_empty() { None } else { Some(bound(end)?) },
+            },
+            None => {
+                let frame = bound(arg)?;
+                FrameRange { start: frame, end: Some(frame + 1) }
+            },
+        };
+        if range.end.is_some_and(|end| end <= range.start) {
+            return Err(format!("empty frame range: {}", arg));
+        }
+        Ok(range)
+    }
+
+    // the frames in the range, along with their numbers
+    pub fn select<T, I: Iterator<Item = T>>(&self, frames: I) -> impl Iterator<Item = (usize, T)> {
+        let end = self.end.unwrap_or(usize::MAX);
+        frames.enumerate().skip(self.start).take(end - self.start)
+    }
+}
+
+// how far + and - can take the speed. much slower and a frame could outlast the terminal,
+// much faster and the frame time is too short to mean anything
+const MIN_FPS: f64 = 0.5;
+const MAX_FPS: f64 = 240.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Playback {
+    pub fps: f64,
+    pub range: FrameRange,
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self { fps: 10.0, range: FrameRange::default() }
+    }
+}
+
+// animates the frames in place when stdout is a terminal. otherwise there's nobody to watch,
+// so every frame is written out straight away, separated by blank lines
+pub fn play(frames: Frames, playback: &Playback) -> io::Result<()> {
+    let frames = playback.range.select(frames);
+    if !termion::is_tty(&io::stdout()) {
+        return write_frames(&mut io::stdout().lock(), frames);
+    }
+
+    // keys come from the terminal itself rather than stdin, which may be the puzzle input
+    let tty = termion::get_tty()?;
+    let (tx, keys) = mpsc::channel();
+    thread::spawn(move || {
+        for key in tty.keys() {
+            if tx.send(key).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut screen = HideCursor::from(io::stdout().into_raw_mode()?);
+    write!(screen, "{}", clear::All)?;
+    let result = animate(&mut screen, frames, &keys, playback.fps);
+    writeln!(screen, "\r")?;
+    result
+}
+
+fn write_frames<W: Write>(out: &mut W, frames: impl Iterator<Item = (usize, String)>) -> io::Result<()> {
+    for (i, (_, frame)) in frames.enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        write!(out, "{}", frame)?;
+        if !frame.ends_with('\n') {
+            writeln!(out)?;
+        }
+    }
+    Ok(())
+}
+
+fn animate<W: Write>(
+    screen: &mut W,
+    mut frames: impl Iterator<Item = (usize, String)>,
+    keys: &Receiver<io::Result<Key>>,
+    mut fps: f64,
+) -> io::Result<()> {
+    let Some(mut current) = frames.next() else {
+        return Ok(());
+    };
+    let mut paused = false;
+    fps = fps.clamp(MIN_FPS, MAX_FPS);
+    let mut due = Instant::now() + Duration::from_secs_f64(1.0 / fps);
+    loop {
+        draw(screen, &current, fps, paused)?;
+        let key = match paused {
+            true => keys.recv().map_err(|_| RecvTimeoutError::Disconnected),
+            false => keys.recv_timeout(due.saturating_duration_since(Instant::now())),
+        };
+        let advance = match key {
+            Ok(key) => match key? {
+                Key::Char(' ') => {
+                    paused = !paused;
+                    false
+                },
+                Key::Char('n') | Key::Right => {
+                    paused = true;
+                    true
+                },
+                Key::Char('+') => {
+                    fps = (fps * 2.0).min(MAX_FPS);
+                    false
+                },
+                Key::Char('-') => {
+                    fps = (fps / 2.0).max(MIN_FPS);
+                    false
+                },
+                Key::Char('q') | Key::Esc | Key::Ctrl('c') => return Ok(()),
+                _ => false,
+            },
+            Err(RecvTimeoutError::Timeout) => true,
+            // the terminal went away, so nothing can unpause it or stop it early
+            Err(RecvTimeoutError::Disconnected) => {
+                paused = false;
+                thread::sleep(due.saturating_duration_since(Instant::now()));
+                true
+            },
+        };
+        if advance {
+            match frames.next() {
+                Some(next) => current = next,
+                None => return Ok(()),
+            }
+            due = Instant::now() + Duration::from_secs_f64(1.0 / fps);
+        }
+    }
+}
+
+// redraws over the last frame line by line, which flickers less than clearing the screen
+fn draw<W: Write>(screen: &mut W, (i, frame): &(usize, String), fps: f64, paused: bool) -> io::Result<()> {
+    write!(screen, "{}", Goto(1, 1))?;
+    for line in frame.lines() {
+        write!(screen, "{}{}\r\n", line, clear::UntilNewline)?;
+    }
+    let state = if paused { "paused" } else { "playing" };
+    write!(
+        screen,
+        "frame {}  {} at {} fps  (space pause, n step, + - speed, q quit){}",
+        i, state, fps, clear::AfterCursor,
+    )?;
+    screen.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn numbered(n: usize) -> Frames {
+        Box::new((0..n).map(|i| format!("frame {}\n", i)))
+    }
+
+    #[test]
+    fn frame_ranges() {
+        assert_eq!(FrameRange::from_arg("3..7"), Ok(FrameRange { start: 3, end: Some(7) }));
+        assert_eq!(FrameRange::from_arg("3.."), Ok(FrameRange { start: 3, end: None }));
+        assert_eq!(FrameRange::from_arg("..7"), Ok(FrameRange { start: 0, end: Some(7) }));
+        assert_eq!(FrameRange::from_arg("5"), Ok(FrameRange { start: 5, end: Some(6) }));
+        assert!(FrameRange::from_arg("7..3").is_err());
+        assert!(FrameRange::from_arg("a..3").is_err());
+        assert!(FrameRange::from_arg("").is_err());
+    }
+
+    #[test]
+    fn selects_frames() {
+        let range = FrameRange::from_arg("2..4").unwrap();
+        let selected: Vec<usize> = range.select(numbered(10)).map(|(i, _)| i).collect();
+        assert_eq!(selected, vec![2, 3]);
+        assert_eq!(FrameRange::from_arg("8..").unwrap().select(numbered(10)).count(), 2);
+        assert_eq!(FrameRange::default().select(numbered(10)).count(), 10);
+    }
+
+    #[test]
+    fn writes_frames_without_a_terminal() {
+        let mut out = Vec::new();
+        write_frames(&mut out, FrameRange::from_arg("1..3").unwrap().select(numbered(5))).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "frame 1\n\nframe 2\n");
+    }
+
+    #[test]
+    fn speed_stays_in_range() {
+        for (key, fps) in [('+', MAX_FPS), ('-', MIN_FPS)] {
+            let (tx, keys) = mpsc::channel();
+            for _ in 0..2000 {
+                tx.send(Ok(Key::Char(key))).unwrap();
+            }
+            tx.send(Ok(Key::Char('q'))).unwrap();
+            let mut screen = Vec::new();
+            animate(&mut screen, FrameRange::default().select(numbered(3)), &keys, 10.0).unwrap();
+            assert!(String::from_utf8(screen).unwrap().contains(&format!("at {} fps", fps)));
+        }
+    }
+
+    #[test]
+    fn animations_are_of_solutions() {
+        for animation in all() {
+            assert!(registry::find(animation.day, animation.part).is_some());
+        }
+        assert!(find(15, 2).is_some());
+        assert!(find(15, 3).is_none());
+        assert!(find(9, 2).is_some());
+        assert!(find(16, 1).is_some());
+    }
+}